@@ -30,6 +30,11 @@ pub fn try_canonicalize(path: impl Into<PathBuf>, or_fail: bool) -> crate::Res<P
     }
 }
 
+/// True if `stdout` is a terminal.
+pub fn stdout_is_tty() -> bool {
+    atty::is(atty::Stream::Stdout)
+}
+
 /// Reads a line from `stdin`.
 pub fn read_line() -> crate::Res<String> {
     let stdin = std::io::stdin();
@@ -140,6 +145,13 @@ pub fn download(url: impl AsRef<str>) -> crate::Res<reqwest::blocking::Response>
     reqwest::blocking::get(url).with_context(|| anyhow!("downloading `{}`", url))
 }
 
+/// SHA-256 hash of some bytes, as a lowercase hexadecimal string.
+pub fn sha256(bytes: impl AsRef<[u8]>) -> String {
+    use sha2::Digest;
+    let hash = sha2::Sha256::digest(bytes.as_ref());
+    hash.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
 /// Retrieves the file extension of a file path.
 pub fn file_ext(path: impl AsRef<Path>) -> Option<std::ffi::OsString> {
     let path = path.as_ref();
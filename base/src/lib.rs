@@ -208,6 +208,31 @@ pub fn unescape_string(s: impl AsRef<str>) -> String {
     res
 }
 
+/// Escapes a string for HTML text and attributes.
+///
+/// ```rust
+/// # use base::html_escape;
+/// assert_eq!(
+///     html_escape(r#"<a href="x">Tom & 'Jerry'</a>"#),
+///     "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;",
+/// );
+/// ```
+pub fn html_escape(s: impl AsRef<str>) -> String {
+    let s = s.as_ref();
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            '\'' => res.push_str("&#39;"),
+            c => res.push(c),
+        }
+    }
+    res
+}
+
 // /// Creates a prelude.
 // ///
 // /// Takes a list of tokens corresponding to some items for the `prelude` module to create.
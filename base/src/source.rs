@@ -4,7 +4,7 @@ use crate::*;
 
 /// A position in a file.
 #[readonly]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
 pub struct Pos {
     /// Line (1-indexed).
     pub row: usize,
@@ -200,7 +200,7 @@ implem! {
 }
 
 /// A position in a file.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct FilePos {
     pub file: String,
     pub pos: Pos,
@@ -238,7 +238,7 @@ implem! {
 }
 
 /// A span in a file.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct FileSpan {
     pub start: FilePos,
     pub end: Pos,
@@ -291,7 +291,7 @@ implem! {
 
 /// A line-span in a file.
 #[readonly]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct LineSpan {
     /// File path.
     pub path: io::PathBuf,
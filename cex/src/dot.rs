@@ -0,0 +1,142 @@
+//! Graphviz (DOT) cex rendering.
+//!
+//! Each state is a node labelled with its index and the variables that changed since the previous
+//! state, all variables for the initial state. Edges between consecutive states are labelled with
+//! the action of the target state. The lasso of [`Shape::Loop`](crate::Shape::Loop) and
+//! [`Shape::Stuttering`](crate::Shape::Stuttering) cexs is an explicit dashed edge.
+//!
+//! Hidden variables of the [`conf::CexView`] of the [`pretty::Spec`] used are ignored.
+
+prelude!();
+
+/// Escapes a string for a DOT string literal, left-justifying lines.
+fn escape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\l"),
+            c => res.push(c),
+        }
+    }
+    res
+}
+
+/// Renders a cex as a DOT graph.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cex::{dot, pretty::Spec, Cex, Shape, State, StateInfo, Value};
+/// # use base::{source::Pos, Int};
+/// let mut cex = Cex::new();
+/// for cnt in 0..3 {
+///     let info = if cnt > 0 {
+///         Some(StateInfo::new("inc", (Pos::new(14, 1), Pos::new(16, 10)), "sw"))
+///     } else {
+///         None
+///     };
+///     let mut state = State::new(info);
+///     let _ = state.insert("cnt".into(), Value::from(Int::from(cnt)));
+///     let _ = state.insert("name".into(), Value::from("sw"));
+///     let _ = cex.push(state);
+/// }
+/// let cex = cex.shape(Shape::Loop(cex.idx_of(1).unwrap()));
+/// assert_eq!(
+///     dot::render(&Spec::default(), &cex),
+///     r#"digraph cex {
+///     node [shape=box, fontname="monospace"];
+///     s0 [label="0: initial state\lcnt = 0\lname = \"sw\"\l"];
+///     s1 [label="1\lcnt = 1\l"];
+///     s2 [label="2\lcnt = 2\l"];
+///     s0 -> s1 [label="inc"];
+///     s1 -> s2 [label="inc"];
+///     s2 -> s1 [label="loops back", style=dashed];
+/// }
+/// "#
+/// );
+/// ```
+pub fn render(spec: &pretty::Spec, cex: &Cex) -> String {
+    use fmt::Write;
+
+    let spec = spec.unstyled();
+    let mut res = String::with_capacity(666);
+    res.push_str("digraph cex {\n");
+    let title = cex
+        .falsified
+        .as_ref()
+        .map(|falsified| format!("counterexample for {}", falsified));
+    let label: Vec<String> = title.into_iter().chain(cex.alias_desc()).collect();
+    if !label.is_empty() {
+        writeln!(
+            res,
+            "    label=\"{}\";\n    labelloc=t;",
+            escape(&label.join(", ")),
+        )
+        .unwrap();
+    }
+    res.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+
+    let mut prev: Option<&State> = None;
+    for (idx, state) in cex.states.index_iter() {
+        let mut label = match prev {
+            None => format!("{}: initial state\n", idx),
+            Some(_) => format!("{}\n", idx),
+        };
+        let mut unchanged = true;
+        for (var, value) in state.values.iter() {
+            let changed = prev.map_or(true, |prev| state.changed_since(prev, var));
+            if changed && spec.view.is_visible(var) {
+                unchanged = false;
+                writeln!(label, "{} = {}", var, spec.var_value_to_string(var, value)).unwrap();
+            }
+        }
+        if unchanged && prev.is_some() {
+            label.push_str("(no change)\n");
+        }
+        writeln!(res, "    s{} [label=\"{}\"];", idx, escape(&label)).unwrap();
+        prev = Some(state);
+    }
+
+    let mut prev = None;
+    for (idx, state) in cex.states.index_iter() {
+        if let Some(prev) = prev {
+            let action = state
+                .info
+                .as_ref()
+                .map(|info| info.action.as_str())
+                .unwrap_or("");
+            writeln!(
+                res,
+                "    s{} -> s{} [label=\"{}\"];",
+                prev,
+                idx,
+                escape(action)
+            )
+            .unwrap();
+        }
+        prev = Some(idx);
+    }
+
+    if let Some(last) = prev {
+        match cex.shape {
+            crate::Shape::Finite => (),
+            crate::Shape::Stuttering => writeln!(
+                res,
+                "    s{0} -> s{0} [label=\"stuttering\", style=dashed];",
+                last,
+            )
+            .unwrap(),
+            crate::Shape::Loop(target) => writeln!(
+                res,
+                "    s{} -> s{} [label=\"loops back\", style=dashed];",
+                last, target,
+            )
+            .unwrap(),
+        }
+    }
+
+    res.push_str("}\n");
+    res
+}
@@ -0,0 +1,172 @@
+//! Standalone HTML cex rendering.
+//!
+//! The page shows a table with one row per state and one column per state variable, in the spirit
+//! of [`table`]. Values that do not fit on one line are collapsible, values that changed since
+//! the previous state are highlighted, each state links to the source of its action, and the
+//! state the trace loops back to is marked.
+
+prelude!();
+
+/// Renders a cex as a standalone HTML page.
+///
+/// Action source links point to `<source_dir>/<module>.tla` if `source_dir` is given, to
+/// `<module>.tla` (relative to the page) otherwise.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cex::{html, pretty::Spec, Cex, Shape, State, Value};
+/// # use base::Int;
+/// let mut cex = Cex::new();
+/// for cnt in 0..2 {
+///     let mut state = State::new(None);
+///     let _ = state.insert("cnt".into(), Value::from(Int::from(cnt)));
+///     let _ = state.insert("reset".into(), Value::from(false));
+///     let _ = cex.push(state);
+/// }
+/// let cex = cex.shape(Shape::Stuttering);
+/// let page = html::render(&Spec::default(), &cex, None);
+/// assert!(page.starts_with("<!DOCTYPE html>"));
+/// assert!(page.contains("<td class=\"changed\"><code>1</code></td>"));
+/// assert!(page.contains("<td><code>false</code></td>"));
+/// assert!(page.contains("<tr class=\"loop-target\">"));
+/// ```
+pub fn render(spec: &pretty::Spec, cex: &Cex, source_dir: Option<&io::Path>) -> String {
+    let spec = spec.unstyled();
+    let title = match cex.falsified.as_ref() {
+        Some(falsified) => format!("counterexample for {}", falsified),
+        None => "counterexample".into(),
+    };
+    let title = html_escape(title);
+    let mut res = String::with_capacity(4096);
+    res.push_str(&format!(
+        "\
+<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: auto; padding: 1em; }}
+code, pre, .idx {{ font-family: monospace; }}
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid #ddd; padding: .2em .5em; text-align: left; vertical-align: top; }}
+pre {{ margin: 0; }}
+summary {{ cursor: pointer; }}
+.idx {{ color: gray; text-align: right; }}
+.changed {{ background-color: #ffd; }}
+.loop-target {{ border-left: 3px solid #c00; }}
+.loop {{ color: #c00; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+",
+        title = title,
+    ));
+    if let Some(desc) = cex.alias_desc() {
+        res.push_str(&format!("<p>{}</p>\n", html_escape(desc)));
+    }
+    res.push_str("<table>\n<tr><th>#</th><th>action</th>");
+
+    let vars = spec.visible_vars(cex);
+    for var in vars.iter() {
+        res.push_str(&format!("<th><code>{}</code></th>", html_escape(var)));
+    }
+    res.push_str("</tr>\n");
+
+    let loops_to = cex.loops_to();
+    let mut prev: Option<&State> = None;
+    for (idx, state) in cex.states.index_iter() {
+        if loops_to == Some(idx) {
+            res.push_str("<tr class=\"loop-target\">");
+            res.push_str(&format!(
+                "<td class=\"idx\"><span class=\"loop\" title=\"the trace loops back to this state\">\
+                {}</span> {}</td>",
+                table::LOOP_MARKER,
+                idx,
+            ));
+        } else {
+            res.push_str("<tr>");
+            res.push_str(&format!("<td class=\"idx\">{}</td>", idx));
+        }
+
+        match state.info.as_ref() {
+            Some(info) => {
+                let file = format!("{}.tla", info.module);
+                let href = match source_dir {
+                    Some(dir) => format!("file://{}", dir.join(&file).display()),
+                    None => file.clone(),
+                };
+                res.push_str(&format!(
+                    "<td><code>{}</code> @ <a href=\"{}\">{}:{}</a></td>",
+                    html_escape(&info.action),
+                    html_escape(href),
+                    html_escape(file),
+                    info.span.0,
+                ));
+            }
+            None => res.push_str("<td><i>initial state</i></td>"),
+        }
+
+        for var in vars.iter() {
+            let changed = prev.map_or(false, |prev| state.changed_since(prev, var));
+            let value = match state.values.get(*var) {
+                Some(value) if changed || prev.is_none() || !spec.view.changed_only => value,
+                _ => {
+                    res.push_str("<td></td>");
+                    continue;
+                }
+            };
+            res.push_str(if changed {
+                "<td class=\"changed\">"
+            } else {
+                "<td>"
+            });
+            let mut ml = String::with_capacity(17);
+            spec.var_value_to_ml_string(var, value, &mut ml);
+            if ml.contains('\n') {
+                res.push_str(&format!(
+                    "<details><summary><code>{}</code></summary><pre>{}</pre></details>",
+                    html_escape(abbrev(&spec.var_value_to_string(var, value))),
+                    html_escape(ml),
+                ));
+            } else {
+                res.push_str(&format!("<code>{}</code>", html_escape(ml)));
+            }
+            res.push_str("</td>");
+        }
+
+        res.push_str("</tr>\n");
+        prev = Some(state);
+    }
+    res.push_str("</table>\n");
+
+    match cex.shape {
+        crate::Shape::Finite => (),
+        crate::Shape::Stuttering => res.push_str(&format!(
+            "<p><span class=\"loop\">{}</span> the trace stutters on its last state</p>\n",
+            table::LOOP_MARKER,
+        )),
+        crate::Shape::Loop(idx) => res.push_str(&format!(
+            "<p><span class=\"loop\">{}</span> the trace loops back to state {}</p>\n",
+            table::LOOP_MARKER,
+            idx,
+        )),
+    }
+
+    res.push_str("</body>\n</html>\n");
+    res
+}
+
+/// Abbreviates a single-line value for the summary of collapsible values.
+fn abbrev(s: &str) -> String {
+    const MAX_LEN: usize = 40;
+    if s.chars().count() <= MAX_LEN {
+        s.into()
+    } else {
+        let mut res: String = s.chars().take(MAX_LEN).collect();
+        res.push_str(" …");
+        res
+    }
+}
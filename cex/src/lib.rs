@@ -8,7 +8,7 @@ pub mod prelude {
     pub use conf;
 
     pub use crate::{
-        idx, pretty,
+        dot, html, idx, pretty, table,
         value::{self, Value},
         Cex, State, StateInfo,
     };
@@ -32,7 +32,10 @@ pub mod idx {
     }
 }
 
+pub mod dot;
+pub mod html;
 pub mod pretty;
+pub mod table;
 pub mod value;
 
 pub use value::Value;
@@ -40,7 +43,7 @@ pub use value::Value;
 prelude!();
 
 /// Some state info.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct StateInfo {
     /// Action name.
     pub action: String,
@@ -65,7 +68,7 @@ impl StateInfo {
 }
 
 /// A state in a counterexample.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct State {
     /// State info, none if the state is initial.
     pub info: Option<StateInfo>,
@@ -88,6 +91,12 @@ impl State {
             values: Map::new(),
         }
     }
+
+    /// True if the value of a variable is different in `prev`.
+    pub fn changed_since(&self, prev: &State, var: impl AsRef<str>) -> bool {
+        let var = var.as_ref();
+        self.values.get(var) != prev.values.get(var)
+    }
 }
 
 /// Shape of a counterexample.
@@ -106,6 +115,21 @@ pub enum Shape {
     /// For property (liveness property) counterexamples.
     Loop(idx::State),
 }
+impl serde::Serialize for Shape {
+    /// Serializes as `"finite"`, `"stuttering"` or `{ "loop": <state index> }`.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        match self {
+            Self::Finite => serializer.serialize_str("finite"),
+            Self::Stuttering => serializer.serialize_str("stuttering"),
+            Self::Loop(idx) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("loop", &**idx)?;
+                map.end()
+            }
+        }
+    }
+}
 
 /// A counterexample.
 #[derive(Debug, Clone)]
@@ -113,6 +137,9 @@ pub struct Cex {
     pub falsified: Option<String>,
     pub states: idx::States<State>,
     pub shape: Shape,
+    /// TLC `ALIAS` the states come from, if any; state values are then the fields of the alias,
+    /// not state variables.
+    pub alias: Option<String>,
 }
 impl Cex {
     /// Constructor with default [`Shape::Finite`] shape.
@@ -121,6 +148,7 @@ impl Cex {
             falsified: None,
             states: idx::States::new(),
             shape: Shape::Finite,
+            alias: None,
         }
     }
 
@@ -166,6 +194,58 @@ impl Cex {
         self.shape = shape;
         self
     }
+
+    /// Sets the `ALIAS` the states come from.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// Description of the `ALIAS` the states come from, if any, for renderers.
+    pub fn alias_desc(&self) -> Option<String> {
+        self.alias
+            .as_ref()
+            .map(|alias| format!("values are the output of alias `{}`", alias))
+    }
+
+    /// Fails if the states come from an `ALIAS`, *i.e.* they are not valuations of the state
+    /// variables.
+    ///
+    /// - `what` describes what needs state variables, *e.g.* `"cex regression tests"`.
+    pub fn check_not_alias(&self, what: impl fmt::Display) -> Res<()> {
+        match self.alias.as_ref() {
+            None => Ok(()),
+            Some(alias) => bail!(
+                "{} require the values of the state variables, \
+                but this cex shows the output of alias `{}`",
+                what,
+                alias,
+            ),
+        }
+    }
+
+    /// State the trace goes back to after its last state, if any.
+    ///
+    /// This is the last state for [`Shape::Stuttering`] cexs.
+    pub fn loops_to(&self) -> Option<idx::State> {
+        match self.shape {
+            Shape::Finite => None,
+            Shape::Stuttering => self.states.len().checked_sub(1).map(idx::State::from),
+            Shape::Loop(idx) => Some(idx),
+        }
+    }
+}
+impl serde::Serialize for Cex {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let states: Vec<&State> = self.states.index_iter().map(|(_, state)| state).collect();
+        let mut cex = serializer.serialize_struct("Cex", 4)?;
+        cex.serialize_field("falsified", &self.falsified)?;
+        cex.serialize_field("shape", &self.shape)?;
+        cex.serialize_field("alias", &self.alias)?;
+        cex.serialize_field("states", &states)?;
+        cex.end()
+    }
 }
 implem! {
     for Cex {
@@ -1,9 +1,14 @@
 //! Cex pretty-printing.
+//!
+//! A [`Spec`] also carries a [`conf::CexView`] which selects the state variables displayed and
+//! how to render them.
 
 prelude!();
 
+use conf::cex_view::Hint;
+
 /// Pretty-printing spec.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Spec {
     /// State index style.
     pub state_idx_style: ansi::Style,
@@ -17,10 +22,16 @@ pub struct Spec {
     pub bag_delim: (&'static str, &'static str),
     /// Opening/closing delimiters for sets.
     pub set_delim: (&'static str, &'static str),
+    /// Opening/closing delimiters for functions.
+    pub fun_delim: (&'static str, &'static str),
     /// String to put between keys and values, **including spaces**.
     pub smap_sep: &'static str,
     /// String to put between keys and values, **including spaces**.
     pub bag_sep: &'static str,
+    /// String to put between keys and values, **including spaces**.
+    pub fun_sep: &'static str,
+    /// String to put between the columns of tables, **including spaces**.
+    pub table_sep: &'static str,
     /// String to put before count.
     pub bag_count_pref: &'static str,
     /// String to put between the count and the element.
@@ -33,6 +44,8 @@ pub struct Spec {
     pub non_string_lit_style: ansi::Style,
     /// String literal style.
     pub string_lit_style: ansi::Style,
+    /// Model value style.
+    pub model_value_style: ansi::Style,
 
     /// Underline style.
     pub uline: ansi::Style,
@@ -41,6 +54,9 @@ pub struct Spec {
     pub align_keys: bool,
     /// Activates pretty-printing debug-info for collections.
     pub debug_pp_colls: bool,
+
+    /// Variables to display and how.
+    pub view: conf::CexView,
 }
 impl Default for Spec {
     fn default() -> Self {
@@ -61,19 +77,25 @@ impl Spec {
             smap_delim: ("[", "]"),
             bag_delim: ("⟬", "⟭"),
             set_delim: ("{", "}"),
+            fun_delim: ("(", ")"),
             smap_sep: " ↦ ",
             bag_sep: " ↦ ",
+            fun_sep: " ↦ ",
+            table_sep: " │ ",
             bag_count_pref: "| ",
             bag_count_sep: " of ",
             smap_key_style: ansi::Style::new(),
             bag_key_style: ansi::Style::new(),
             non_string_lit_style: ansi::Style::new(),
             string_lit_style: ansi::Style::new(),
+            model_value_style: ansi::Style::new(),
 
             uline: ansi::Style::new(),
 
             align_keys: true,
             debug_pp_colls: false,
+
+            view: conf::CexView::new(),
         }
     }
     /// Fancy spec.
@@ -86,11 +108,34 @@ impl Spec {
         slf.non_string_lit_style = ansi::Style::new().bold();
         slf.bag_key_style = slf.non_string_lit_style.clone();
         slf.string_lit_style = ansi::Color::Yellow.bold();
+        slf.model_value_style = ansi::Color::Cyan.bold();
 
         slf.uline = ansi::Style::new().underline();
 
         slf
     }
+
+    /// Same spec without any style.
+    pub fn unstyled(&self) -> Self {
+        let empty = Self::empty();
+        Self {
+            state_idx_style: empty.state_idx_style,
+            svar_style: empty.svar_style,
+            smap_key_style: empty.smap_key_style,
+            bag_key_style: empty.bag_key_style,
+            non_string_lit_style: empty.non_string_lit_style,
+            string_lit_style: empty.string_lit_style,
+            model_value_style: empty.model_value_style,
+            uline: empty.uline,
+            ..self.clone()
+        }
+    }
+
+    /// Sets the variables to display and how.
+    pub fn view(mut self, view: conf::CexView) -> Self {
+        self.view = view;
+        self
+    }
 }
 
 pub struct PrettyStatePref<'spec> {
@@ -324,6 +369,7 @@ impl Spec {
         use fmt::Write;
 
         let mut pretty_state = PrettyStatePref::new(self, cex);
+        let mut prev: Option<&State> = None;
 
         for (idx, state) in cex.states.index_iter() {
             if *idx > 0 {
@@ -332,13 +378,21 @@ impl Spec {
 
             let content = {
                 let mut buf = String::with_capacity(113);
-                let max_key_len = state
+                let visible: Vec<(&String, &value::Value)> = state
                     .values
-                    .keys()
-                    .map(|key| key.chars().count())
+                    .iter()
+                    .filter(|(key, value)| {
+                        let unchanged = self.view.changed_only
+                            && prev.map_or(false, |prev| prev.values.get(*key) == Some(*value));
+                        self.view.is_visible(key) && !unchanged
+                    })
+                    .collect();
+                let max_key_len = visible
+                    .iter()
+                    .map(|(key, _)| key.chars().count())
                     .max()
                     .unwrap_or(0);
-                for (key, value) in state.values.iter() {
+                for (key, value) in visible {
                     buf.push('\n');
                     write!(buf, "{}", self.svar_style.paint(key)).unwrap();
                     if self.align_keys {
@@ -348,13 +402,139 @@ impl Spec {
                     }
                     buf.push(':');
                     buf.push(' ');
-                    self.value_to_ml_string(value, &mut buf)
+                    self.var_value_to_ml_string(key, value, &mut buf)
                 }
                 buf
             };
 
             pretty_state.pretty_state(buf, idx, state.info.as_ref(), &content);
+            prev = Some(state);
+        }
+    }
+    /// Multi-line string representation of the value of a state variable.
+    ///
+    /// Applies the variable's [`Hint`], if any.
+    pub fn var_value_to_ml_string(&self, var: &str, value: &value::Value, buf: &mut String) {
+        let plain = match value {
+            value::Value::Null => {
+                buf.push_str("null");
+                return;
+            }
+            value::Value::Plain(plain) => plain,
+        };
+        match self.view.hint(var) {
+            Some(Hint::Table) => {
+                if !self.table_to_ml_string(plain, buf) {
+                    self.plain_value_to_ml_string(plain, buf)
+                }
+            }
+            Some(Hint::Fold(max)) => self.folding_plain_value_to_ml_string(plain, Some(max), buf),
+            None => self.plain_value_to_ml_string(plain, buf),
+        }
+    }
+    /// Single-line string representation of the value of a state variable.
+    ///
+    /// Applies the variable's [`Hint::Fold`] hint, if any.
+    pub fn var_value_to_string(&self, var: &str, value: &value::Value) -> String {
+        let mut buf = String::with_capacity(17);
+        match (value, self.view.hint(var)) {
+            (value::Value::Plain(plain), Some(Hint::Fold(max))) => {
+                self.folding_plain_value_to_ml_string(plain, Some(max), &mut buf)
+            }
+            (value, _) => self.value_to_ml_string(value, &mut buf),
+        }
+        buf.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+    /// Visible state variables of all the states of a cex.
+    pub fn visible_vars<'cex>(&self, cex: &'cex Cex) -> Vec<&'cex str> {
+        let vars: Set<&str> = cex
+            .states
+            .index_iter()
+            .flat_map(|(_, state)| state.values.keys())
+            .map(String::as_str)
+            .filter(|var| self.view.is_visible(var))
+            .collect();
+        vars.into_iter().collect()
+    }
+    /// Multi-line representation of a function, record or sequence as a table.
+    ///
+    /// Each binding (index for sequences) goes on its own line with the keys in the first column.
+    /// Returns `false` and writes nothing for other values and empty collections.
+    fn table_to_ml_string(&self, value: &value::Plain, buf: &mut String) -> bool {
+        let rows: Vec<(String, usize, &value::Plain)> = match value {
+            value::Plain::Fun(fun) => fun
+                .iter()
+                .map(|(key, value)| {
+                    let (key, key_len) = self.fun_key_to_string(key);
+                    (key, key_len, value)
+                })
+                .collect(),
+            value::Plain::SMap(smap) => smap
+                .iter()
+                .map(|(key, value)| {
+                    let styled = self.smap_key_style.paint(key).to_string();
+                    (styled, key.chars().count(), value)
+                })
+                .collect(),
+            value::Plain::Tuple(tuple) => tuple
+                .iter()
+                .enumerate()
+                .map(|(idx, value)| {
+                    let idx = (idx + 1).to_string();
+                    let styled = self.non_string_lit_style.paint(&idx).to_string();
+                    (styled, idx.chars().count(), value)
+                })
+                .collect(),
+            _ => return false,
+        };
+        if rows.is_empty() {
+            return false;
+        }
+        let max_key_len = rows
+            .iter()
+            .map(|(_, key_len, _)| *key_len)
+            .max()
+            .unwrap_or(0);
+        for (key, key_len, value) in rows {
+            let mut value_buf = String::with_capacity(17);
+            self.plain_value_to_ml_string(value, &mut value_buf);
+            buf.push_str("\n    ");
+            buf.push_str(&key);
+            for _ in key_len..max_key_len {
+                buf.push(' ');
+            }
+            buf.push_str(self.table_sep);
+            let mut lines = value_buf.lines();
+            if let Some(first) = lines.next() {
+                buf.push_str(first);
+            }
+            for line in lines {
+                buf.push_str("\n    ");
+                for _ in 0..max_key_len {
+                    buf.push(' ');
+                }
+                buf.push_str(self.table_sep);
+                buf.push_str(line);
+            }
         }
+        true
+    }
+    /// Representation of a folded set or bag: its delimiters around its cardinality.
+    fn folded_to_ml_string(&self, delim: (&str, &str), card: impl fmt::Display, buf: &mut String) {
+        buf.push_str(delim.0);
+        buf.push(' ');
+        buf.push_str(
+            &self
+                .non_string_lit_style
+                .paint(format!("{} elements", card))
+                .to_string(),
+        );
+        buf.push(' ');
+        buf.push_str(delim.1);
     }
     /// Multi-line string representation of a value.
     pub fn value_to_ml_string(&self, value: &value::Value, buf: &mut String) {
@@ -363,8 +543,27 @@ impl Spec {
             value::Value::Plain(value) => self.plain_value_to_ml_string(value, buf),
         }
     }
+    /// String representation of a function key, and its length ignoring styles.
+    fn fun_key_to_string(&self, key: &value::Plain) -> (String, usize) {
+        let mut styled = String::with_capacity(17);
+        self.plain_value_to_ml_string(key, &mut styled);
+        let mut unstyled = String::with_capacity(17);
+        self.unstyled().plain_value_to_ml_string(key, &mut unstyled);
+        (styled, unstyled.chars().count())
+    }
+
     /// Multi-line string representation of a plain value.
     pub fn plain_value_to_ml_string(&self, value: &value::Plain, buf: &mut String) {
+        self.folding_plain_value_to_ml_string(value, None, buf)
+    }
+    /// Multi-line string representation of a plain value, folding sets and bags with more than
+    /// `fold` elements, if any, to their cardinality.
+    pub fn folding_plain_value_to_ml_string(
+        &self,
+        value: &value::Plain,
+        fold: Option<usize>,
+        buf: &mut String,
+    ) {
         enum Frame<'a> {
             Tuple {
                 ml: bool,
@@ -383,6 +582,12 @@ impl Spec {
                 indent: usize,
                 tail: std::collections::btree_map::Iter<'a, value::Plain, Int>,
             },
+            Fun {
+                ml: bool,
+                max_key_len: usize,
+                indent: usize,
+                tail: std::vec::IntoIter<(String, usize, &'a value::Plain)>,
+            },
         }
         let mut current = value;
         let mut curr_indent = 0;
@@ -410,6 +615,17 @@ impl Spec {
                 value::Plain::Cst(cst @ value::Cst::S(_)) => {
                     buf.push_str(&self.string_lit_style.paint(cst.to_string()).to_string());
                 }
+                value::Plain::Cst(cst @ value::Cst::M(_)) => {
+                    buf.push_str(&self.model_value_style.paint(cst.to_string()).to_string());
+                }
+                value::Plain::Interval(interval) => {
+                    buf.push_str(
+                        &self
+                            .non_string_lit_style
+                            .paint(interval.to_string())
+                            .to_string(),
+                    );
+                }
                 value::Plain::Tuple(tuple) => {
                     buf.push_str(self.tuple_delim.0);
                     if self.debug_pp_colls {
@@ -439,6 +655,13 @@ impl Spec {
                         buf.push_str(self.tuple_delim.1);
                     }
                 }
+                value::Plain::Set(set) if fold.map_or(false, |max| set.len() > max) => {
+                    self.folded_to_ml_string(self.set_delim, set.len(), buf)
+                }
+                value::Plain::Bag(bag) if fold.map_or(false, |max| bag.len() > max) => {
+                    let card = bag.values().fold(Int::zero(), |acc, count| acc + count);
+                    self.folded_to_ml_string(self.bag_delim, card, buf)
+                }
                 value::Plain::Set(set) => {
                     buf.push_str(self.set_delim.0);
                     if self.debug_pp_colls {
@@ -556,6 +779,56 @@ impl Spec {
                         buf.push_str(self.bag_delim.1);
                     }
                 }
+                value::Plain::Fun(fun) => {
+                    let bindings: Vec<(String, usize, &value::Plain)> = fun
+                        .iter()
+                        .map(|(key, value)| {
+                            let (key, key_len) = self.fun_key_to_string(key);
+                            (key, key_len, value)
+                        })
+                        .collect();
+                    let max_key_len = bindings
+                        .iter()
+                        .map(|(_, key_len, _)| *key_len)
+                        .max()
+                        // `max_key_len` will not be used if there are no elements.
+                        .unwrap_or(0);
+                    buf.push_str(self.fun_delim.0);
+                    if self.debug_pp_colls {
+                        buf.push_str(&format!("<{}>", current.fmt_len()));
+                    }
+                    let ml = !current.is_one_line();
+                    let indent = curr_indent;
+                    if ml {
+                        curr_indent += 1;
+                    }
+                    let mut iter = bindings.into_iter();
+                    if let Some((first_key, first_key_len, first_val)) = iter.next() {
+                        if ml {
+                            buf.push('\n');
+                            pref!();
+                        } else {
+                            buf.push(' ');
+                        }
+                        buf.push_str(&first_key);
+                        if ml && self.align_keys {
+                            for _ in 0..max_key_len - first_key_len {
+                                buf.push(' ');
+                            }
+                        }
+                        buf.push_str(self.fun_sep);
+                        current = first_val;
+                        stack.push(Frame::Fun {
+                            ml,
+                            max_key_len,
+                            indent,
+                            tail: iter,
+                        });
+                        continue 'go_down;
+                    } else {
+                        buf.push_str(self.fun_delim.1);
+                    }
+                }
             }
 
             'go_up: loop {
@@ -675,6 +948,47 @@ impl Spec {
                             continue 'go_up;
                         }
                     }
+                    Some(Frame::Fun {
+                        ml,
+                        max_key_len,
+                        indent,
+                        mut tail,
+                    }) => {
+                        if let Some((next_key, next_key_len, next_value)) = tail.next() {
+                            curr_indent = indent + 1;
+                            buf.push(',');
+                            if ml {
+                                buf.push('\n');
+                                pref!();
+                            } else {
+                                buf.push(' ');
+                            }
+                            buf.push_str(&next_key);
+                            if ml && self.align_keys {
+                                for _ in 0..max_key_len - next_key_len {
+                                    buf.push(' ');
+                                }
+                            }
+                            buf.push_str(self.fun_sep);
+                            current = next_value;
+                            stack.push(Frame::Fun {
+                                ml,
+                                max_key_len,
+                                indent,
+                                tail,
+                            });
+                            continue 'go_down;
+                        } else {
+                            if ml {
+                                buf.push('\n');
+                                pref!(indent);
+                            } else {
+                                buf.push(' ');
+                            }
+                            buf.push_str(self.fun_delim.1);
+                            continue 'go_up;
+                        }
+                    }
                 }
             }
         }
@@ -0,0 +1,300 @@
+//! Tabular cex rendering: one row per state, one column per state variable.
+//!
+//! Tables respect the [`conf::CexView`] of the [`pretty::Spec`] used: hidden variables have no
+//! column, and with [`conf::CexView::changed_only`] the cells of variables that did not change
+//! since the previous state are empty. Values are rendered on a single line.
+//!
+//! Additional [`Column`]s can follow the state variables, see [`render_with`].
+
+prelude!();
+
+/// Table flavor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flavor {
+    /// Box-drawing table for terminals, styled by the spec.
+    Terminal,
+    /// Github-flavored Markdown table, unstyled.
+    Markdown,
+}
+
+/// Marker for the state a trace loops back to.
+pub const LOOP_MARKER: &str = "↺";
+
+/// An additional column, *e.g.* the values of an expression in each state.
+#[derive(Debug, Clone)]
+pub struct Column {
+    /// Header of the column.
+    pub header: String,
+    /// Value of the column in each state, if any.
+    pub values: Vec<Option<Value>>,
+}
+impl Column {
+    /// Constructor.
+    pub fn new(header: impl Into<String>, values: Vec<Option<Value>>) -> Self {
+        Self {
+            header: header.into(),
+            values,
+        }
+    }
+}
+
+/// A table cell: its content and its length ignoring styles.
+struct Cell {
+    content: String,
+    len: usize,
+}
+impl Cell {
+    /// Constructor.
+    fn new(content: impl Into<String>, len: usize) -> Self {
+        Self {
+            content: content.into(),
+            len,
+        }
+    }
+    /// Constructor for unstyled content.
+    fn unstyled(content: impl Into<String>) -> Self {
+        let content = content.into();
+        let len = content.chars().count();
+        Self::new(content, len)
+    }
+}
+
+/// Renders a value as a cell.
+fn value_cell(
+    spec: &pretty::Spec,
+    unstyled: &pretty::Spec,
+    flavor: Flavor,
+    var: &str,
+    value: &Value,
+) -> Cell {
+    let len = unstyled.var_value_to_string(var, value).chars().count();
+    let content = spec.var_value_to_string(var, value);
+    match flavor {
+        Flavor::Terminal => Cell::new(content, len),
+        Flavor::Markdown => Cell::unstyled(format!("`{}`", content.replace('|', "\\|"))),
+    }
+}
+
+/// Builds the header and rows of a table.
+fn cells(
+    spec: &pretty::Spec,
+    cex: &Cex,
+    flavor: Flavor,
+    columns: &[Column],
+) -> (Vec<Cell>, Vec<Vec<Cell>>) {
+    let unstyled = spec.unstyled();
+    let styled = match flavor {
+        Flavor::Terminal => spec,
+        Flavor::Markdown => &unstyled,
+    };
+    let vars = spec.visible_vars(cex);
+    let loops_to = cex.loops_to();
+
+    let mut header = vec![Cell::unstyled("#"), Cell::unstyled("action")];
+    header.extend(vars.iter().map(|var| {
+        Cell::new(
+            styled.svar_style.paint(*var).to_string(),
+            var.chars().count(),
+        )
+    }));
+    header.extend(columns.iter().map(|col| match flavor {
+        Flavor::Terminal => Cell::unstyled(col.header.clone()),
+        Flavor::Markdown => Cell::unstyled(format!("`{}`", col.header.replace('|', "\\|"))),
+    }));
+
+    let mut rows = Vec::with_capacity(cex.states.len());
+    let mut prev: Option<&State> = None;
+    for (pos, (idx, state)) in cex.states.index_iter().enumerate() {
+        let mut row = Vec::with_capacity(vars.len() + columns.len() + 2);
+
+        let index = if loops_to == Some(idx) {
+            format!("{} {}", LOOP_MARKER, idx)
+        } else {
+            idx.to_string()
+        };
+        let index_len = index.chars().count();
+        row.push(Cell::new(
+            styled.state_idx_style.paint(index).to_string(),
+            index_len,
+        ));
+
+        let action = match state.info.as_ref() {
+            Some(info) => format!("{} @ {}.tla:{}", info.action, info.module, info.span.0),
+            None => "initial state".into(),
+        };
+        row.push(Cell::unstyled(action));
+
+        for var in vars.iter() {
+            let unchanged = spec.view.changed_only
+                && prev.map_or(false, |prev| !state.changed_since(prev, var));
+            let cell = match state.values.get(*var) {
+                Some(value) if !unchanged => value_cell(styled, &unstyled, flavor, var, value),
+                _ => Cell::unstyled(""),
+            };
+            row.push(cell);
+        }
+        for col in columns.iter() {
+            let cell = match col.values.get(pos) {
+                Some(Some(value)) => value_cell(styled, &unstyled, flavor, &col.header, value),
+                Some(None) | None => Cell::unstyled(""),
+            };
+            row.push(cell);
+        }
+
+        rows.push(row);
+        prev = Some(state);
+    }
+    (header, rows)
+}
+
+/// Description of the shape of a cex, if it loops.
+fn shape_desc(cex: &Cex) -> Option<String> {
+    match cex.shape {
+        crate::Shape::Finite => None,
+        crate::Shape::Stuttering => Some(format!(
+            "{} the trace stutters on its last state",
+            LOOP_MARKER
+        )),
+        crate::Shape::Loop(idx) => Some(format!(
+            "{} the trace loops back to state {}",
+            LOOP_MARKER, idx
+        )),
+    }
+}
+
+/// Renders a cex as a table.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cex::{pretty::Spec, table::{render, Flavor}, Cex, State, Value};
+/// # use base::Int;
+/// let mut cex = Cex::new();
+/// for cnt in 0..2 {
+///     let mut state = State::new(None);
+///     let _ = state.insert("cnt".into(), Value::from(Int::from(cnt)));
+///     let _ = state.insert("reset".into(), Value::from(false));
+///     let _ = cex.push(state);
+/// }
+/// let table = render(&Spec::empty(), &cex, Flavor::Markdown);
+/// assert_eq!(
+///     table,
+///     "\
+/// | # | action        | cnt | reset   |
+/// |--:|:--------------|:----|:--------|
+/// | 0 | initial state | `0` | `false` |
+/// | 1 | initial state | `1` | `false` |
+/// "
+/// );
+/// ```
+pub fn render(spec: &pretty::Spec, cex: &Cex, flavor: Flavor) -> String {
+    render_with(spec, cex, flavor, &[])
+}
+
+/// Renders a cex as a table with additional columns after the state variables.
+///
+/// The `n`-th value of a column is the value of the column in the `n`-th state.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cex::{pretty::Spec, table::{render_with, Column, Flavor}, Cex, State, Value};
+/// # use base::Int;
+/// let mut cex = Cex::new();
+/// for cnt in 0..2 {
+///     let mut state = State::new(None);
+///     let _ = state.insert("cnt".into(), Value::from(Int::from(cnt)));
+///     let _ = cex.push(state);
+/// }
+/// let col = Column::new("cnt > 0", vec![None, Some(Value::from(true))]);
+/// let table = render_with(&Spec::empty(), &cex, Flavor::Markdown, &[col]);
+/// assert_eq!(
+///     table,
+///     "\
+/// | # | action        | cnt | `cnt > 0` |
+/// |--:|:--------------|:----|:----------|
+/// | 0 | initial state | `0` |           |
+/// | 1 | initial state | `1` | `true`    |
+/// "
+/// );
+/// ```
+pub fn render_with(spec: &pretty::Spec, cex: &Cex, flavor: Flavor, columns: &[Column]) -> String {
+    let (header, rows) = cells(spec, cex, flavor, columns);
+    let widths: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .map(|(col, cell)| {
+            rows.iter()
+                .map(|row| row[col].len)
+                .chain(Some(cell.len))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut buf = String::with_capacity(666);
+    let push_row = |buf: &mut String, row: &[Cell], sep: &str, pref: &str, suff: &str| {
+        buf.push_str(pref);
+        for (col, cell) in row.iter().enumerate() {
+            if col > 0 {
+                buf.push_str(sep);
+            }
+            // Right-align state indices.
+            if col == 0 {
+                buf.extend(std::iter::repeat(' ').take(widths[col] - cell.len));
+            }
+            buf.push_str(&cell.content);
+            if col > 0 {
+                buf.extend(std::iter::repeat(' ').take(widths[col] - cell.len));
+            }
+        }
+        buf.push_str(suff);
+        buf.push('\n');
+    };
+
+    match flavor {
+        Flavor::Terminal => {
+            let sep = format!(" {} ", pretty::PrettyStatePref::v_line);
+            push_row(&mut buf, &header, &sep, " ", "");
+            for (col, width) in widths.iter().enumerate() {
+                if col > 0 {
+                    buf.push(pretty::PrettyStatePref::h_line);
+                    buf.push('┼');
+                }
+                buf.extend(std::iter::repeat(pretty::PrettyStatePref::h_line).take(width + 1));
+            }
+            buf.push('\n');
+            for row in rows.iter() {
+                push_row(&mut buf, row, &sep, " ", "");
+            }
+            for desc in shape_desc(cex).into_iter().chain(cex.alias_desc()) {
+                buf.push_str(&desc);
+                buf.push('\n');
+            }
+        }
+        Flavor::Markdown => {
+            push_row(&mut buf, &header, " | ", "| ", " |");
+            buf.push('|');
+            for (col, width) in widths.iter().enumerate() {
+                if col == 0 {
+                    buf.extend(std::iter::repeat('-').take(*width + 1));
+                    buf.push(':');
+                } else {
+                    buf.push(':');
+                    buf.extend(std::iter::repeat('-').take(*width + 1));
+                }
+                buf.push('|');
+            }
+            buf.push('\n');
+            for row in rows.iter() {
+                push_row(&mut buf, row, " | ", "| ", " |");
+            }
+            for desc in shape_desc(cex).into_iter().chain(cex.alias_desc()) {
+                buf.push('\n');
+                buf.push_str(&desc);
+                buf.push('\n');
+            }
+        }
+    }
+    buf
+}
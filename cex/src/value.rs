@@ -3,7 +3,10 @@
 prelude!();
 
 /// Either a plain value or `null`.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+///
+/// Serializes as `null` or as the underlying [`Plain`] value.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(untagged)]
 pub enum Value {
     Null,
     Plain(Plain),
@@ -20,9 +23,16 @@ implem! {
         From<SMap> { |smap| Self::Plain(smap.into()) }
         From<Set> { |set| Self::Plain(set.into()) }
         From<Bag> { |bag| Self::Plain(bag.into()) }
+        From<Fun> { |fun| Self::Plain(fun.into()) }
+        From<Interval> { |interval| Self::Plain(interval.into()) }
     }
 }
 impl Value {
+    /// Displays the value in TLA+ syntax, `null` if undefined.
+    pub fn tla(&self) -> Tla {
+        Tla(self)
+    }
+
     // /// Structural depth.
     // pub fn depth(&self) -> usize {
     //     match self {
@@ -33,13 +43,21 @@ impl Value {
 }
 
 /// A plain value.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+///
+/// Sequences are tuples, and records are string maps. Functions are only used for functions which
+/// domain is not `1..n`, since TLC prints the latter as sequences.
+///
+/// Serializes as `{ "kind": <kind>, "value": <value> }` where `<kind>` is the lowercase variant name.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(tag = "kind", content = "value", rename_all = "lowercase")]
 pub enum Plain {
     Cst(Cst),
     Tuple(Tuple),
     SMap(SMap),
     Set(Set),
     Bag(Bag),
+    Fun(Fun),
+    Interval(Interval),
 }
 implem! {
     impl('a) for Plain {
@@ -52,35 +70,128 @@ implem! {
         From<SMap> { |smap| Self::SMap(smap) }
         From<Set> { |set| Self::Set(set) }
         From<Bag> { |bag| Self::Bag(bag) }
+        From<Fun> { |fun| Self::Fun(fun) }
+        From<Interval> { |interval| Self::Interval(interval) }
     }
 }
 impl Plain {
+    /// Writes the value in TLA+ syntax, as TLC prints it.
+    ///
+    /// ```rust
+    /// # use cex::value::*;
+    /// # use base::Int;
+    /// let fun: Plain = Fun::new(
+    ///     vec![
+    ///         (Int::from(1).into(), Cst::model("p1").into()),
+    ///         (Int::from(2).into(), Tuple::new(vec![true.into(), "a".into()]).into()),
+    ///     ]
+    ///     .into_iter()
+    ///     .collect(),
+    /// )
+    /// .into();
+    /// let value = Value::from(fun);
+    /// assert_eq!(value.tla().to_string(), r#"(1 :> p1 @@ 2 :> <<TRUE, "a">>)"#);
+    /// ```
+    pub fn fmt_tla(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        /// Writes the elements of a collection separated by `sep`.
+        fn fmt_elms<T>(
+            fmt: &mut fmt::Formatter,
+            elms: impl Iterator<Item = T>,
+            sep: &str,
+            mut fmt_elm: impl FnMut(&mut fmt::Formatter, T) -> fmt::Result,
+        ) -> fmt::Result {
+            for (idx, elm) in elms.enumerate() {
+                if idx > 0 {
+                    fmt.write_str(sep)?;
+                }
+                fmt_elm(fmt, elm)?;
+            }
+            Ok(())
+        }
+
+        match self {
+            Self::Cst(Cst::B(true)) => fmt.write_str("TRUE"),
+            Self::Cst(Cst::B(false)) => fmt.write_str("FALSE"),
+            Self::Cst(cst) => fmt::Display::fmt(cst, fmt),
+            Self::Interval(interval) => fmt::Display::fmt(interval, fmt),
+            Self::Tuple(tuple) => {
+                fmt.write_str("<<")?;
+                fmt_elms(fmt, tuple.iter(), ", ", |fmt, elm| elm.fmt_tla(fmt))?;
+                fmt.write_str(">>")
+            }
+            Self::Set(set) => {
+                fmt.write_str("{")?;
+                fmt_elms(fmt, set.iter(), ", ", |fmt, elm| elm.fmt_tla(fmt))?;
+                fmt.write_str("}")
+            }
+            Self::SMap(smap) => {
+                fmt.write_str("[")?;
+                fmt_elms(fmt, smap.iter(), ", ", |fmt, (key, value)| {
+                    write!(fmt, "{} |-> ", key)?;
+                    value.fmt_tla(fmt)
+                })?;
+                fmt.write_str("]")
+            }
+            Self::Bag(bag) => {
+                fmt.write_str("(")?;
+                fmt_elms(fmt, bag.iter(), " @@ ", |fmt, (elm, count)| {
+                    elm.fmt_tla(fmt)?;
+                    write!(fmt, " :> {}", count)
+                })?;
+                fmt.write_str(")")
+            }
+            // Functions with an empty domain are empty sequences.
+            Self::Fun(fun) if fun.is_empty() => fmt.write_str("<<>>"),
+            Self::Fun(fun) => {
+                fmt.write_str("(")?;
+                fmt_elms(fmt, fun.iter(), " @@ ", |fmt, (key, value)| {
+                    key.fmt_tla(fmt)?;
+                    fmt.write_str(" :> ")?;
+                    value.fmt_tla(fmt)
+                })?;
+                fmt.write_str(")")
+            }
+        }
+    }
+
     /// True on constants.
     pub fn is_cst(&self) -> bool {
         match self {
             Self::Cst(_) => true,
-            Self::Tuple(_) | Self::SMap(_) | Self::Set(_) | Self::Bag(_) => false,
+            Self::Tuple(_)
+            | Self::SMap(_)
+            | Self::Set(_)
+            | Self::Bag(_)
+            | Self::Fun(_)
+            | Self::Interval(_) => false,
         }
     }
     /// True on bool/int constants.
     pub fn is_tiny_cst(&self) -> bool {
         match self {
             Self::Cst(Cst::B(_) | Cst::I(_)) => true,
-            Self::Cst(Cst::S(_)) | Self::Tuple(_) | Self::SMap(_) | Self::Set(_) | Self::Bag(_) => {
-                false
-            }
+            Self::Cst(Cst::S(_) | Cst::M(_))
+            | Self::Tuple(_)
+            | Self::SMap(_)
+            | Self::Set(_)
+            | Self::Bag(_)
+            | Self::Fun(_)
+            | Self::Interval(_) => false,
         }
     }
 
     /// Formatting-length of a value for formatting, helps deciding whether to one-line print.
     pub fn fmt_len(&self) -> usize {
         match self {
-            Self::Cst(Cst::B(_) | Cst::I(_)) => 1,
-            Self::Cst(Cst::S(s)) => s.len() / 10,
+            Self::Cst(Cst::B(_) | Cst::I(_)) | Self::Interval(_) => 1,
+            Self::Cst(Cst::S(s) | Cst::M(s)) => s.len() / 10,
             Self::Tuple(t) => t.iter().fold(0, |sum, next| sum + next.fmt_len().max(1)),
             Self::SMap(smap) => smap
                 .iter()
                 .fold(0, |sum, (_key, value)| sum + 1 + value.fmt_len().max(1)),
+            Self::Fun(fun) => fun.iter().fold(0, |sum, (key, value)| {
+                sum + key.fmt_len().max(1) + value.fmt_len().max(1)
+            }),
             Self::Set(set) => set.iter().fold(0, |sum, next| sum + next.fmt_len().max(1)),
             // Bags are special because they require multi-line printing. So they are considered to
             // have max length unless they are empty.
@@ -98,7 +209,7 @@ impl Plain {
         const MAX_ELEMENT_COUNT: usize = 7;
         const MAX_BINDING_COUNT: usize = 5;
         match self {
-            Self::Cst(_) => true,
+            Self::Cst(_) | Self::Interval(_) => true,
             Self::Tuple(tuple) => {
                 tuple.len() <= MAX_ELEMENT_COUNT
                     && tuple
@@ -117,6 +228,15 @@ impl Plain {
                         .values()
                         .all(|v| v.is_one_line() && (v.is_cst() || v.fmt_len() <= 2))
             }
+            Self::Fun(fun) => {
+                fun.len() <= MAX_BINDING_COUNT
+                    && fun.iter().all(|(k, v)| {
+                        k.is_one_line()
+                            && (k.is_cst() || k.fmt_len() <= 2)
+                            && v.is_one_line()
+                            && (v.is_cst() || v.fmt_len() <= 2)
+                    })
+            }
             Self::Bag(bag) => bag.is_empty(),
         }
     }
@@ -197,12 +317,74 @@ impl Plain {
     // }
 }
 
+/// Wrapper displaying a value in TLA+ syntax, see [`Plain::fmt_tla`].
+pub struct Tla<'a>(&'a Value);
+impl<'a> fmt::Display for Tla<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Value::Null => fmt.write_str("null"),
+            Value::Plain(plain) => plain.fmt_tla(fmt),
+        }
+    }
+}
+
+/// Serializes an integer as a number if it fits in an `i64`, as a string otherwise.
+pub fn serialize_int<S: serde::Serializer>(int: &Int, serializer: S) -> Result<S::Ok, S::Error> {
+    if let Some(i) = int.to_i64() {
+        serializer.serialize_i64(i)
+    } else {
+        serializer.serialize_str(&int.to_string())
+    }
+}
+
+/// Wrapper around an integer reference, serialized with [`serialize_int`].
+struct SerInt<'a>(&'a Int);
+impl<'a> serde::Serialize for SerInt<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_int(self.0, serializer)
+    }
+}
+
+/// Writes a string as a TLA+ string literal, escaping `"`, `\` and control characters.
+pub fn write_escaped(s: &str, fmt: &mut fmt::Formatter) -> fmt::Result {
+    use fmt::Write;
+    fmt.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => fmt.write_str("\\\"")?,
+            '\\' => fmt.write_str("\\\\")?,
+            '\n' => fmt.write_str("\\n")?,
+            '\t' => fmt.write_str("\\t")?,
+            '\r' => fmt.write_str("\\r")?,
+            '\x0c' => fmt.write_str("\\f")?,
+            c => fmt.write_char(c)?,
+        }
+    }
+    fmt.write_char('"')
+}
+
 /// A constant.
+///
+/// String constants are unescaped, [`Display`](fmt::Display) escapes them back.
+///
+/// ```rust
+/// # use cex::value::Cst;
+/// assert_eq!(Cst::from("say \"hi\"\n").to_string(), r#""say \"hi\"\n""#);
+/// assert_eq!(Cst::model("p1").to_string(), "p1");
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Cst {
     B(bool),
     I(Int),
     S(String),
+    /// Model value, or any other symbolic constant such as `Nat`.
+    M(String),
+}
+impl Cst {
+    /// Model value constructor.
+    pub fn model(name: impl Into<String>) -> Self {
+        Self::M(name.into())
+    }
 }
 implem! {
     impl('a) for Cst {
@@ -214,13 +396,31 @@ implem! {
         Display { |&self, fmt| match self {
             Self::B(b) => b.fmt(fmt),
             Self::I(i) => i.fmt(fmt),
-            Self::S(s) => write!(fmt, "\"{}\"", s),
+            Self::S(s) => write_escaped(s, fmt),
+            Self::M(m) => m.fmt(fmt),
         } }
     }
 }
+impl serde::Serialize for Cst {
+    /// Serializes model values as `{ "model": <name> }`, other constants as themselves.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::B(b) => serializer.serialize_bool(*b),
+            Self::I(i) => serialize_int(i, serializer),
+            Self::S(s) => serializer.serialize_str(s),
+            Self::M(m) => {
+                use serde::ser::SerializeMap;
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("model", m)?;
+                map.end()
+            }
+        }
+    }
+}
 
-/// A tuple.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// A tuple, or a sequence.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(transparent)]
 pub struct Tuple {
     pub elms: Vec<Plain>,
 }
@@ -240,7 +440,8 @@ implem! {
 }
 
 /// A set.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(transparent)]
 pub struct Set {
     pub elms: Vec<Plain>,
 }
@@ -259,8 +460,9 @@ implem! {
     }
 }
 
-/// A string-map (called *structure* in TLA+).
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// A string-map (called *record* or *structure* in TLA+).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(transparent)]
 pub struct SMap {
     pub elms: Map<String, Plain>,
 }
@@ -308,3 +510,72 @@ implem! {
         }
     }
 }
+impl serde::Serialize for Bag {
+    /// Serializes as a list of `[value, count]` pairs, since bag keys are not strings.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+        let mut seq = serializer.serialize_seq(Some(self.elms.len()))?;
+        for (value, count) in self.elms.iter() {
+            seq.serialize_element(&(value, SerInt(count)))?;
+        }
+        seq.end()
+    }
+}
+
+/// A function, written `(<key> :> <value> @@ ...)` by TLC.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Fun {
+    pub elms: Map<Plain, Plain>,
+}
+impl Fun {
+    /// Constructor.
+    pub fn new(elms: Map<Plain, Plain>) -> Self {
+        Self { elms }
+    }
+
+    /// Empty constructor.
+    pub fn new_empty() -> Self {
+        Self { elms: Map::new() }
+    }
+}
+implem! {
+    for Fun {
+        Deref<Target = Map<Plain, Plain>> {
+            |&self| &self.elms,
+            |&mut self| &mut self.elms,
+        }
+    }
+}
+impl serde::Serialize for Fun {
+    /// Serializes as a list of `[key, value]` pairs, since function keys are not strings.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+        let mut seq = serializer.serialize_seq(Some(self.elms.len()))?;
+        for (key, value) in self.elms.iter() {
+            seq.serialize_element(&(key, value))?;
+        }
+        seq.end()
+    }
+}
+
+/// An integer interval `lo..hi`, *i.e.* the set of integers between `lo` and `hi` inclusive.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+pub struct Interval {
+    /// Lower bound.
+    #[serde(serialize_with = "serialize_int")]
+    pub lo: Int,
+    /// Upper bound.
+    #[serde(serialize_with = "serialize_int")]
+    pub hi: Int,
+}
+impl Interval {
+    /// Constructor.
+    pub fn new(lo: Int, hi: Int) -> Self {
+        Self { lo, hi }
+    }
+}
+implem! {
+    for Interval {
+        Display { |&self, fmt| write!(fmt, "{}..{}", self.lo, self.hi) }
+    }
+}
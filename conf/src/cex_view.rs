@@ -0,0 +1,178 @@
+//! Counterexample display configuration.
+//!
+//! Specifies which state variables counterexamples display and how, see [`CexView`]. It comes from
+//! the `[cex]` section of the project's configuration file and from `matla run`'s command-line
+//! arguments:
+//!
+//! ```toml
+//! [cex]
+//! only = ["queue", "seen", "cnt"]
+//! hide = ["history"]
+//! changed_only = true
+//!
+//! [cex.hints]
+//! queue = "table"
+//! seen = "fold:20"
+//! ```
+
+prelude!();
+
+/// Sets with more elements than this are folded by [`Hint::Fold`] when no bound is given.
+pub const FOLD_DEFAULT: usize = 10;
+
+/// Per-variable rendering hint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    /// Renders functions and records as a two-column table, one binding per line.
+    Table,
+    /// Renders sets and bags with more than this many elements as their cardinality.
+    Fold(usize),
+}
+impl Hint {
+    /// Value description for hints.
+    pub const VALUES: &'static str = "table|fold[:<INT>]";
+
+    /// Parses a hint.
+    ///
+    /// ```rust
+    /// # use conf::cex_view::*;
+    /// assert_eq!(Hint::parse("table"), Ok(Hint::Table));
+    /// assert_eq!(Hint::parse("fold"), Ok(Hint::Fold(FOLD_DEFAULT)));
+    /// assert_eq!(Hint::parse("fold:3"), Ok(Hint::Fold(3)));
+    /// assert!(Hint::parse("fold:").is_err());
+    /// ```
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim() {
+            "table" => Ok(Self::Table),
+            "fold" => Ok(Self::Fold(FOLD_DEFAULT)),
+            s => s
+                .strip_prefix("fold:")
+                .and_then(|n| n.trim().parse::<usize>().ok())
+                .map(Self::Fold)
+                .ok_or_else(|| format!("expected {}, got `{}`", Self::VALUES, s)),
+        }
+    }
+}
+implem! {
+    for Hint {
+        Display {
+            |&self, fmt| match self {
+                Self::Table => write!(fmt, "table"),
+                Self::Fold(n) => write!(fmt, "fold:{}", n),
+            }
+        }
+    }
+}
+
+/// Parses a variable hint of the form `<var>=<hint>`.
+///
+/// ```rust
+/// # use conf::cex_view::*;
+/// assert_eq!(parse_var_hint("queue=table"), Ok(("queue".into(), Hint::Table)));
+/// assert!(parse_var_hint("queue").is_err());
+/// ```
+pub fn parse_var_hint(s: &str) -> Result<(String, Hint), String> {
+    let (var, hint) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected `<variable>={}`, got `{}`", Hint::VALUES, s))?;
+    let var = var.trim();
+    if var.is_empty() {
+        return Err(format!("expected a variable before `=` in `{}`", s));
+    }
+    Ok((var.to_string(), Hint::parse(hint)?))
+}
+
+/// Counterexample display configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CexView {
+    /// If some, only these variables are displayed.
+    pub only: Option<Set<String>>,
+    /// Variables never displayed.
+    pub hide: Set<String>,
+    /// If true, only display the variables that changed since the previous state.
+    ///
+    /// The initial state always displays all (visible) variables.
+    pub changed_only: bool,
+    /// Rendering hints by variable.
+    pub hints: Map<String, Hint>,
+}
+impl CexView {
+    /// Constructor, displays everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True if the configuration displays everything as is.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// True if a variable is displayed, ignoring [`Self::changed_only`].
+    ///
+    /// ```rust
+    /// # use conf::CexView;
+    /// let mut view = CexView::new();
+    /// assert!(view.is_visible("cnt"));
+    /// view.hide.insert("cnt".into());
+    /// assert!(!view.is_visible("cnt"));
+    /// view.only = Some(vec!["cnt".to_string(), "reset".to_string()].into_iter().collect());
+    /// assert!(!view.is_visible("cnt"));
+    /// assert!(view.is_visible("reset"));
+    /// assert!(!view.is_visible("other"));
+    /// ```
+    pub fn is_visible(&self, var: impl AsRef<str>) -> bool {
+        let var = var.as_ref();
+        let shown = self.only.as_ref().map_or(true, |only| only.contains(var));
+        shown && !self.hide.contains(var)
+    }
+
+    /// Rendering hint of a variable, if any.
+    pub fn hint(&self, var: impl AsRef<str>) -> Option<Hint> {
+        self.hints.get(var.as_ref()).cloned()
+    }
+
+    /// Overwrites `self` with the information of `other`.
+    ///
+    /// Used to apply command-line arguments to the project configuration: `other`'s `only` list
+    /// replaces `self`'s if any, hidden variables and hints are added.
+    pub fn receive(&mut self, other: &Self) {
+        if let Some(only) = other.only.as_ref() {
+            self.only = Some(only.clone())
+        }
+        self.hide.extend(other.hide.iter().cloned());
+        self.changed_only = self.changed_only || other.changed_only;
+        self.hints
+            .extend(other.hints.iter().map(|(var, hint)| (var.clone(), *hint)));
+    }
+
+    /// Serializes itself as a toml `[cex]` section, does nothing if [`Self::is_empty`].
+    pub fn ser_toml(&self, w: &mut impl io::Write) -> Res<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let list = |vars: &Set<String>| {
+            vars.iter()
+                .map(|var| format!("\"{}\"", var))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        writeln!(w, "[cex]")?;
+        if let Some(only) = self.only.as_ref() {
+            writeln!(w, "only = [{}]", list(only))?;
+        }
+        if !self.hide.is_empty() {
+            writeln!(w, "hide = [{}]", list(&self.hide))?;
+        }
+        if self.changed_only {
+            writeln!(w, "changed_only = true")?;
+        }
+        if !self.hints.is_empty() {
+            writeln!(w)?;
+            writeln!(w, "[cex.hints]")?;
+            for (var, hint) in self.hints.iter() {
+                writeln!(w, "{} = \"{}\"", var, hint)?;
+            }
+        }
+        Ok(())
+    }
+}
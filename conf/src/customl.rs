@@ -64,6 +64,32 @@ implem! {
     }
 }
 
+/// Double-quoted toml string, escapes `\` and `"`.
+///
+/// Fails on newlines, which the configuration [parsers](parse) do not support.
+///
+/// ```rust
+/// # use conf::customl::toml_str;
+/// assert_eq!(toml_str("tla2tools.jar").unwrap(), r#""tla2tools.jar""#);
+/// assert_eq!(toml_str(r#"say "C:\tla""#).unwrap(), r#""say \"C:\\tla\"""#);
+/// assert!(toml_str("multi\nline").is_err());
+/// ```
+pub fn toml_str(s: &str) -> Res<String> {
+    if s.contains('\n') {
+        bail!("illegal newline in configuration value `{}`", s.escape_debug())
+    }
+    let mut res = String::with_capacity(s.len() + 2);
+    res.push('"');
+    for c in s.chars() {
+        if matches!(c, '\\' | '"') {
+            res.push('\\')
+        }
+        res.push(c)
+    }
+    res.push('"');
+    Ok(res)
+}
+
 /// Same as [`TlcCla`] but geared towards (de)serialization to/from toml config files.
 #[derive(Debug, Clone)]
 pub struct TlcCla {
@@ -83,6 +109,20 @@ pub struct TlcCla {
     pub print_callstack: Option<(bool, Source)>,
     /// (De)activates timestats-printing.
     pub timestats: Option<(bool, Source)>,
+    /// (De)activates simulation mode.
+    pub simulate: Option<(bool, Source)>,
+    /// Maximum length of simulation traces, `None` for default.
+    pub depth: Option<(Option<u64>, Source)>,
+    /// Number of simulation traces to generate, `None` for unbounded.
+    pub num_traces: Option<(Option<u64>, Source)>,
+    /// Depth-first iterative deepening up to some depth, `None` for breadth-first search.
+    pub dfid: Option<(Option<u64>, Source)>,
+    /// Checkpoint interval in minutes, `None` for default.
+    pub checkpoint: Option<(Option<u64>, Source)>,
+    /// Coverage reporting interval in minutes, `None` to deactivate coverage.
+    pub coverage: Option<(Option<u64>, Source)>,
+    /// (De)activates checking after the first violation.
+    pub keep_going: Option<(bool, Source)>,
 }
 implem! {
     for TlcCla {
@@ -96,6 +136,13 @@ implem! {
                 check_deadlocks: Some((cla.check_deadlocks, source)),
                 print_callstack: Some((cla.print_callstack, source)),
                 timestats: Some((cla.timestats, source)),
+                simulate: Some((cla.simulate, source)),
+                depth: Some((cla.depth, source)),
+                num_traces: Some((cla.num_traces, source)),
+                dfid: Some((cla.dfid, source)),
+                checkpoint: Some((cla.checkpoint, source)),
+                coverage: Some((cla.coverage, source)),
+                keep_going: Some((cla.keep_going, source)),
             }
         }
     }
@@ -121,6 +168,13 @@ impl TlcCla {
         check_deadlocks: Option<bool>,
         print_callstack: Option<bool>,
         timestats: Option<bool>,
+        simulate: Option<bool>,
+        depth: Option<Option<u64>>,
+        num_traces: Option<Option<u64>>,
+        dfid: Option<Option<u64>>,
+        checkpoint: Option<Option<u64>>,
+        coverage: Option<Option<u64>>,
+        keep_going: Option<bool>,
     ) -> Self {
         let source = source.into();
         Self {
@@ -132,6 +186,13 @@ impl TlcCla {
             check_deadlocks: check_deadlocks.map(|val| (val, source)),
             print_callstack: print_callstack.map(|val| (val, source)),
             timestats: timestats.map(|val| (val, source)),
+            simulate: simulate.map(|val| (val, source)),
+            depth: depth.map(|val| (val, source)),
+            num_traces: num_traces.map(|val| (val, source)),
+            dfid: dfid.map(|val| (val, source)),
+            checkpoint: checkpoint.map(|val| (val, source)),
+            coverage: coverage.map(|val| (val, source)),
+            keep_going: keep_going.map(|val| (val, source)),
         }
     }
     /// Constructor with all fields set to `None`.
@@ -145,6 +206,13 @@ impl TlcCla {
             check_deadlocks: None,
             print_callstack: None,
             timestats: None,
+            simulate: None,
+            depth: None,
+            num_traces: None,
+            dfid: None,
+            checkpoint: None,
+            coverage: None,
+            keep_going: None,
         }
     }
 
@@ -229,6 +297,50 @@ impl TlcCla {
                 "If active, matla will present time statistics during runs",
                 Some(b) => if b { "on" } else { "off" },
             }
+            simulate: "'on'|'off'|'true'|'false'"(true) => {
+                "If active, TLC will check random behaviors instead of exploring all states",
+                Some(b) => if b { "on" } else { "off" },
+            }
+            depth: "u64|'default'"(true) => {
+                "Maximum length of the traces generated in simulation mode",
+                Some(depth) => match depth {
+                    Some(depth) => depth.to_string(),
+                    None => "default".into(),
+                },
+            }
+            num_traces: "u64|'default'"(true) => {
+                "Number of traces generated in simulation mode, unbounded by default",
+                Some(num) => match num {
+                    Some(num) => num.to_string(),
+                    None => "default".into(),
+                },
+            }
+            dfid: "u64|'off'"(true) => {
+                "If not `off`, TLC will use depth-first iterative deepening up to this depth",
+                Some(dfid) => match dfid {
+                    Some(dfid) => dfid.to_string(),
+                    None => "off".into(),
+                },
+            }
+            checkpoint: "u64|'default'"(true) => {
+                "Minutes between two TLC checkpoints, `0` deactivates checkpoints",
+                Some(checkpoint) => match checkpoint {
+                    Some(checkpoint) => checkpoint.to_string(),
+                    None => "default".into(),
+                },
+            }
+            coverage: "u64|'off'"(true) => {
+                "If not `off`, minutes between two TLC coverage reports",
+                Some(coverage) => match coverage {
+                    Some(coverage) => coverage.to_string(),
+                    None => "off".into(),
+                },
+            }
+            keep_going: "'on'|'off'|'true'|'false'"(true) => {
+                "If active, TLC keeps checking after the first violation and reports all of them \
+                (alias: `continue`)",
+                Some(b) => if b { "on" } else { "off" },
+            }
         }
 
         Ok(())
@@ -331,11 +443,81 @@ impl TlcCla {
                 "If active, matla will present time statistics during runs",
                 Some(b) => if b { "'on'" } else { "'off'" },
             }
+            simulate {
+                "If active, TLC will check random behaviors instead of exploring all states",
+                Some(b) => if b { "'on'" } else { "'off'" },
+            }
+            depth {
+                "Maximum length of the traces generated in simulation mode",
+                Some(depth) => match depth {
+                    Some(depth) => depth.to_string(),
+                    None => "'default'".into(),
+                },
+            }
+            num_traces {
+                "Number of traces generated in simulation mode, unbounded by default",
+                Some(num) => match num {
+                    Some(num) => num.to_string(),
+                    None => "'default'".into(),
+                },
+            }
+            dfid {
+                "If not `off`, TLC will use depth-first iterative deepening up to this depth",
+                Some(dfid) => match dfid {
+                    Some(dfid) => dfid.to_string(),
+                    None => "'off'".into(),
+                },
+            }
+            checkpoint {
+                "Minutes between two TLC checkpoints, `0` deactivates checkpoints",
+                Some(checkpoint) => match checkpoint {
+                    Some(checkpoint) => checkpoint.to_string(),
+                    None => "'default'".into(),
+                },
+            }
+            coverage {
+                "If not `off`, minutes between two TLC coverage reports",
+                Some(coverage) => match coverage {
+                    Some(coverage) => coverage.to_string(),
+                    None => "'off'".into(),
+                },
+            }
+            keep_going {
+                "If active, TLC keeps checking after the first violation and reports all of them \
+                (alias: `continue`)",
+                Some(b) => if b { "'on'" } else { "'off'" },
+            }
         }
 
         Ok(())
     }
 
+    /// Checks that the options are consistent.
+    ///
+    /// Fails if `simulate` is active along with `dfid`, as TLC cannot do both. Warns if `depth` is
+    /// set while `simulate` is not active, since TLC ignores it in that case.
+    pub fn check(&self) -> Res<()> {
+        let simulate = match self.simulate {
+            Some((true, source)) => Some(source),
+            Some((false, _)) | None => None,
+        };
+        if let (Some(simulate), Some((Some(_), dfid))) = (simulate, self.dfid) {
+            bail!(
+                "`simulate` (from {}) and `dfid` (from {}) are incompatible, \
+                TLC cannot simulate and use depth-first iterative deepening at the same time",
+                simulate,
+                dfid,
+            )
+        }
+        if let (None, Some((Some(_), depth))) = (simulate, self.depth) {
+            log::warn!(
+                "`depth` (from {}) is ignored since `simulate` is not active",
+                depth
+            )
+        }
+        Ok(())
+    }
+
     /// Overwrites non-`None` values of `self` with those of `that`.
     pub fn receive(&mut self, that: &Self) {
         // If you're getting an error here, it probably means the fields of `Self` have changed and
@@ -351,6 +533,13 @@ impl TlcCla {
             check_deadlocks,
             print_callstack,
             timestats,
+            simulate,
+            depth,
+            num_traces,
+            dfid,
+            checkpoint,
+            coverage,
+            keep_going,
         } = that;
         macro_rules! overwrite {
             ( $($field:ident),* $(,)? ) => (
@@ -370,6 +559,13 @@ impl TlcCla {
             check_deadlocks,
             print_callstack,
             timestats,
+            simulate,
+            depth,
+            num_traces,
+            dfid,
+            checkpoint,
+            coverage,
+            keep_going,
         );
     }
 }
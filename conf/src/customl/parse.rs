@@ -68,6 +68,9 @@ peg::parser! {
         rule or_auto<T>(sub: rule<T>) -> Option<T>
         = ("auto" / "Auto" / "_") { None }
         / sub:sub() { Some(sub) }
+        rule or_off<T>(sub: rule<T>) -> Option<T>
+        = ("off" / "Off" / "_") { None }
+        / sub:sub() { Some(sub) }
 
         // Parsers for the fields of [`crate::customl::TlcCla`].
         rule workers() -> Option<usize>
@@ -86,6 +89,21 @@ peg::parser! {
         = "print_callstack" _ "=" _ val:string_opt_of(<bool()>) { val }
         rule timestats() -> bool
         = "timestats" _ "=" _ val:string_opt_of(<bool()>) { val }
+        rule simulate() -> bool
+        = "simulate" _ "=" _ val:string_opt_of(<bool()>) { val }
+        rule depth() -> Option<u64>
+        = "depth" _ "=" _ val:string_opt_of(<or_default(<u64()>)>) { val }
+        rule num_traces() -> Option<u64>
+        = "num_traces" _ "=" _ val:string_opt_of(<or_default(<u64()>)>) { val }
+        rule dfid() -> Option<u64>
+        = "dfid" _ "=" _ val:string_opt_of(<or_off(<u64()>)>) { val }
+        rule checkpoint() -> Option<u64>
+        = "checkpoint" _ "=" _ val:string_opt_of(<or_default(<u64()>)>) { val }
+        rule coverage() -> Option<u64>
+        = "coverage" _ "=" _ val:string_opt_of(<or_off(<u64()>)>) { val }
+        // Also accepts `continue`, like TLC's own option.
+        rule keep_going() -> bool
+        = ("keep_going" / "continue") _ "=" _ val:string_opt_of(<bool()>) { val }
 
         // Parses a full [`crate::customl::TlcCla`].
         rule sub_tlc_cla(source: customl::Source, tlc_cla: &mut TlcClaToml)
@@ -114,10 +132,190 @@ peg::parser! {
             / val:timestats() {?
                 tlc_cla_error!(tlc_cla => timestats => val, source)
             }
+            / val:simulate() {?
+                tlc_cla_error!(tlc_cla => simulate => val, source)
+            }
+            / val:depth() {?
+                tlc_cla_error!(tlc_cla => depth => val, source)
+            }
+            / val:num_traces() {?
+                tlc_cla_error!(tlc_cla => num_traces => val, source)
+            }
+            / val:dfid() {?
+                tlc_cla_error!(tlc_cla => dfid => val, source)
+            }
+            / val:checkpoint() {?
+                tlc_cla_error!(tlc_cla => checkpoint => val, source)
+            }
+            / val:coverage() {?
+                tlc_cla_error!(tlc_cla => coverage => val, source)
+            }
+            / val:keep_going() {?
+                tlc_cla_error!(tlc_cla => keep_going => val, source)
+            }
         ) ** _
         rule section_tlc_cla(source: customl::Source, tlc_cla: &mut TlcClaToml)
         = "[" _ "tlc_cla" _ "]" _ sub_tlc_cla(source, tlc_cla)
 
+        // Parsers for the `[cex]` sections, see [`crate::cex_view`].
+        rule var_list() -> Set<String>
+        = "[" _ vars:(string_of(<ident()>) ** (_ "," _)) _ ","? _ "]" {
+            vars.into_iter().map(String::from).collect()
+        }
+        rule hint() -> cex_view::Hint
+        = hint:$([^ '"' | '\'' | '\n']*) {?
+            cex_view::Hint::parse(hint).map_err(|_| "cex hint (`table`, `fold` or `fold:<int>`)")
+        }
+        rule sub_cex_view(cex_view: &mut CexView)
+        = (
+            "only" _ "=" _ vars:var_list() {?
+                if cex_view.only.is_some() {
+                    return Err("trying to specify `only` twice");
+                }
+                cex_view.only = Some(vars);
+                Ok(())
+            }
+            / "hide" _ "=" _ vars:var_list() {?
+                if !cex_view.hide.is_empty() {
+                    return Err("trying to specify `hide` twice");
+                }
+                cex_view.hide = vars;
+                Ok(())
+            }
+            / "changed_only" _ "=" _ val:string_opt_of(<bool()>) {
+                cex_view.changed_only = val
+            }
+        ) ** _
+        rule section_cex(cex_view: &mut CexView)
+        = "[" _ "cex" _ "]" _ sub_cex_view(cex_view)
+        rule section_cex_hints(cex_view: &mut CexView)
+        = "[" _ "cex" _ "." _ "hints" _ "]" _ (
+            var:ident() _ "=" _ hint:string_of(<hint()>) {?
+                match cex_view.hints.insert(var.into(), hint) {
+                    None => Ok(()),
+                    Some(_) => Err("trying to specify the hint of a variable twice"),
+                }
+            }
+        ) ** _
+
+        // Parsers for the `[modules.<module>]` sections, see [`crate::module_conf`].
+        rule module_conf_entry() -> (&'input str, &'input str)
+        = key:$("alias" / "view") _ "=" _ op:string_of(<ident()>) { (key, op) }
+        rule section_module(modules: &mut Map<String, ModuleConf>)
+        = "[" _ "modules" _ "." _ module:ident() _ "]" _ entries:(module_conf_entry() ** _) {?
+            let mut conf = ModuleConf::new();
+            for (key, op) in entries {
+                let prev = match key {
+                    "alias" => conf.alias.replace(op.into()),
+                    _ => conf.view.replace(op.into()),
+                };
+                if prev.is_some() {
+                    return Err("trying to specify `alias` or `view` twice");
+                }
+            }
+            match modules.insert(module.into(), conf) {
+                None => Ok(()),
+                Some(_) => Err("trying to specify the configuration of a module twice"),
+            }
+        }
+
+        // Parsers for the `[launcher]` sections, see [`crate::launcher`].
+        //
+        // Double-quoted strings support `\"` and `\\`, other backslashes are kept as is.
+        rule str_lit() -> String
+        = "\"" chars:(
+            "\\\"" { '"' }
+            / "\\\\" { '\\' }
+            / c:[^ '"' | '\n'] { c }
+        )* "\"" {
+            chars.into_iter().collect()
+        }
+        / "'" s:$([^ '\'' | '\n']*) "'" { s.into() }
+        rule str_list() -> Vec<String>
+        = "[" _ items:(str_lit() ** (_ "," _)) _ ","? _ "]" { items }
+        rule sub_launcher(launcher: &mut Launcher)
+        = (
+            "java" _ "=" _ val:str_lit() {?
+                match launcher.java.replace(val) {
+                    None => Ok(()),
+                    Some(_) => Err("trying to specify `java` twice"),
+                }
+            }
+            / "heap" _ "=" _ val:str_lit() {?
+                match launcher.heap.replace(val) {
+                    None => Ok(()),
+                    Some(_) => Err("trying to specify `heap` twice"),
+                }
+            }
+            / "jvm_flags" _ "=" _ vals:str_list() {?
+                if !launcher.jvm_flags.is_empty() {
+                    return Err("trying to specify `jvm_flags` twice");
+                }
+                launcher.jvm_flags = vals;
+                Ok(())
+            }
+            / "classpath" _ "=" _ vals:str_list() {?
+                if !launcher.classpath.is_empty() {
+                    return Err("trying to specify `classpath` twice");
+                }
+                launcher.classpath = vals;
+                Ok(())
+            }
+            / "command" _ "=" _ vals:str_list() {?
+                if vals.is_empty() {
+                    return Err("launcher `command` cannot be empty");
+                }
+                match launcher.command.replace(vals) {
+                    None => Ok(()),
+                    Some(_) => Err("trying to specify `command` twice"),
+                }
+            }
+        ) ** _
+        rule section_launcher(launcher: &mut Launcher)
+        = "[" _ "launcher" _ "]" _ sub_launcher(launcher)
+        rule property_key() -> String
+        = str_lit()
+        / key:$(['a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '.']+) { key.into() }
+        rule section_launcher_properties(launcher: &mut Launcher)
+        = "[" _ "launcher" _ "." _ "properties" _ "]" _ (
+            key:property_key() _ "=" _ val:str_lit() {?
+                match launcher.properties.insert(key, val) {
+                    None => Ok(()),
+                    Some(_) => Err("trying to specify the value of a property twice"),
+                }
+            }
+        ) ** _
+
+        rule sub_install(install: &mut Install)
+        = (
+            "source" _ "=" _ val:str_lit() {?
+                match install.source.replace(val) {
+                    None => Ok(()),
+                    Some(_) => Err("trying to specify `source` twice"),
+                }
+            }
+            / "sha256" _ "=" _ val:str_lit() {?
+                match install.sha256.replace(val) {
+                    None => Ok(()),
+                    Some(_) => Err("trying to specify `sha256` twice"),
+                }
+            }
+            / "installed_source" _ "=" _ val:str_lit() {?
+                match install.installed_source.replace(val) {
+                    None => Ok(()),
+                    Some(_) => Err("trying to specify `installed_source` twice"),
+                }
+            }
+            / "installed_sha256" _ "=" _ val:str_lit() {?
+                match install.installed_sha256.replace(val) {
+                    None => Ok(()),
+                    Some(_) => Err("trying to specify `installed_sha256` twice"),
+                }
+            }
+        ) ** _
+        rule section_install(install: &mut Install)
+        = "[" _ "install" _ "]" _ sub_install(install)
+
         // Parses the toolchain part of a user's config.
         rule section_toolchain(target: &mut io::PathBuf)
         = "[" _ "config" _ "]" _
@@ -127,13 +325,48 @@ peg::parser! {
         // Parses the config part of a project's config
         rule section_project()
         = "[" _ "project" _ "]"
+        // Parses the toolchain pin of a project's config, see [`crate::toolchain_store`].
+        rule project_toolchain(toolchain: &mut Option<String>)
+        = "toolchain" _ "=" _ name:string_of(<toolchain_name()>) {
+            *toolchain = Some(name.into())
+        }
+        rule toolchain_name() -> &'input str
+        = name:$(['a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '.']+) { name }
 
         // Parses the user's toml config file.
-        pub rule user(path: &mut io::PathBuf, tlc_cla: &mut TlcClaToml)
+        //
+        // The `[config]` and `[tlc_cla]` sections come first, the other ones in any order.
+        pub rule user(
+            path: &mut io::PathBuf,
+            tlc_cla: &mut TlcClaToml,
+            launcher: &mut Launcher,
+            install: &mut Install,
+        )
         = _ section_toolchain(path) _ section_tlc_cla((customl::Source::User), tlc_cla) _
+        ((
+            section_launcher_properties(launcher)
+            / section_launcher(launcher)
+            / section_install(install)
+        ) _)*
 
         // Parses the project's toml config file.
-        pub rule project(tlc_cla: &mut TlcClaToml)
-        = _ section_project() _ sub_tlc_cla((customl::Source::Project), tlc_cla) _
+        //
+        // The `[project]` section comes first, the other ones in any order.
+        pub rule project(
+            toolchain: &mut Option<String>,
+            tlc_cla: &mut TlcClaToml,
+            cex_view: &mut CexView,
+            launcher: &mut Launcher,
+            modules: &mut Map<String, ModuleConf>,
+        )
+        = _ section_project() _ (project_toolchain(toolchain) _)?
+        sub_tlc_cla((customl::Source::Project), tlc_cla) _
+        ((
+            section_cex_hints(cex_view)
+            / section_cex(cex_view)
+            / section_launcher_properties(launcher)
+            / section_launcher(launcher)
+            / section_module(modules)
+        ) _)*
     }
 }
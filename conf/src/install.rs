@@ -0,0 +1,249 @@
+//! Installation of the `tla2tools` jar by `matla setup` and `matla update`.
+//!
+//! By default, matla downloads the latest release of the TLA+ toolbox from [`DEFAULT_SOURCE`].
+//! The `[install]` section of the user's configuration file can specify another [`Source`], *e.g.*
+//! a mirror or a local jar, and the SHA-256 hash the jar must have:
+//!
+//! ```toml
+//! [install]
+//! source = "file:///mnt/mirror/tlaplus/tla2tools.jar"
+//! sha256 = "4ae5e2b8c3de8f0f1a0ff1d1e1bd5e0b6a34de5e4e4a4ad5a9a3b2c8f5e1b0c2"
+//! ```
+//!
+//! Environment variables [`SOURCE_ENV_VAR`] and [`SHA256_ENV_VAR`] take precedence over the
+//! configuration file, which is convenient on machines that do not have one yet. When matla
+//! installs a jar, it records its source and hash in this section as `installed_source` and
+//! `installed_sha256`.
+
+prelude!();
+
+use crate::customl::toml_str;
+
+/// URL of the latest release of the TLA toolbox, the default installation source.
+pub const DEFAULT_SOURCE: &str =
+    "https://github.com/tlaplus/tlaplus/releases/latest/download/tla2tools.jar";
+/// Environment variable overriding [`Install::source`].
+pub const SOURCE_ENV_VAR: &str = "MATLA_TOOLBOX_SOURCE";
+/// Environment variable overriding [`Install::sha256`].
+pub const SHA256_ENV_VAR: &str = "MATLA_TOOLBOX_SHA256";
+
+/// Where to install the `tla2tools` jar from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// An `http` or `https` URL.
+    Url(String),
+    /// A local file, given as a path or a `file://` URL.
+    File(io::PathBuf),
+}
+impl Source {
+    /// Parses a source.
+    ///
+    /// ```rust
+    /// # use conf::install::Source;
+    /// assert_eq!(
+    ///     Source::parse("https://mirror.org/tla2tools.jar").unwrap(),
+    ///     Source::Url("https://mirror.org/tla2tools.jar".into()),
+    /// );
+    /// assert_eq!(
+    ///     Source::parse("file:///mnt/mirror/tla2tools.jar").unwrap(),
+    ///     Source::File("/mnt/mirror/tla2tools.jar".into()),
+    /// );
+    /// assert_eq!(
+    ///     Source::parse("jars/tla2tools.jar").unwrap(),
+    ///     Source::File("jars/tla2tools.jar".into()),
+    /// );
+    /// assert!(Source::parse("ftp://mirror.org/tla2tools.jar").is_err());
+    /// ```
+    pub fn parse(source: &str) -> Res<Self> {
+        let source = source.trim();
+        if let Some(path) = source.strip_prefix("file://") {
+            Ok(Self::File(path.into()))
+        } else if source.starts_with("http://") || source.starts_with("https://") {
+            Ok(Self::Url(source.into()))
+        } else if let Some((scheme, _)) = source.split_once("://") {
+            bail!(
+                "unsupported scheme `{}` in toolbox source `{}`, expected `http`, `https` or `file`",
+                scheme,
+                source
+            )
+        } else {
+            Ok(Self::File(source.into()))
+        }
+    }
+
+    /// Retrieves the content of the jar.
+    pub fn fetch(&self) -> Res<Vec<u8>> {
+        match self {
+            Self::Url(url) => {
+                let body = io::download(url)?
+                    .error_for_status()
+                    .with_context(|| anyhow!("downloading `{}`", url))?
+                    .bytes()
+                    .with_context(|| anyhow!("accessing the body of `{}`", url))?;
+                Ok(body.to_vec())
+            }
+            Self::File(path) => std::fs::read(path)
+                .with_context(|| anyhow!("failed to read toolbox jar `{}`", path.display())),
+        }
+    }
+}
+implem! {
+    for Source {
+        Display {
+            |&self, fmt| match self {
+                Self::Url(url) => url.fmt(fmt),
+                Self::File(path) => path.display().fmt(fmt),
+            }
+        }
+    }
+}
+
+/// Installation configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Install {
+    /// Source to install the jar from, [`DEFAULT_SOURCE`] if none.
+    pub source: Option<String>,
+    /// SHA-256 hash the jar must have, if any.
+    pub sha256: Option<String>,
+    /// Source of the installed jar, if matla installed it.
+    pub installed_source: Option<String>,
+    /// SHA-256 hash of the installed jar, if matla installed it.
+    pub installed_sha256: Option<String>,
+}
+impl Install {
+    /// Constructor, installs from [`DEFAULT_SOURCE`] without checking the hash of the jar.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True if the configuration does not change anything to the default one.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Source to install the jar from.
+    ///
+    /// From [`SOURCE_ENV_VAR`] if set, [`Self::source`] otherwise, [`DEFAULT_SOURCE`] if none.
+    pub fn source(&self) -> Res<Source> {
+        match std::env::var(SOURCE_ENV_VAR) {
+            Ok(source) if !source.trim().is_empty() => Source::parse(&source)
+                .with_context(|| anyhow!("illegal value for `{}`", SOURCE_ENV_VAR)),
+            _ => Source::parse(self.source.as_deref().unwrap_or(DEFAULT_SOURCE)),
+        }
+    }
+
+    /// SHA-256 hash the jar must have, from [`SHA256_ENV_VAR`] if set, [`Self::sha256`] otherwise.
+    pub fn expected_sha256(&self) -> Option<String> {
+        match std::env::var(SHA256_ENV_VAR) {
+            Ok(sha256) if !sha256.trim().is_empty() => Some(sha256.trim().to_lowercase()),
+            _ => self
+                .sha256
+                .as_ref()
+                .map(|sha256| sha256.trim().to_lowercase()),
+        }
+    }
+
+    /// Checks the hash of a jar against the [expected one][Self::expected_sha256], if any.
+    ///
+    /// Returns the hash of the jar.
+    ///
+    /// ```rust
+    /// # use conf::install::Install;
+    /// let mut install = Install::new();
+    /// install.sha256 = Some("66D744EFB075E047089930F4DF64D2F560CA152AC3222D3DCDF42DFE1D415C31".into());
+    /// assert_eq!(
+    ///     install.check_sha256(b"matla").unwrap(),
+    ///     "66d744efb075e047089930f4df64d2f560ca152ac3222d3dcdf42dfe1d415c31",
+    /// );
+    /// assert!(install.check_sha256(b"not matla").is_err());
+    /// ```
+    pub fn check_sha256(&self, jar: &[u8]) -> Res<String> {
+        let sha256 = io::sha256(jar);
+        if let Some(expected) = self.expected_sha256() {
+            if expected != sha256 {
+                bail!(
+                    "SHA-256 mismatch, expected `{}` but the jar's hash is `{}`",
+                    expected,
+                    sha256
+                )
+            }
+        }
+        Ok(sha256)
+    }
+
+    /// Fetches the jar from its [source][Self::source] and [checks its hash][Self::check_sha256].
+    ///
+    /// Returns the jar, and records its source and hash as the installed ones. Does not write
+    /// anything to the disk.
+    pub fn fetch(&mut self) -> Res<Vec<u8>> {
+        let source = self.source()?;
+        let jar = source.fetch()?;
+        let sha256 = self
+            .check_sha256(&jar)
+            .with_context(|| anyhow!("refusing to install toolbox from `{}`", source))?;
+        self.installed_source = Some(source.to_string());
+        self.installed_sha256 = Some(sha256);
+        Ok(jar)
+    }
+
+    /// Serializes itself as a toml `[install]` section, does nothing if [`Self::is_empty`].
+    ///
+    /// Strings are written with [`toml_str`], fails if a string contains a newline.
+    ///
+    /// ```rust
+    /// # use conf::install::Install;
+    /// let mut install = Install::new();
+    /// install.source = Some("file:///mnt/mirror/tla2tools.jar".into());
+    /// install.installed_sha256 = Some("66d744ef".into());
+    /// let mut bytes = vec![];
+    /// install.ser_toml(&mut bytes).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(bytes).unwrap(),
+    ///     "\
+    /// [install]
+    /// source = \"file:///mnt/mirror/tla2tools.jar\"
+    /// installed_sha256 = \"66d744ef\"
+    /// ",
+    /// );
+    /// ```
+    ///
+    /// Quotes and backslashes survive a round trip through a configuration file:
+    ///
+    /// ```rust
+    /// # use conf::{customl::TlcCla, install::Install, toolchain::Toolchain};
+    /// let mut install = Install::new();
+    /// install.source = Some(r#"C:\mirror\it's "tla2tools".jar"#.into());
+    /// let toolchain = Toolchain {
+    ///     tla2tools: "tla2tools.jar".into(),
+    ///     tlc_cla: TlcCla::none(),
+    ///     launcher: Default::default(),
+    ///     install: install.clone(),
+    /// };
+    /// let mut bytes = vec![];
+    /// toolchain.ser_toml(&mut bytes).unwrap();
+    /// let toml = String::from_utf8(bytes).unwrap();
+    /// assert!(toml.contains(r#"source = "C:\\mirror\\it's \"tla2tools\".jar""#));
+    /// assert_eq!(Toolchain::de_toml(&toml).unwrap().install, install);
+    ///
+    /// install.sha256 = Some("66d744ef\n".into());
+    /// assert!(install.ser_toml(&mut vec![]).is_err());
+    /// ```
+    pub fn ser_toml(&self, w: &mut impl io::Write) -> Res<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        writeln!(w, "[install]")?;
+        let items = [
+            ("source", &self.source),
+            ("sha256", &self.sha256),
+            ("installed_source", &self.installed_source),
+            ("installed_sha256", &self.installed_sha256),
+        ];
+        for (key, val) in items {
+            if let Some(val) = val.as_ref() {
+                writeln!(w, "{} = {}", key, toml_str(val)?)?;
+            }
+        }
+        Ok(())
+    }
+}
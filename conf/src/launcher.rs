@@ -0,0 +1,263 @@
+//! TLC launcher configuration.
+//!
+//! Specifies how matla launches the JVM running the `tla2tools` jar, see [`Launcher`]. It comes
+//! from the `[launcher]` section of the user's and the project's configuration files, the latter
+//! taking precedence:
+//!
+//! ```toml
+//! [launcher]
+//! java = "/usr/lib/jvm/java-17-openjdk/bin/java"
+//! heap = "8g"
+//! jvm_flags = ["-XX:+UseG1GC", "-Xss4m"]
+//! classpath = ["lib/CommunityModules-deps.jar"]
+//!
+//! [launcher.properties]
+//! "tlc2.TLC.stopAfter" = "3600"
+//! ```
+//!
+//! Alternatively, `command` replaces the whole java invocation when launching TLC, *e.g.* with a
+//! native TLC executable or a wrapper script. TLC's arguments are appended to it as usual.
+//!
+//! ```toml
+//! [launcher]
+//! command = ["scripts/tlc.sh", "--fast"]
+//! ```
+//!
+//! Relative paths in the project's configuration file are relative to the project's directory.
+
+prelude!();
+
+use crate::customl::toml_str;
+
+/// Java executable used when none is specified.
+pub const JAVA_DEFAULT: &str = "java";
+/// Garbage collector flag used unless [`Launcher::jvm_flags`] selects one.
+pub const GC_DEFAULT: &str = "-XX:+UseParallelGC";
+
+/// JVM launcher configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Launcher {
+    /// Java executable, [`JAVA_DEFAULT`] if none.
+    pub java: Option<String>,
+    /// Maximum heap size, passed as `-Xmx<heap>`.
+    pub heap: Option<String>,
+    /// Additional JVM flags.
+    pub jvm_flags: Vec<String>,
+    /// Classpath entries, placed before the `tla2tools` jar so that they can override its modules.
+    pub classpath: Vec<String>,
+    /// Java system properties, passed as `-D<key>=<value>`.
+    pub properties: Map<String, String>,
+    /// Custom command launching TLC instead of the JVM, if any.
+    pub command: Option<Vec<String>>,
+}
+impl Launcher {
+    /// Constructor, launches the JVM with matla's default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True if the launcher does not change anything to the default one.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Receives the items of a launcher with higher priority.
+    ///
+    /// Its java executable, heap and command replace the ones in `self`, its properties override
+    /// the ones in `self`. Its JVM flags come after the ones in `self`, and its classpath entries
+    /// before.
+    pub fn receive(&mut self, other: &Self) {
+        if let Some(java) = other.java.as_ref() {
+            self.java = Some(java.clone())
+        }
+        if let Some(heap) = other.heap.as_ref() {
+            self.heap = Some(heap.clone())
+        }
+        self.jvm_flags.extend(other.jvm_flags.iter().cloned());
+        let classpath = mem::take(&mut self.classpath);
+        self.classpath = other.classpath.iter().cloned().chain(classpath).collect();
+        self.properties.extend(
+            other
+                .properties
+                .iter()
+                .map(|(key, val)| (key.clone(), val.clone())),
+        );
+        if let Some(command) = other.command.as_ref() {
+            self.command = Some(command.clone())
+        }
+    }
+
+    /// Makes the relative paths of the launcher relative to some directory.
+    ///
+    /// Java and command executables are only affected if they mention a directory, so that plain
+    /// executable names are still looked up in the `PATH`.
+    ///
+    /// ```rust
+    /// # use conf::launcher::Launcher;
+    /// let mut launcher = Launcher::new();
+    /// launcher.java = Some("java".into());
+    /// launcher.classpath = vec!["lib/deps.jar".into()];
+    /// launcher.command = Some(vec!["scripts/tlc.sh".into(), "--fast".into()]);
+    /// launcher.resolve("/project");
+    /// assert_eq!(launcher.java.as_deref(), Some("java"));
+    /// assert_eq!(launcher.classpath, vec!["/project/lib/deps.jar"]);
+    /// assert_eq!(
+    ///     launcher.command,
+    ///     Some(vec!["/project/scripts/tlc.sh".into(), "--fast".into()]),
+    /// );
+    /// ```
+    pub fn resolve(&mut self, dir: impl AsRef<io::Path>) {
+        let dir = dir.as_ref();
+        let resolve = |path: &mut String| {
+            let as_path = io::Path::new(path.as_str());
+            if as_path.is_relative() {
+                *path = dir.join(as_path).display().to_string();
+            }
+        };
+        let has_dir = |path: &str| io::Path::new(path).components().count() > 1;
+        if let Some(java) = self.java.as_mut().filter(|java| has_dir(java)) {
+            resolve(java)
+        }
+        self.classpath.iter_mut().for_each(resolve);
+        if let Some(exe) = self.command.as_mut().and_then(|cmd| cmd.first_mut()) {
+            if has_dir(exe) {
+                resolve(exe)
+            }
+        }
+    }
+
+    /// Java call to some main class of the `tla2tools` jar.
+    ///
+    /// ```rust
+    /// # use conf::launcher::Launcher;
+    /// let mut launcher = Launcher::new();
+    /// launcher.heap = Some("8g".into());
+    /// launcher.jvm_flags = vec!["-XX:+UseG1GC".into()];
+    /// let _ = launcher.properties.insert("tlc2.TLC.stopAfter".into(), "60".into());
+    /// let cmd = launcher.java_cmd("tla2tools.jar", "tlc2.TLC").unwrap();
+    /// assert_eq!(
+    ///     format!("{:?}", cmd),
+    ///     r#""java" "-Xmx8g" "-XX:+UseG1GC" "-Dtlc2.TLC.stopAfter=60" "-cp" "tla2tools.jar" "tlc2.TLC""#,
+    /// );
+    /// ```
+    pub fn java_cmd(&self, tla2tools: impl AsRef<io::Path>, main_class: &str) -> Res<io::Command> {
+        let mut cmd = io::Command::new(self.java.as_deref().unwrap_or(JAVA_DEFAULT));
+        let selects_gc = self
+            .jvm_flags
+            .iter()
+            .any(|flag| flag.starts_with("-XX:+Use") && flag.ends_with("GC"));
+        if !selects_gc {
+            cmd.arg(GC_DEFAULT);
+        }
+        if let Some(heap) = self.heap.as_ref() {
+            cmd.arg(format!("-Xmx{}", heap));
+        }
+        cmd.args(&self.jvm_flags);
+        for (key, val) in self.properties.iter() {
+            cmd.arg(format!("-D{}={}", key, val));
+        }
+        let classpath = self
+            .classpath
+            .iter()
+            .map(io::Path::new)
+            .chain(Some(tla2tools.as_ref()));
+        let classpath = std::env::join_paths(classpath).context("illegal classpath entry")?;
+        cmd.arg("-cp").arg(classpath).arg(main_class);
+        Ok(cmd)
+    }
+
+    /// Command launching TLC, [`Self::command`] if any, a [java call][Self::java_cmd] otherwise.
+    pub fn tlc_cmd(&self, tla2tools: impl AsRef<io::Path>) -> Res<io::Command> {
+        match self.command.as_ref().map(|cmd| cmd.split_first()) {
+            Some(Some((exe, args))) => {
+                let mut cmd = io::Command::new(exe);
+                cmd.args(args);
+                Ok(cmd)
+            }
+            Some(None) => bail!("illegal launcher, `command` cannot be empty"),
+            None => self.java_cmd(tla2tools, "tlc2.TLC"),
+        }
+    }
+
+    /// Serializes itself as a toml `[launcher]` section, does nothing if [`Self::is_empty`].
+    ///
+    /// Strings are written with [`toml_str`], fails if a string contains a newline.
+    ///
+    /// ```rust
+    /// # use conf::launcher::Launcher;
+    /// let mut launcher = Launcher::new();
+    /// launcher.heap = Some("8g".into());
+    /// launcher.classpath = vec!["lib/deps.jar".into()];
+    /// let _ = launcher.properties.insert("tlc2.TLC.stopAfter".into(), "60".into());
+    /// let mut bytes = vec![];
+    /// launcher.ser_toml(&mut bytes).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(bytes).unwrap(),
+    ///     "\
+    /// [launcher]
+    /// heap = \"8g\"
+    /// classpath = [\"lib/deps.jar\"]
+    ///
+    /// [launcher.properties]
+    /// \"tlc2.TLC.stopAfter\" = \"60\"
+    /// ",
+    /// );
+    /// ```
+    ///
+    /// Quotes and backslashes survive a round trip through a configuration file:
+    ///
+    /// ```rust
+    /// # use conf::{customl::TlcCla, launcher::Launcher, toolchain::Toolchain};
+    /// let mut launcher = Launcher::new();
+    /// launcher.jvm_flags = vec![r#"-Dgreeting="it's \o/""#.into()];
+    /// let toolchain = Toolchain {
+    ///     tla2tools: "tla2tools.jar".into(),
+    ///     tlc_cla: TlcCla::none(),
+    ///     launcher: launcher.clone(),
+    ///     install: Default::default(),
+    /// };
+    /// let mut bytes = vec![];
+    /// toolchain.ser_toml(&mut bytes).unwrap();
+    /// let toml = String::from_utf8(bytes).unwrap();
+    /// assert!(toml.contains(r#"jvm_flags = ["-Dgreeting=\"it's \\o/\""]"#));
+    /// assert_eq!(Toolchain::de_toml(&toml).unwrap().launcher, launcher);
+    ///
+    /// launcher.heap = Some("8g\n".into());
+    /// assert!(launcher.ser_toml(&mut vec![]).is_err());
+    /// ```
+    pub fn ser_toml(&self, w: &mut impl io::Write) -> Res<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let list = |items: &[String]| -> Res<String> {
+            let items = items
+                .iter()
+                .map(|item| toml_str(item))
+                .collect::<Res<Vec<_>>>()?;
+            Ok(items.join(", "))
+        };
+        writeln!(w, "[launcher]")?;
+        if let Some(java) = self.java.as_ref() {
+            writeln!(w, "java = {}", toml_str(java)?)?;
+        }
+        if let Some(heap) = self.heap.as_ref() {
+            writeln!(w, "heap = {}", toml_str(heap)?)?;
+        }
+        if !self.jvm_flags.is_empty() {
+            writeln!(w, "jvm_flags = [{}]", list(&self.jvm_flags)?)?;
+        }
+        if !self.classpath.is_empty() {
+            writeln!(w, "classpath = [{}]", list(&self.classpath)?)?;
+        }
+        if let Some(command) = self.command.as_ref() {
+            writeln!(w, "command = [{}]", list(command)?)?;
+        }
+        if !self.properties.is_empty() {
+            writeln!(w, "\n[launcher.properties]")?;
+            for (key, val) in self.properties.iter() {
+                writeln!(w, "{} = {}", toml_str(key)?, toml_str(val)?)?;
+            }
+        }
+        Ok(())
+    }
+}
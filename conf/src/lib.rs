@@ -4,6 +4,11 @@
 //! - [`top_cla`]: top-level options;
 //! - [`user`]: global user configuration;
 //! - [`project`]: project-level configuration;
+//! - [`cex_view`]: counterexample display configuration;
+//! - [`module_conf`]: per-module configuration, *e.g.* TLC's `ALIAS` and `VIEW`;
+//! - [`launcher`]: how TLC is launched, *e.g.* the java executable and JVM flags;
+//! - [`install`]: where `matla setup` and `matla update` install the `tla2tools` jar from;
+//! - [`toolchain_store`]: local store of named `tla2tools` jars projects can pin;
 //! - [`target`]: handles build paths.
 //!
 //! Feature-wise, this crate maintains a global [`Conf`] that other crates can use whenever they
@@ -22,11 +27,16 @@ pub extern crate toml;
 #[macro_use]
 pub mod prelude;
 
+pub mod cex_view;
 pub mod customl;
 pub(crate) mod glob;
+pub mod install;
+pub mod launcher;
+pub mod module_conf;
 pub mod project;
 pub mod target;
 pub mod toolchain;
+pub mod toolchain_store;
 pub mod top_cla;
 pub mod user;
 
@@ -213,6 +223,25 @@ pub struct TlcCla {
     pub print_callstack: bool,
     /// (De)activates timestats-printing.
     pub timestats: bool,
+    /// (De)activates simulation mode: random behaviors instead of exhaustive exploration.
+    pub simulate: bool,
+    /// Maximum length of simulation traces, `None` for default.
+    pub depth: Option<u64>,
+    /// Number of simulation traces to generate, `None` for unbounded.
+    pub num_traces: Option<u64>,
+    /// Depth-first iterative deepening up to some depth, `None` for breadth-first search.
+    pub dfid: Option<u64>,
+    /// Checkpoint interval in minutes, `None` for default, `0` deactivates checkpoints.
+    pub checkpoint: Option<u64>,
+    /// Coverage reporting interval in minutes, `None` to deactivate coverage.
+    pub coverage: Option<u64>,
+    /// (De)activates checking after the first violation, collecting all counterexamples.
+    pub keep_going: bool,
+    /// Checkpoint directory to recover from, if any.
+    ///
+    /// Only set for a specific run, never (de)serialized.
+    #[serde(skip)]
+    pub recover: Option<io::PathBuf>,
 }
 impl TlcCla {
     /// Turns itself into a customl TLC command-line options.
@@ -233,6 +262,13 @@ implem! {
                     check_deadlocks,
                     print_callstack,
                     timestats,
+                    simulate,
+                    depth,
+                    num_traces,
+                    dfid,
+                    checkpoint,
+                    coverage,
+                    keep_going,
                 } = toml;
                 let mut slf = Self::default();
                 workers.map(|(val, _)| slf.workers = val);
@@ -243,6 +279,13 @@ implem! {
                 check_deadlocks.map(|(val, _)| slf.check_deadlocks = val);
                 print_callstack.map(|(val, _)| slf.print_callstack = val);
                 timestats.map(|(val, _)| slf.timestats = val);
+                simulate.map(|(val, _)| slf.simulate = val);
+                depth.map(|(val, _)| slf.depth = val);
+                num_traces.map(|(val, _)| slf.num_traces = val);
+                dfid.map(|(val, _)| slf.dfid = val);
+                checkpoint.map(|(val, _)| slf.checkpoint = val);
+                coverage.map(|(val, _)| slf.coverage = val);
+                keep_going.map(|(val, _)| slf.keep_going = val);
                 slf
             }
         }
@@ -259,6 +302,14 @@ impl Default for TlcCla {
             check_deadlocks: true,
             print_callstack: false,
             timestats: false,
+            simulate: false,
+            depth: None,
+            num_traces: None,
+            dfid: None,
+            checkpoint: None,
+            coverage: None,
+            keep_going: false,
+            recover: None,
         }
     }
 }
@@ -293,6 +344,46 @@ impl TlcCla {
         self.check_deadlocks = check_deadlocks;
         self
     }
+    /// Sets the [`Self::simulate`] flag.
+    pub fn simulate(mut self, simulate: bool) -> Self {
+        self.simulate = simulate;
+        self
+    }
+    /// Sets the maximum length of simulation traces, `None` for default.
+    pub fn depth(mut self, depth: Option<u64>) -> Self {
+        self.depth = depth;
+        self
+    }
+    /// Sets the number of simulation traces, `None` for unbounded.
+    pub fn num_traces(mut self, num_traces: Option<u64>) -> Self {
+        self.num_traces = num_traces;
+        self
+    }
+    /// Sets the depth of depth-first iterative deepening, `None` for breadth-first search.
+    pub fn dfid(mut self, dfid: Option<u64>) -> Self {
+        self.dfid = dfid;
+        self
+    }
+    /// Sets the checkpoint interval in minutes, `None` for default.
+    pub fn checkpoint(mut self, checkpoint: Option<u64>) -> Self {
+        self.checkpoint = checkpoint;
+        self
+    }
+    /// Sets the coverage reporting interval in minutes, `None` to deactivate coverage.
+    pub fn coverage(mut self, coverage: Option<u64>) -> Self {
+        self.coverage = coverage;
+        self
+    }
+    /// Sets the [`Self::keep_going`] flag.
+    pub fn keep_going(mut self, keep_going: bool) -> Self {
+        self.keep_going = keep_going;
+        self
+    }
+    /// Sets the checkpoint directory to recover from.
+    pub fn recover(mut self, recover: Option<io::PathBuf>) -> Self {
+        self.recover = recover;
+        self
+    }
 
     /// Applies the arguments to an actual command.
     pub fn apply(&self, tlc_cmd: &mut io::Command) {
@@ -320,6 +411,31 @@ impl TlcCla {
         if !self.check_deadlocks {
             tlc_cmd.arg("-deadlock");
         }
+        if self.simulate {
+            tlc_cmd.arg("-simulate");
+            if let Some(num_traces) = self.num_traces {
+                tlc_cmd.arg(format!("num={}", num_traces));
+            }
+            if let Some(depth) = self.depth {
+                tlc_cmd.args(["-depth", &depth.to_string()]);
+            }
+        }
+        if let Some(dfid) = self.dfid {
+            tlc_cmd.args(["-dfid", &dfid.to_string()]);
+        }
+        if let Some(checkpoint) = self.checkpoint {
+            tlc_cmd.args(["-checkpoint", &checkpoint.to_string()]);
+        }
+        if let Some(coverage) = self.coverage {
+            tlc_cmd.args(["-coverage", &coverage.to_string()]);
+        }
+        if self.keep_going {
+            tlc_cmd.arg("-continue");
+        }
+        if let Some(recover) = self.recover.as_ref() {
+            tlc_cmd.arg("-recover");
+            tlc_cmd.arg(recover);
+        }
     }
 
     /// [`Spawns`](io::Command::spawn) a TLC process.
@@ -0,0 +1,73 @@
+//! Per-module configuration.
+//!
+//! Specifies TLC's `ALIAS` and `VIEW` for a module, see [`ModuleConf`]. It comes from the
+//! `[modules.<module>]` sections of the project's configuration file:
+//!
+//! ```toml
+//! [modules.sw]
+//! alias = "DebugAlias"
+//! view = "StateView"
+//! ```
+//!
+//! Both are operators of the module. The alias is a record TLC displays in traces instead of the
+//! state variables; the view is an expression TLC fingerprints instead of the whole state, which
+//! abstracts away the variables it does not mention.
+
+prelude!();
+
+/// Configuration of a module.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleConf {
+    /// Operator TLC displays in traces instead of the state variables, if any.
+    pub alias: Option<String>,
+    /// Operator TLC uses to fingerprint states, if any.
+    pub view: Option<String>,
+}
+impl ModuleConf {
+    /// Constructor, no alias and no view.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True if the configuration has no alias and no view.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Lines to add to the module's cfg file.
+    ///
+    /// ```rust
+    /// # use conf::module_conf::ModuleConf;
+    /// let mut conf = ModuleConf::new();
+    /// assert!(conf.cfg_lines().is_empty());
+    /// conf.alias = Some("DebugAlias".into());
+    /// conf.view = Some("StateView".into());
+    /// assert_eq!(conf.cfg_lines(), vec!["ALIAS DebugAlias", "VIEW StateView"]);
+    /// ```
+    pub fn cfg_lines(&self) -> Vec<String> {
+        let mut lines = Vec::with_capacity(2);
+        if let Some(alias) = self.alias.as_ref() {
+            lines.push(format!("ALIAS {}", alias));
+        }
+        if let Some(view) = self.view.as_ref() {
+            lines.push(format!("VIEW {}", view));
+        }
+        lines
+    }
+
+    /// Serializes itself as a toml `[modules.<module>]` section, does nothing if
+    /// [`Self::is_empty`].
+    pub fn ser_toml(&self, module: &str, w: &mut impl io::Write) -> Res<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        writeln!(w, "[modules.{}]", module)?;
+        if let Some(alias) = self.alias.as_ref() {
+            writeln!(w, "alias = \"{}\"", alias)?;
+        }
+        if let Some(view) = self.view.as_ref() {
+            writeln!(w, "view = \"{}\"", view)?;
+        }
+        Ok(())
+    }
+}
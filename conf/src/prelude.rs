@@ -5,10 +5,15 @@ pub use serde::{Deserialize, Serialize};
 pub use toml;
 
 pub use crate::{
+    cex_view::{self, CexView},
     customl,
+    install::{self, Install},
+    launcher::{self, Launcher},
+    module_conf::{self, ModuleConf},
     project::{self, Project},
     target::{self, Target},
     toolchain::{self, Toolchain},
+    toolchain_store,
     top_cla::{self, TopCla},
     vlog, Conf, TlcCla,
 };
@@ -61,15 +61,21 @@ pub fn raw_load(path: impl AsRef<io::Path>) -> Res<Project> {
     Project::de_toml(&buf)
 }
 /// Loads the project configuration from some path.
+///
+/// Relative paths of the [`Launcher`] are made relative to the project's directory.
 pub fn load_from(path: impl Into<io::PathBuf>) -> Res<bool> {
     let mut path = path.into();
+    let project_dir = path.clone();
     path.push(TOML_CONFIG_FILENAME);
     if !path.exists() {
         Ok(false)
     } else if path.is_dir() {
         bail!("`{}` is a directory, it should be a file", path.display())
     } else {
-        let conf = raw_load(path)?;
+        let mut conf = raw_load(path)?;
+        // TLC runs in the build directory, launcher paths must not depend on the current one.
+        conf.launcher
+            .resolve(io::try_canonicalize(project_dir, true)?);
         // println!("user conf:\n{:#?}", conf);
         glob::project_conf_write(|target| {
             if target.is_some() {
@@ -101,13 +107,25 @@ pub fn apply<Out>(action: impl FnOnce(&Project) -> Res<Out>) -> Res<Option<Out>>
 /// Configuration corresponding to a project's toml file.
 #[derive(Debug, Clone)]
 pub struct Project {
+    /// Toolchain of the [store][crate::toolchain_store] the project runs with, if any.
+    pub toolchain: Option<String>,
     /// TLC command-line arguments.
     pub tlc_cla: customl::TlcCla,
+    /// Counterexample display configuration.
+    pub cex_view: CexView,
+    /// Launcher configuration, overrides the user's one.
+    pub launcher: Launcher,
+    /// Per-module configuration.
+    pub modules: Map<String, ModuleConf>,
 }
 impl Default for Project {
     fn default() -> Self {
         Self {
+            toolchain: None,
             tlc_cla: customl::TlcCla::default(),
+            cex_view: CexView::default(),
+            launcher: Launcher::default(),
+            modules: Map::new(),
         }
     }
 }
@@ -115,16 +133,124 @@ impl Project {
     /// Serializes itself to toml.
     pub fn ser_toml(&self, w: &mut impl io::Write) -> Res<()> {
         writeln!(w, "[project]")?;
+        if let Some(toolchain) = self.toolchain.as_ref() {
+            writeln!(w, "toolchain = \"{}\"", toolchain)?;
+        }
         self.tlc_cla.ser_toml_file(w)?;
         writeln!(w)?;
+        if !self.cex_view.is_empty() {
+            self.cex_view.ser_toml(w)?;
+            writeln!(w)?;
+        }
+        if !self.launcher.is_empty() {
+            self.launcher.ser_toml(w)?;
+            writeln!(w)?;
+        }
+        for (module, conf) in self.modules.iter() {
+            if !conf.is_empty() {
+                conf.ser_toml(module, w)?;
+                writeln!(w)?;
+            }
+        }
         Ok(())
     }
+    /// Configuration of a module, if any.
+    pub fn module(&self, module: impl AsRef<str>) -> Option<&ModuleConf> {
+        self.modules.get(module.as_ref())
+    }
     /// Deserialization from toml.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use conf::{cex_view::Hint, Project};
+    /// let txt = r#"
+    /// [project]
+    /// toolchain = "1.8.0"
+    /// workers = 2
+    ///
+    /// [cex]
+    /// only = ["queue", "cnt"]
+    /// changed_only = true
+    ///
+    /// [cex.hints]
+    /// queue = "table"
+    /// seen = "fold:20"
+    ///
+    /// [launcher]
+    /// heap = "8g"
+    /// classpath = ["lib/deps.jar"]
+    ///
+    /// [launcher.properties]
+    /// "tlc2.TLC.stopAfter" = "3600"
+    ///
+    /// [modules.sw]
+    /// alias = "DebugAlias"
+    /// "#;
+    /// let project = Project::de_toml(txt).unwrap();
+    /// assert_eq!(project.toolchain.as_deref(), Some("1.8.0"));
+    /// assert!(project.cex_view.is_visible("cnt"));
+    /// assert!(!project.cex_view.is_visible("seen"));
+    /// assert!(project.cex_view.changed_only);
+    /// assert_eq!(project.cex_view.hint("queue"), Some(Hint::Table));
+    /// assert_eq!(project.cex_view.hint("seen"), Some(Hint::Fold(20)));
+    /// let sw = project.module("sw").unwrap();
+    /// assert_eq!(sw.alias.as_deref(), Some("DebugAlias"));
+    /// assert_eq!(sw.view, None);
+    /// assert_eq!(project.launcher.heap.as_deref(), Some("8g"));
+    /// assert_eq!(project.launcher.properties["tlc2.TLC.stopAfter"], "3600");
+    ///
+    /// let mut bytes = vec![];
+    /// project.ser_toml(&mut bytes).unwrap();
+    /// let reparsed = Project::de_toml(&String::from_utf8(bytes).unwrap()).unwrap();
+    /// assert_eq!(project.cex_view, reparsed.cex_view);
+    /// assert_eq!(project.toolchain, reparsed.toolchain);
+    /// assert_eq!(project.launcher, reparsed.launcher);
+    /// assert_eq!(project.modules, reparsed.modules);
+    ///
+    /// // sections other than `[project]` can come in any order
+    /// let txt = r#"
+    /// [project]
+    /// [modules.sw]
+    /// view = "DebugView"
+    /// [launcher.properties]
+    /// "tlc2.TLC.stopAfter" = "3600"
+    /// [cex.hints]
+    /// queue = "table"
+    /// [launcher]
+    /// heap = "8g"
+    /// [cex]
+    /// hide = ["seen"]
+    /// "#;
+    /// let project = Project::de_toml(txt).unwrap();
+    /// assert_eq!(project.module("sw").unwrap().view.as_deref(), Some("DebugView"));
+    /// assert_eq!(project.launcher.properties["tlc2.TLC.stopAfter"], "3600");
+    /// assert_eq!(project.launcher.heap.as_deref(), Some("8g"));
+    /// assert_eq!(project.cex_view.hint("queue"), Some(Hint::Table));
+    /// assert!(!project.cex_view.is_visible("seen"));
+    /// ```
     pub fn de_toml(txt: &str) -> Res<Self> {
+        let mut toolchain = None;
         let mut tlc_cla = customl::TlcCla::none();
-        customl::parse::config::project(txt, &mut tlc_cla)
-            .map_err(|e| Error::msg(e.to_string()))?;
-        Ok(Self { tlc_cla })
+        let mut cex_view = CexView::new();
+        let mut launcher = Launcher::new();
+        let mut modules = Map::new();
+        customl::parse::config::project(
+            txt,
+            &mut toolchain,
+            &mut tlc_cla,
+            &mut cex_view,
+            &mut launcher,
+            &mut modules,
+        )
+        .map_err(|e| Error::msg(e.to_string()))?;
+        Ok(Self {
+            toolchain,
+            tlc_cla,
+            cex_view,
+            launcher,
+            modules,
+        })
     }
 
     /// Dumps itself in some directory to a file named [`TOML_CONFIG_FILENAME`].
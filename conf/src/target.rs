@@ -15,6 +15,36 @@ prelude!();
 /// Name of the top target (build) directory.
 pub const TARGET_DIR_NAME: &str = "target";
 
+/// Extension of TLC checkpoint files.
+pub const CHECKPOINT_EXT: &str = "chkpt";
+
+/// Date of the latest checkpoint file in a directory, if any.
+///
+/// A directory is a checkpoint directory if it contains at least one TLC checkpoint file, *i.e.* a
+/// file with extension [`CHECKPOINT_EXT`].
+pub fn checkpoint_date(dir: impl AsRef<io::Path>) -> Res<Option<time::SystemTime>> {
+    let dir = dir.as_ref();
+    if !dir.is_dir() {
+        return Ok(None);
+    }
+    let mut res = None;
+    for entry in dir
+        .read_dir()
+        .with_context(|| anyhow!("failed to read directory `{}`", dir.display()))?
+    {
+        let path = entry
+            .with_context(|| anyhow!("failed to read an entry of directory `{}`", dir.display()))?
+            .path();
+        if path.is_file() && io::file_ext(&path).map_or(false, |ext| ext == CHECKPOINT_EXT) {
+            let date = io::file_modified(&path)?;
+            if res.map_or(true, |prev| prev < date) {
+                res = Some(date)
+            }
+        }
+    }
+    Ok(res)
+}
+
 /// Stores the important directories needed for building a project.
 #[readonly]
 #[derive(Debug, Clone)]
@@ -160,6 +190,62 @@ impl Target {
         Ok(cmd)
     }
 
+    /// Checkpoint directories in the TLC metadir, from oldest to latest.
+    ///
+    /// Checkpoints are either in the metadir itself or in one of its direct sub-directories, see
+    /// [`checkpoint_date`].
+    pub fn checkpoints(&self) -> Res<Vec<io::PathBuf>> {
+        let mut res = vec![];
+        if let Some(date) = checkpoint_date(&self.metadir_path)? {
+            res.push((date, self.metadir_path.clone()));
+        }
+        if self.metadir_path.is_dir() {
+            for entry in self.metadir_path.read_dir().with_context(|| {
+                anyhow!("failed to read directory `{}`", self.metadir_path.display())
+            })? {
+                let path = entry
+                    .with_context(|| {
+                        anyhow!(
+                            "failed to read an entry of directory `{}`",
+                            self.metadir_path.display()
+                        )
+                    })?
+                    .path();
+                if let Some(date) = checkpoint_date(&path)? {
+                    res.push((date, path));
+                }
+            }
+        }
+        res.sort();
+        Ok(res.into_iter().map(|(_, path)| path).collect())
+    }
+
+    /// Latest checkpoint directory in the TLC metadir, if any.
+    pub fn latest_checkpoint(&self) -> Res<Option<io::PathBuf>> {
+        self.checkpoints().map(|mut checkpoints| checkpoints.pop())
+    }
+
+    /// Retrieves a checkpoint directory.
+    ///
+    /// The `checkpoint` path is first understood as relative to the TLC metadir, and then as a path
+    /// of its own. Fails if neither is a checkpoint directory.
+    pub fn checkpoint(&self, checkpoint: impl AsRef<io::Path>) -> Res<io::PathBuf> {
+        let checkpoint = checkpoint.as_ref();
+        let mut in_metadir = self.metadir_path.clone();
+        in_metadir.push(checkpoint);
+        for path in [in_metadir, checkpoint.to_path_buf()] {
+            if checkpoint_date(&path)?.is_some() {
+                return Ok(path);
+            }
+        }
+        bail!(
+            "`{}` is not a checkpoint, no checkpoint file in `{}` nor in `{}`",
+            checkpoint.display(),
+            self.metadir_path.join(checkpoint).display(),
+            checkpoint.display(),
+        )
+    }
+
     /// Generates a TLC command with default [`TlcCla`].
     pub fn default_tlc_cmd(&self) -> Res<io::Command> {
         self.tlc_cmd(&crate::TlcCla::default())
@@ -289,4 +375,37 @@ impl Target {
             metadir_path,
         }
     }
+
+    /// Constructor for a cex evaluation project, see `matla cex eval`.
+    ///
+    /// Very similar to [`Self::new_run`], but the build directory will be
+    /// `<project_dir>/target/<debug|release>/cex_eval`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use conf::target::Target;
+    /// use path_slash::PathExt;
+    ///
+    /// let project_path = "project/dir";
+    /// let target = Target::new_cex_eval(project_path, false);
+    /// assert_eq!(
+    ///     target.build_path.to_slash_lossy(),
+    ///     format!("{}/target/debug/cex_eval", project_path),
+    /// );
+    /// assert_eq!(
+    ///     target.metadir_path.to_slash_lossy(),
+    ///     format!("{}/target/debug/cex_eval/tlc_meta", project_path),
+    /// );
+    /// ```
+    pub fn new_cex_eval(project_path: impl Into<io::PathBuf>, release: bool) -> Self {
+        let mut target = Self::new_run(project_path, release);
+        target.build_path.push("cex_eval");
+        target.metadir_path = {
+            let mut path = target.build_path.clone();
+            path.push("tlc_meta");
+            path
+        };
+        target
+    }
 }
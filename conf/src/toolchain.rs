@@ -7,7 +7,8 @@
 //! As such, all these functions fail if the global configuration has not been set properly.
 //!
 //! The [`Toolchain`] configuration is created from the user's global configuration file, or from
-//! the environment in portable mode.
+//! the environment in portable mode. Commands are launched as specified by the [`Launcher`] of the
+//! user's configuration, overridden by the one of the project's configuration, see [`launcher`].
 
 prelude!();
 
@@ -21,7 +22,11 @@ pub fn user_read<T>(action: impl FnOnce(&Toolchain) -> T) -> Res<T> {
 
 /// Path to the `tla2tools` jar.
 ///
-/// Fails if the configuration is not loaded.
+/// This is the jar of the toolchain the project pins in the
+/// [toolchain store][crate::toolchain_store] if any, the one from the user's configuration
+/// otherwise.
+///
+/// Fails if the configuration is not loaded, or if the pinned toolchain is not installed.
 ///
 /// # Examples
 ///
@@ -36,8 +41,22 @@ pub fn user_read<T>(action: impl FnOnce(&Toolchain) -> T) -> Res<T> {
 /// assert!( tla2tools.display().to_string().ends_with("tla2tools.jar") );
 /// ```
 pub fn tla2tools() -> Res<io::PathBuf> {
+    if let Some(name) = crate::toolchain_store::pinned() {
+        return crate::toolchain_store::get(&name)
+            .with_context(|| anyhow!("the project pins toolchain `{}`", name));
+    }
     crate::glob::read_map(|conf| conf.toolchain.tla2tools.clone())
 }
+/// Launcher from the user's configuration, overridden by the project's one if any.
+///
+/// Fails if the configuration is not loaded.
+pub fn launcher() -> Res<Launcher> {
+    let mut launcher = crate::glob::read_map(|conf| conf.toolchain.launcher.clone())?;
+    if let Some(project) = crate::project::read(|project| project.launcher.clone()) {
+        launcher.receive(&project);
+    }
+    Ok(launcher)
+}
 /// TLC command.
 ///
 /// Fails if the configuration is not loaded.
@@ -63,7 +82,7 @@ pub fn tla2tools() -> Res<io::PathBuf> {
 /// );
 /// ```
 pub fn tlc_cmd() -> Res<io::Command> {
-    crate::glob::read_map(|conf| conf.toolchain.tlc_cmd())
+    launcher()?.tlc_cmd(tla2tools()?)
 }
 /// Documentation `tla2tex` command.
 ///
@@ -90,7 +109,7 @@ pub fn tlc_cmd() -> Res<io::Command> {
 /// );
 /// ```
 pub fn tla2tex_cmd() -> Res<io::Command> {
-    crate::glob::read_map(|conf| conf.toolchain.tla2tex_cmd())
+    launcher()?.java_cmd(tla2tools()?, "tla2tex.TLA")
 }
 
 /// TLC options.
@@ -100,6 +119,10 @@ pub struct Toolchain {
     pub tla2tools: io::PathBuf,
     /// TLC CLA from the user's config toml file.
     pub tlc_cla: crate::customl::TlcCla,
+    /// Launcher from the user's config toml file.
+    pub launcher: Launcher,
+    /// Installation configuration from the user's config toml file.
+    pub install: Install,
 }
 impl Toolchain {
     /// Default `tla2tools` jar name.
@@ -115,6 +138,14 @@ impl Toolchain {
         )?;
         self.tlc_cla.ser_toml_file(w)?;
         writeln!(w)?;
+        if !self.launcher.is_empty() {
+            self.launcher.ser_toml(w)?;
+            writeln!(w)?;
+        }
+        if !self.install.is_empty() {
+            self.install.ser_toml(w)?;
+            writeln!(w)?;
+        }
 
         Ok(())
     }
@@ -122,8 +153,22 @@ impl Toolchain {
     pub fn de_toml(txt: &str) -> Res<Self> {
         let mut tla2tools = io::PathBuf::new();
         let mut tlc_cla = crate::customl::TlcCla::none();
-        customl::parse::config::user(txt, &mut tla2tools, &mut tlc_cla).map_err(Error::from)?;
-        Ok(Self { tla2tools, tlc_cla })
+        let mut launcher = Launcher::new();
+        let mut install = Install::new();
+        customl::parse::config::user(
+            txt,
+            &mut tla2tools,
+            &mut tlc_cla,
+            &mut launcher,
+            &mut install,
+        )
+        .map_err(Error::from)?;
+        Ok(Self {
+            tla2tools,
+            tlc_cla,
+            launcher,
+            install,
+        })
     }
 
     /// Attempts to build a toolchain configuration from the environment.
@@ -156,6 +201,8 @@ impl Toolchain {
         Ok(Self {
             tla2tools,
             tlc_cla: crate::customl::TlcCla::default(),
+            launcher: Launcher::new(),
+            install: Install::new(),
         })
     }
     /// Sets a toolchain configuration as the global one.
@@ -167,35 +214,26 @@ impl Toolchain {
         crate::glob::write_map(|conf| mem::replace(&mut conf.toolchain, self))
     }
 
-    /// Classless java call to [`Self::tla2tools`].
-    fn java_cmd(&self) -> io::Command {
-        let mut cmd = io::Command::new("java");
-        cmd.args(["-XX:+UseParallelGC", "-cp"]).arg(&self.tla2tools);
-        cmd
-    }
-
     /// Mutable accessor to [`Self::tlc_cla`].
     pub fn tlc_cla_mut(&mut self) -> &mut crate::customl::TlcCla {
         &mut self.tlc_cla
     }
 
-    /// Command for calling TLC.
+    /// Command for calling TLC, ignores the project's launcher.
     ///
     /// Users should use the [module-level `tlc_cmd` function][self::tlc_cmd], which calls this
-    /// function on the global toolchain configuration.
-    pub fn tlc_cmd(&self) -> io::Command {
-        let mut cmd = self.java_cmd();
-        cmd.arg("tlc2.TLC");
-        cmd
+    /// function on the global toolchain configuration and takes the project's launcher into
+    /// account.
+    pub fn tlc_cmd(&self) -> Res<io::Command> {
+        self.launcher.tlc_cmd(&self.tla2tools)
     }
-    /// Command for calling `tla2tex`.
+    /// Command for calling `tla2tex`, ignores the project's launcher.
     ///
     /// Users should use the [module-level `tla2tex_cmd` function][self::tla2tex_cmd], which calls
-    /// this function on the global toolchain configuration.
-    pub fn tla2tex_cmd(&self) -> io::Command {
-        let mut cmd = self.java_cmd();
-        cmd.arg("tla2tex.TLA");
-        cmd
+    /// this function on the global toolchain configuration and takes the project's launcher into
+    /// account.
+    pub fn tla2tex_cmd(&self) -> Res<io::Command> {
+        self.launcher.java_cmd(&self.tla2tools, "tla2tex.TLA")
     }
 }
 implem! {
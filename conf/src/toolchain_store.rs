@@ -0,0 +1,185 @@
+//! Local store of named `tla2tools` jars.
+//!
+//! The store lives in the [`DIR_NAME`] sub-directory of the user's configuration directory. Each
+//! toolchain is a jar named `<name>.jar`, see `matla toolchain add`. When adding a toolchain, matla
+//! runs TLC once and records the version it reports in `<name>.tlc_version` so that later runs can
+//! detect that the jar changed. If this fails, the version is recorded the first time TLC runs with
+//! the toolchain.
+//!
+//! A project can pin a toolchain in its configuration file, in which case it runs with this
+//! toolchain instead of the user's default one:
+//!
+//! ```toml
+//! [project]
+//! toolchain = "1.8.0"
+//! ```
+
+prelude!();
+
+/// Name of the toolchain store directory in the user's configuration directory.
+pub const DIR_NAME: &str = "toolchains";
+/// Extension of the toolchain jars.
+pub const JAR_EXT: &str = "jar";
+/// Extension of the files storing the TLC version of a toolchain.
+pub const VERSION_EXT: &str = "tlc_version";
+
+/// Path to the toolchain store.
+pub fn path() -> Res<io::PathBuf> {
+    Ok(crate::user::conf_path()?.join(DIR_NAME))
+}
+
+/// Path to the jar of a toolchain, which might not exist.
+pub fn jar_path(name: &str) -> Res<io::PathBuf> {
+    Ok(path()?.join(format!("{}.{}", name, JAR_EXT)))
+}
+
+/// Path to the file storing the TLC version of a toolchain, which might not exist.
+fn version_path(name: &str) -> Res<io::PathBuf> {
+    Ok(path()?.join(format!("{}.{}", name, VERSION_EXT)))
+}
+
+/// Checks that a toolchain name is legal.
+///
+/// Names are non-empty and only contain ASCII alphanumeric characters, `.`, `-` and `_`.
+///
+/// ```rust
+/// # use conf::toolchain_store::check_name;
+/// assert!(check_name("1.8.0").is_ok());
+/// assert!(check_name("nightly_2023-05").is_ok());
+/// assert!(check_name("").is_err());
+/// assert!(check_name("../1.8.0").is_err());
+/// ```
+pub fn check_name(name: &str) -> Res<()> {
+    let legal = |c: char| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_';
+    if name.is_empty() || !name.chars().all(legal) || name.chars().all(|c| c == '.') {
+        bail!(
+            "illegal toolchain name `{}`, expected ASCII alphanumeric characters, `.`, `-` or `_`",
+            name
+        )
+    }
+    Ok(())
+}
+
+/// A toolchain of the store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Name of the toolchain.
+    pub name: String,
+    /// Path to the jar.
+    pub jar: io::PathBuf,
+    /// Version reported by TLC, if it ran with this toolchain already, see [`tlc_version`].
+    pub tlc_version: Option<String>,
+}
+
+/// Lists the toolchains of the store, sorted by name.
+pub fn list() -> Res<Vec<Entry>> {
+    let dir = path()?;
+    let mut res = vec![];
+    if !dir.is_dir() {
+        return Ok(res);
+    }
+    let entries = dir
+        .read_dir()
+        .with_context(|| anyhow!("failed to read toolchain store `{}`", dir.display()))?;
+    for entry in entries {
+        let jar = entry
+            .with_context(|| anyhow!("failed to read toolchain store `{}`", dir.display()))?
+            .path();
+        if !jar.is_file() || io::file_ext(&jar).map_or(true, |ext| ext != JAR_EXT) {
+            continue;
+        }
+        let name = io::file_stem(&jar)?;
+        let tlc_version = tlc_version(&name)?;
+        res.push(Entry {
+            name,
+            jar,
+            tlc_version,
+        });
+    }
+    res.sort_by(|e1, e2| e1.name.cmp(&e2.name));
+    Ok(res)
+}
+
+/// Adds a jar to the store under some name, returns the path to the stored jar.
+///
+/// Fails if the store already has a toolchain with this name.
+pub fn add(jar: impl AsRef<io::Path>, name: &str) -> Res<io::PathBuf> {
+    let jar = jar.as_ref();
+    check_name(name)?;
+    if !jar.is_file() {
+        bail!("`{}` is not a file", jar.display())
+    }
+    let target = jar_path(name)?;
+    if target.exists() {
+        bail!(
+            "toolchain `{}` already exists, remove it first with `matla toolchain remove {}`",
+            name,
+            name
+        )
+    }
+    let dir = path()?;
+    io::create_dir_all(&dir)
+        .with_context(|| anyhow!("failed to create toolchain store `{}`", dir.display()))?;
+    io::copy(jar, &target).with_context(|| {
+        anyhow!(
+            "failed to copy `{}` to `{}`",
+            jar.display(),
+            target.display()
+        )
+    })?;
+    Ok(target)
+}
+
+/// Removes a toolchain from the store.
+pub fn remove(name: &str) -> Res<()> {
+    check_name(name)?;
+    let jar = jar_path(name)?;
+    if !jar.is_file() {
+        bail!("unknown toolchain `{}`", name)
+    }
+    io::remove_file(&jar).with_context(|| anyhow!("failed to delete `{}`", jar.display()))?;
+    let version = version_path(name)?;
+    if version.exists() {
+        io::remove_file(&version)
+            .with_context(|| anyhow!("failed to delete `{}`", version.display()))?;
+    }
+    Ok(())
+}
+
+/// Path to the jar of a toolchain, fails if the store does not have it.
+pub fn get(name: &str) -> Res<io::PathBuf> {
+    check_name(name)?;
+    let jar = jar_path(name)?;
+    if !jar.is_file() {
+        bail!(
+            "toolchain `{}` is not installed, add it with `matla toolchain add <JAR> --name {}`",
+            name,
+            name
+        )
+    }
+    Ok(jar)
+}
+
+/// Version reported by TLC when it ran with a toolchain, if any.
+pub fn tlc_version(name: &str) -> Res<Option<String>> {
+    let path = version_path(name)?;
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let version = io::load_file(&path)?;
+    Ok(Some(version.trim().to_string()))
+}
+
+/// Records the version reported by TLC when it ran with a toolchain.
+pub fn record_tlc_version(name: &str, version: &str) -> Res<()> {
+    use io::Write;
+    let path = version_path(name)?;
+    let mut file = io::write_file(&path, true, false)?;
+    writeln!(file, "{}", version.trim())
+        .with_context(|| anyhow!("failed to write to `{}`", path.display()))
+}
+
+/// Toolchain pinned by the project, if any.
+pub fn pinned() -> Option<String> {
+    crate::project::read(|project| project.toolchain.clone()).flatten()
+}
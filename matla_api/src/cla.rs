@@ -36,6 +36,15 @@ pub mod utils {
             },
         }
     }
+    pub fn validate_u64_or_off(s: &str) -> Result<Option<u64>, String> {
+        match s {
+            "off" | "Off" | "_" => Ok(None),
+            _ => match u64::from_str_radix(s, 10) {
+                Ok(res) => Ok(Some(res)),
+                Err(e) => Err(e.to_string()),
+            },
+        }
+    }
     pub fn validate_usize_or_auto(s: &str) -> Result<Option<usize>, String> {
         match s {
             "auto" | "Auto" | "_" => Ok(None),
@@ -57,6 +66,7 @@ pub mod utils {
 
         pub const U64_OR_RANDOM: &str = "[Rr]andom|_|INT ≥ 0";
         pub const U64_OR_DEFAULT: &str = "[Dd]efault|_|INT ≥ 0";
+        pub const U64_OR_OFF: &str = "[Oo]ff|_|INT ≥ 0";
     }
 
     pub fn if_flags_free_add(
@@ -1,11 +1,15 @@
 //! Aggregates matla's run-modes.
 
+pub mod cex;
 pub mod clean;
+pub mod doc;
+pub mod doctor;
 pub mod init;
 pub mod run;
 pub mod setup;
 pub mod testing;
 pub mod tlc;
+pub mod toolchain;
 pub mod uninstall;
 pub mod update;
 
@@ -158,8 +162,14 @@ mod requires_clap {
     enum_gather_specs! {
         /// Gathers all modes.
         pub enum Mode {
+            /// Counterexample management mode.
+            Cex for cex,
             /// Project cleaning mode.
             Clean for clean,
+            /// Documentation generation mode.
+            Doc for doc,
+            /// Environment diagnostics mode.
+            Doctor for doctor,
             /// Project init mode.
             Init for init,
             /// Run mode.
@@ -170,6 +180,8 @@ mod requires_clap {
             Test for testing,
             /// TLC mode, only runs TLC.
             Tlc for tlc,
+            /// Toolchain store management mode.
+            Toolchain for toolchain,
             /// Uninstals matla.
             Uninstall for uninstall,
             /// Updates the TLA+ toolchain.
@@ -0,0 +1,718 @@
+//! Cex mode, lists, shows and exports the counterexamples saved by runs.
+//!
+//! Runs save their cexs in `target/<debug|release>/cex`, see [`project::cex_store`]. Cexs are
+//! designated by their identifier as given by `matla cex list`, or by the path to their file.
+//! Commands working on a single cex default to the latest one.
+//!
+//! `matla cex regress` turns a cex into a regression test in the project's `tests/cex` directory,
+//! see [`testing::regression`]. `matla cex eval` evaluates a TLA+ expression in the states of a
+//! cex, see [`project::cex_eval`].
+
+prelude!();
+
+use project::cex_store::{SavedCex, Store};
+
+/// CLAP stuff.
+#[cfg(feature = "with_clap")]
+pub mod cla {
+    use super::*;
+
+    /// Cex subcommand name.
+    const CMD_NAME: &str = "cex";
+    /// List subcommand name.
+    const LIST_CMD_NAME: &str = "list";
+    /// Show subcommand name.
+    const SHOW_CMD_NAME: &str = "show";
+    /// Export subcommand name.
+    const EXPORT_CMD_NAME: &str = "export";
+    /// Regress subcommand name.
+    const REGRESS_CMD_NAME: &str = "regress";
+    /// Eval subcommand name.
+    const EVAL_CMD_NAME: &str = "eval";
+    /// Key for release mode.
+    const RELEASE_KEY: &str = "CEX_RELEASE_KEY";
+    /// Key for the cex to work on.
+    const CEX_KEY: &str = "CEX_CEX_KEY";
+    /// Key for the export format.
+    const FORMAT_KEY: &str = "CEX_FORMAT_KEY";
+    /// Export format default value.
+    const FORMAT_DEFAULT: &str = "text";
+    /// Key for the export output file.
+    const OUTPUT_KEY: &str = "CEX_OUTPUT_KEY";
+    /// Key for displaying cexs as tables.
+    const TABLE_KEY: &str = "CEX_TABLE_KEY";
+    /// Key for the expectation of a regression test.
+    const EXPECT_KEY: &str = "CEX_EXPECT_KEY";
+    /// Key for the name of a regression test.
+    const NAME_KEY: &str = "CEX_NAME_KEY";
+    /// Key for the expression to evaluate.
+    const EXPR_KEY: &str = "CEX_EXPR_KEY";
+    /// Key for the state to evaluate an expression in.
+    const STATE_KEY: &str = "CEX_STATE_KEY";
+
+    /// Cex subcommand.
+    pub fn subcommand() -> clap::Command<'static> {
+        let release_arg = || {
+            clap::Arg::new(RELEASE_KEY)
+                .help("Works on the cexs of release runs")
+                .long("release")
+        };
+        let cex_arg = || {
+            clap::Arg::new(CEX_KEY)
+                .help("Identifier or path of a cex, defaults to the latest cex")
+                .value_name("CEX")
+        };
+        clap::Command::new(CMD_NAME)
+            .about("Lists, shows, exports and evaluates the cexs saved by `matla run`.")
+            .arg(crate::cla::top::project_path_arg())
+            .subcommand_required(true)
+            .subcommand(
+                clap::Command::new(LIST_CMD_NAME)
+                    .about("Lists the saved cexs, oldest first.")
+                    .args([crate::cla::top::project_path_arg(), release_arg()]),
+            )
+            .subcommand(
+                clap::Command::new(SHOW_CMD_NAME)
+                    .about("Displays a saved cex.")
+                    .args([
+                        crate::cla::top::project_path_arg(),
+                        release_arg(),
+                        cex_arg(),
+                        clap::Arg::new(TABLE_KEY)
+                            .help("Displays the cex as a table, one column per variable")
+                            .long("table"),
+                    ]),
+            )
+            .subcommand(
+                clap::Command::new(EXPORT_CMD_NAME)
+                    .about("Exports a saved cex.")
+                    .args([
+                        crate::cla::top::project_path_arg(),
+                        release_arg(),
+                        cex_arg(),
+                        clap::Arg::new(FORMAT_KEY)
+                            .help("Export format")
+                            .long("format")
+                            .takes_value(true)
+                            .default_value(FORMAT_DEFAULT)
+                            .value_name(super::Format::VALUES)
+                            .validator(|arg| super::Format::parse(&arg).map(|_| ())),
+                        clap::Arg::new(OUTPUT_KEY)
+                            .help("Writes the cex to a file instead of `stdout`")
+                            .long("output")
+                            .short('o')
+                            .takes_value(true)
+                            .value_name(crate::cla::utils::val_name::FILE),
+                    ]),
+            )
+            .subcommand(
+                clap::Command::new(REGRESS_CMD_NAME)
+                    .about("Turns a saved cex into a regression test run by `matla test`.")
+                    .args([
+                        crate::cla::top::project_path_arg(),
+                        release_arg(),
+                        cex_arg(),
+                        clap::Arg::new(EXPECT_KEY)
+                            .help("Whether the trace is expected to be fixed or still violated")
+                            .long("expect")
+                            .takes_value(true)
+                            .required(true)
+                            .value_name(testing::regression::Expect::VALUES)
+                            .validator(|arg| testing::regression::Expect::parse(&arg).map(|_| ())),
+                        clap::Arg::new(NAME_KEY)
+                            .help("Name of the test, defaults to the cex identifier")
+                            .long("name")
+                            .takes_value(true)
+                            .value_name("NAME"),
+                    ]),
+            )
+            .subcommand(
+                clap::Command::new(EVAL_CMD_NAME)
+                    .about("Evaluates a TLA+ expression in the states of a saved cex.")
+                    .args([
+                        crate::cla::top::project_path_arg(),
+                        release_arg(),
+                        clap::Arg::new(EXPR_KEY)
+                            .help("TLA+ expression to evaluate")
+                            .value_name("EXPR")
+                            .required(true),
+                        cex_arg(),
+                        clap::Arg::new(STATE_KEY)
+                            .help("Only evaluates the expression in this state")
+                            .long("state")
+                            .takes_value(true)
+                            .value_name("N")
+                            .validator(|arg| arg.parse::<usize>().map(|_| ())),
+                    ]),
+            )
+    }
+
+    /// Constructs a [`Run`] if cex subcommand is active.
+    pub fn check_matches(matches: &clap::ArgMatches) -> Option<Res<Run>> {
+        matches
+            .subcommand_matches(CMD_NAME)
+            .map(|matches| match matches.subcommand() {
+                Some((LIST_CMD_NAME, matches)) => {
+                    Run::new(matches.is_present(RELEASE_KEY), Action::List)
+                }
+                Some((SHOW_CMD_NAME, matches)) => Run::new(
+                    matches.is_present(RELEASE_KEY),
+                    Action::Show {
+                        cex: matches.value_of(CEX_KEY).map(String::from),
+                        table: matches.is_present(TABLE_KEY),
+                    },
+                ),
+                Some((EXPORT_CMD_NAME, matches)) => {
+                    let format = matches
+                        .value_of(FORMAT_KEY)
+                        .expect("unreachable: CLA value with default");
+                    Run::new(
+                        matches.is_present(RELEASE_KEY),
+                        Action::Export {
+                            cex: matches.value_of(CEX_KEY).map(String::from),
+                            format: super::Format::parse(format).map_err(Error::msg)?,
+                            output: matches.value_of(OUTPUT_KEY).map(io::PathBuf::from),
+                        },
+                    )
+                }
+                Some((EVAL_CMD_NAME, matches)) => Run::new(
+                    matches.is_present(RELEASE_KEY),
+                    Action::Eval {
+                        cex: matches.value_of(CEX_KEY).map(String::from),
+                        expr: matches
+                            .value_of(EXPR_KEY)
+                            .expect("unreachable: required CLA")
+                            .into(),
+                        state: matches
+                            .value_of(STATE_KEY)
+                            .map(|state| state.parse::<usize>())
+                            .transpose()
+                            .context("failed to parse argument despite validation")?,
+                    },
+                ),
+                Some((REGRESS_CMD_NAME, matches)) => {
+                    let expect = matches
+                        .value_of(EXPECT_KEY)
+                        .expect("unreachable: required CLA");
+                    Run::new(
+                        matches.is_present(RELEASE_KEY),
+                        Action::Regress {
+                            cex: matches.value_of(CEX_KEY).map(String::from),
+                            expect: testing::regression::Expect::parse(expect)
+                                .map_err(Error::msg)?,
+                            name: matches.value_of(NAME_KEY).map(String::from),
+                        },
+                    )
+                }
+                Some((sub, _)) => bail!("unexpected cex command `{}`", sub),
+                None => bail!("expected cex command, found nothing"),
+            })
+    }
+}
+
+/// Export format of a cex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Human-oriented, unstyled text, as displayed by `matla run`.
+    Text,
+    /// JSON, as the cexs of the JSON output of `matla run`.
+    Json,
+    /// TLC's trace syntax, *i.e.* the content of the cex file.
+    Tla,
+    /// Unstyled table, one row per state and one column per variable.
+    Table,
+    /// Markdown table, one row per state and one column per variable.
+    Markdown,
+    /// Standalone HTML page.
+    Html,
+    /// Graphviz graph.
+    Dot,
+}
+impl Format {
+    /// Value description for format arguments.
+    pub const VALUES: &'static str = "text|json|tla|table|markdown|html|dot";
+
+    /// Parses a format.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "tla" => Ok(Self::Tla),
+            "table" => Ok(Self::Table),
+            "markdown" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            "dot" => Ok(Self::Dot),
+            _ => Err(format!("expected {}", Self::VALUES)),
+        }
+    }
+
+    /// Renders a saved cex.
+    pub fn render(self, saved: &SavedCex) -> Res<String> {
+        let mut res = String::new();
+        let spec = cex::pretty::Spec::default()
+            .view(project_cex_view())
+            .unstyled();
+        match self {
+            Self::Text => {
+                spec.cex_to_ml_string(&saved.cex, &mut res);
+                res.push('\n');
+            }
+            Self::Table => {
+                res = cex::table::render(&spec, &saved.cex, cex::table::Flavor::Terminal)
+            }
+            Self::Markdown => {
+                res = cex::table::render(&spec, &saved.cex, cex::table::Flavor::Markdown)
+            }
+            Self::Html => {
+                let source_dir = conf::top_cla::project_path().ok();
+                res = cex::html::render(&spec, &saved.cex, source_dir.as_deref())
+            }
+            Self::Dot => res = cex::dot::render(&spec, &saved.cex),
+            Self::Json => {
+                res = serde_json::to_string_pretty(&saved.cex)
+                    .context("failed to serialize cex to JSON")?;
+                res.push('\n');
+            }
+            Self::Tla => res = io::load_file(&saved.path)?,
+        }
+        Ok(res)
+    }
+}
+
+/// Cex display configuration of the project, if any.
+fn project_cex_view() -> conf::CexView {
+    conf::project::read(|project| project.cex_view.clone()).unwrap_or_default()
+}
+
+/// Action performed by the cex mode.
+#[derive(Debug, Clone)]
+pub enum Action {
+    /// Lists the saved cexs.
+    List,
+    /// Displays a cex, the latest one if `None`.
+    Show {
+        /// Cex to display.
+        cex: Option<String>,
+        /// If true, display the cex as a table.
+        table: bool,
+    },
+    /// Exports a cex, the latest one if `None`.
+    Export {
+        /// Cex to export.
+        cex: Option<String>,
+        /// Export format.
+        format: Format,
+        /// File to write the cex to, `stdout` if `None`.
+        output: Option<io::PathBuf>,
+    },
+    /// Evaluates an expression in the states of a cex, the latest one if `None`.
+    Eval {
+        /// Cex to evaluate the expression over.
+        cex: Option<String>,
+        /// Expression to evaluate.
+        expr: String,
+        /// State to evaluate the expression in, all states if `None`.
+        state: Option<usize>,
+    },
+    /// Turns a cex into a regression test, the latest one if `None`.
+    Regress {
+        /// Cex to turn into a test.
+        cex: Option<String>,
+        /// What the test expects.
+        expect: testing::regression::Expect,
+        /// Name of the test, the cex identifier if `None`.
+        name: Option<String>,
+    },
+}
+
+/// Runs cex mode.
+#[readonly]
+#[derive(Debug, Clone)]
+pub struct Run {
+    /// Target configuration.
+    pub target: conf::Target,
+    /// Cex store of the target.
+    pub store: Store,
+    /// Action to perform.
+    pub action: Action,
+}
+impl Run {
+    /// Constructor.
+    pub fn new(release: bool, action: Action) -> Res<Self> {
+        let target = conf::Target::new_run(conf::top_cla::project_path()?, release);
+        let store = Store::new(&target);
+        Ok(Self {
+            target,
+            store,
+            action,
+        })
+    }
+
+    /// Retrieves a cex, the latest one if `None`.
+    fn get(&self, cex: Option<&String>) -> Res<SavedCex> {
+        match cex {
+            Some(cex) => self.store.load(cex),
+            None => self.store.latest()?.ok_or_else(|| {
+                anyhow!("no counterexample saved in `{}`", self.store.dir.display())
+            }),
+        }
+    }
+
+    /// Launches cex mode.
+    pub fn launch(&self) -> Res<()> {
+        let styles = conf::Styles::new();
+        match &self.action {
+            Action::List => {
+                let cexs = self.store.list()?;
+                if cexs.is_empty() {
+                    println!("no counterexample saved in `{}`", self.store.dir.display());
+                }
+                for saved in cexs {
+                    println!("{}", styles.bold.paint(&saved.id));
+                    println!("  {}", Self::desc(&saved, &styles));
+                }
+            }
+            Action::Show { cex, table } => {
+                let saved = self.get(cex.as_ref())?;
+                println!("{}", styles.bold.paint(&saved.id));
+                println!("{}", styles.comment.paint(Self::desc(&saved, &styles)));
+                println!();
+                let spec = cex::pretty::Spec::default().view(project_cex_view());
+                let format = if *table {
+                    mode::run::CexFormat::Table
+                } else {
+                    mode::run::CexFormat::Text
+                };
+                mode::run::print_cex(&styles, &spec, format, &saved.cex);
+            }
+            Action::Export {
+                cex,
+                format,
+                output,
+            } => {
+                let saved = self.get(cex.as_ref())?;
+                let content = format.render(&saved)?;
+                match output {
+                    Some(path) => {
+                        use io::Write;
+                        let mut file = io::write_file(path, true, false)?;
+                        file.write_all(content.as_bytes()).with_context(|| {
+                            anyhow!("failed to write cex to `{}`", path.display())
+                        })?;
+                        log::info!("cex `{}` exported to `{}`", saved.id, path.display());
+                    }
+                    None => print!("{}", content),
+                }
+            }
+            Action::Eval { cex, expr, state } => {
+                let saved = self.get(cex.as_ref())?;
+                let src_project =
+                    project::SourceProject::from_path(&conf::top_cla::project_path()?)?;
+                let eval = project::cex_eval::eval(
+                    &src_project,
+                    self.target.release,
+                    &saved,
+                    expr,
+                    *state,
+                )?;
+                let mut failed = false;
+                for error in eval.errors.iter() {
+                    let pretty = error.pretty(&eval.project, &styles)?;
+                    if error.is_warning() {
+                        eprint!("{}: ", styles.uline.paint("Warning"));
+                    } else {
+                        failed = true;
+                        eprint!("{}: ", styles.uline.paint("Error"));
+                    }
+                    for line in pretty {
+                        eprintln!("{}", line)
+                    }
+                    eprintln!();
+                }
+                if failed {
+                    bail!("failed to evaluate `{}` over cex `{}`", expr, saved.id)
+                }
+
+                println!("{}", styles.bold.paint(&saved.id));
+                println!("{}", styles.comment.paint(Self::desc(&saved, &styles)));
+                println!();
+                let spec = cex::pretty::Spec::default().view(project_cex_view());
+                let column = cex::table::Column::new(expr, eval.column(saved.cex.states.len()));
+                print!(
+                    "{}",
+                    cex::table::render_with(
+                        &spec,
+                        &saved.cex,
+                        cex::table::Flavor::Terminal,
+                        &[column]
+                    )
+                );
+            }
+            Action::Regress { cex, expect, name } => {
+                let mut saved = self.get(cex.as_ref())?;
+                saved.cex.check_not_alias("regression tests")?;
+                let name = name.as_ref().unwrap_or(&saved.id);
+                let mut path = conf::top_cla::project_path()?;
+                path.push("tests");
+                path.push("cex");
+                io::create_dir_all(&path)
+                    .with_context(|| anyhow!("failed to create directory `{}`", path.display()))?;
+                path.push(format!("{}.{}", name, project::cex_store::FILE_EXT));
+                if path.exists() {
+                    bail!("regression test `{}` already exists", path.display())
+                }
+                let _prev = saved
+                    .extra
+                    .insert(testing::regression::EXPECT_KEY.into(), expect.to_string());
+                saved.write_to(&path, false)?;
+                println!(
+                    "cex `{}` saved as regression test `{}`, expecting {}",
+                    saved.id,
+                    path.display(),
+                    styles.bold.paint(expect.to_string()),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// One-line description of a saved cex.
+    fn desc(saved: &SavedCex, styles: &conf::Styles) -> String {
+        let (falsified, is_temporal) = saved.cex.falsified();
+        let kind = if is_temporal {
+            "temporal property"
+        } else {
+            "invariant"
+        };
+        let falsified = match falsified {
+            Some(name) => format!("{} {}", kind, styles.bad.paint(name)),
+            None => format!("unknown {}", kind),
+        };
+        let seed = match saved.seed {
+            Some(seed) => format!(", seed {}", seed),
+            None => "".into(),
+        };
+        let alias = match saved.cex.alias.as_ref() {
+            Some(alias) => format!(", alias {}", alias),
+            None => "".into(),
+        };
+        format!(
+            "module {}, {}, {} state(s){}{}, {}",
+            saved.module,
+            falsified,
+            saved.cex.states.len(),
+            seed,
+            alias,
+            saved.date.format("%Y-%m-%d %H:%M:%S UTC"),
+        )
+    }
+}
+
+#[cfg(feature = "with_clap")]
+mod cla_spec {
+    prelude!();
+
+    use testing::regression::Expect;
+
+    use super::{Action, Format};
+
+    /// Cex subcommand name.
+    const CMD_NAME: &str = "cex";
+    /// List subcommand name.
+    const LIST_CMD_NAME: &str = "list";
+    /// Show subcommand name.
+    const SHOW_CMD_NAME: &str = "show";
+    /// Export subcommand name.
+    const EXPORT_CMD_NAME: &str = "export";
+    /// Regress subcommand name.
+    const REGRESS_CMD_NAME: &str = "regress";
+    /// Eval subcommand name.
+    const EVAL_CMD_NAME: &str = "eval";
+    /// Key for release mode.
+    const RELEASE_KEY: &str = "CEX_RELEASE_KEY";
+    /// Key for the cex to work on.
+    const CEX_KEY: &str = "CEX_CEX_KEY";
+    /// Key for the export format.
+    const FORMAT_KEY: &str = "CEX_FORMAT_KEY";
+    /// Export format default value.
+    const FORMAT_DEFAULT: &str = "text";
+    /// Key for the export output file.
+    const OUTPUT_KEY: &str = "CEX_OUTPUT_KEY";
+    /// Key for displaying cexs as tables.
+    const TABLE_KEY: &str = "CEX_TABLE_KEY";
+    /// Key for the expectation of a regression test.
+    const EXPECT_KEY: &str = "CEX_EXPECT_KEY";
+    /// Key for the name of a regression test.
+    const NAME_KEY: &str = "CEX_NAME_KEY";
+    /// Key for the expression to evaluate.
+    const EXPR_KEY: &str = "CEX_EXPR_KEY";
+    /// Key for the state to evaluate an expression in.
+    const STATE_KEY: &str = "CEX_STATE_KEY";
+
+    /// Release argument, shared by all cex subcommands.
+    ///
+    /// Cex subcommands also all accept a project path argument, which
+    /// [`cla::top::resolve_project_path`] expects at each subcommand level.
+    fn release_arg() -> clap::Arg<'static> {
+        clap::Arg::new(RELEASE_KEY)
+            .help("Works on the cexs of release runs")
+            .long("release")
+    }
+    /// Cex argument, for subcommands working on a single cex.
+    fn cex_arg() -> clap::Arg<'static> {
+        clap::Arg::new(CEX_KEY)
+            .help("Identifier or path of a cex, defaults to the latest cex")
+            .value_name("CEX")
+    }
+
+    impl mode::ClaMode for super::Run {
+        const SUBCOMMAND_IDENT: &'static str = CMD_NAME;
+        const PREREQ: mode::ClaModePrereq = mode::ClaModePrereq::Project;
+
+        fn build_command(cmd: clap::Command<'static>) -> clap::Command<'static> {
+            cmd.about("Lists, shows, exports and evaluates the cexs saved by `matla run`.")
+                .arg(cla::top::project_path_arg())
+                .subcommand_required(true)
+                .subcommand(
+                    clap::Command::new(LIST_CMD_NAME)
+                        .about("Lists the saved cexs, oldest first.")
+                        .args([cla::top::project_path_arg(), release_arg()]),
+                )
+                .subcommand(
+                    clap::Command::new(SHOW_CMD_NAME)
+                        .about("Displays a saved cex.")
+                        .args([
+                            cla::top::project_path_arg(),
+                            release_arg(),
+                            cex_arg(),
+                            clap::Arg::new(TABLE_KEY)
+                                .help("Displays the cex as a table, one column per variable")
+                                .long("table"),
+                        ]),
+                )
+                .subcommand(
+                    clap::Command::new(EXPORT_CMD_NAME)
+                        .about("Exports a saved cex.")
+                        .args([
+                            cla::top::project_path_arg(),
+                            release_arg(),
+                            cex_arg(),
+                            clap::Arg::new(FORMAT_KEY)
+                                .help("Export format")
+                                .long("format")
+                                .takes_value(true)
+                                .default_value(FORMAT_DEFAULT)
+                                .value_name(Format::VALUES)
+                                .validator(|arg| Format::parse(&arg).map(|_| ())),
+                            clap::Arg::new(OUTPUT_KEY)
+                                .help("Writes the cex to a file instead of `stdout`")
+                                .long("output")
+                                .short('o')
+                                .takes_value(true)
+                                .value_name(cla::utils::val_name::FILE),
+                        ]),
+                )
+                .subcommand(
+                    clap::Command::new(REGRESS_CMD_NAME)
+                        .about("Turns a saved cex into a regression test run by `matla test`.")
+                        .args([
+                            cla::top::project_path_arg(),
+                            release_arg(),
+                            cex_arg(),
+                            clap::Arg::new(EXPECT_KEY)
+                                .help("Whether the trace is expected to be fixed or still violated")
+                                .long("expect")
+                                .takes_value(true)
+                                .required(true)
+                                .value_name(Expect::VALUES)
+                                .validator(|arg| Expect::parse(&arg).map(|_| ())),
+                            clap::Arg::new(NAME_KEY)
+                                .help("Name of the test, defaults to the cex identifier")
+                                .long("name")
+                                .takes_value(true)
+                                .value_name("NAME"),
+                        ]),
+                )
+                .subcommand(
+                    clap::Command::new(EVAL_CMD_NAME)
+                        .about("Evaluates a TLA+ expression in the states of a saved cex.")
+                        .args([
+                            cla::top::project_path_arg(),
+                            release_arg(),
+                            clap::Arg::new(EXPR_KEY)
+                                .help("TLA+ expression to evaluate")
+                                .value_name("EXPR")
+                                .required(true),
+                            cex_arg(),
+                            clap::Arg::new(STATE_KEY)
+                                .help("Only evaluates the expression in this state")
+                                .long("state")
+                                .takes_value(true)
+                                .value_name("N")
+                                .validator(|arg| arg.parse::<usize>().map(|_| ())),
+                        ]),
+                )
+        }
+        fn build(matches: &clap::ArgMatches) -> Res<Self> {
+            match matches.subcommand() {
+                Some((LIST_CMD_NAME, matches)) => {
+                    Self::new(matches.is_present(RELEASE_KEY), Action::List)
+                }
+                Some((SHOW_CMD_NAME, matches)) => Self::new(
+                    matches.is_present(RELEASE_KEY),
+                    Action::Show {
+                        cex: matches.value_of(CEX_KEY).map(String::from),
+                        table: matches.is_present(TABLE_KEY),
+                    },
+                ),
+                Some((EXPORT_CMD_NAME, matches)) => {
+                    let format = matches
+                        .value_of(FORMAT_KEY)
+                        .expect("unreachable: CLA value with default");
+                    Self::new(
+                        matches.is_present(RELEASE_KEY),
+                        Action::Export {
+                            cex: matches.value_of(CEX_KEY).map(String::from),
+                            format: Format::parse(format).map_err(Error::msg)?,
+                            output: matches.value_of(OUTPUT_KEY).map(io::PathBuf::from),
+                        },
+                    )
+                }
+                Some((EVAL_CMD_NAME, matches)) => Self::new(
+                    matches.is_present(RELEASE_KEY),
+                    Action::Eval {
+                        cex: matches.value_of(CEX_KEY).map(String::from),
+                        expr: matches
+                            .value_of(EXPR_KEY)
+                            .expect("unreachable: required CLA")
+                            .into(),
+                        state: matches
+                            .value_of(STATE_KEY)
+                            .map(|state| state.parse::<usize>())
+                            .transpose()
+                            .context("failed to parse argument despite validation")?,
+                    },
+                ),
+                Some((REGRESS_CMD_NAME, matches)) => {
+                    let expect = matches
+                        .value_of(EXPECT_KEY)
+                        .expect("unreachable: required CLA");
+                    Self::new(
+                        matches.is_present(RELEASE_KEY),
+                        Action::Regress {
+                            cex: matches.value_of(CEX_KEY).map(String::from),
+                            expect: Expect::parse(expect).map_err(Error::msg)?,
+                            name: matches.value_of(NAME_KEY).map(String::from),
+                        },
+                    )
+                }
+                Some((sub, _)) => bail!("unexpected cex command `{}`", sub),
+                None => bail!("expected cex command, found nothing"),
+            }
+        }
+        fn run(self) -> Res<Option<i32>> {
+            self.launch()?;
+            Ok(None)
+        }
+    }
+}
@@ -1,4 +1,7 @@
-//! Clean mode, just deletes the project's target directory.
+//! Clean mode, deletes the project's target directory.
+//!
+//! TLC checkpoints and saved counterexamples are kept unless asked otherwise, see
+//! [`conf::target::checkpoint_date`] and [`project::cex_store`].
 
 prelude!();
 
@@ -9,19 +12,29 @@ pub mod cla {
 
     /// Clean subcommand name.
     const CMD_NAME: &str = "clean";
+    /// Key for also deleting checkpoints and saved counterexamples.
+    const ALL_KEY: &str = "CLEAN_ALL_KEY";
 
     /// Clean subcommand.
     pub fn subcommand() -> clap::Command<'static> {
         clap::Command::new(CMD_NAME)
-            .about("Cleans the current project: deletes the `target` directory.")
-            .args(&[crate::cla::top::project_path_arg()])
+            .about(
+                "Cleans the current project: deletes the `target` directory, \
+                except TLC checkpoints and saved counterexamples.",
+            )
+            .args(&[
+                crate::cla::top::project_path_arg(),
+                clap::Arg::new(ALL_KEY)
+                    .help("Also deletes TLC checkpoints and saved counterexamples")
+                    .long("all"),
+            ])
     }
 
     /// Constructs a [`Run`] if clean subcommand is active.
     pub fn check_matches(matches: &clap::ArgMatches) -> Option<Res<Run>> {
         matches
             .subcommand_matches(CMD_NAME)
-            .map(|_matches| Run::new())
+            .map(|matches| Run::new(matches.is_present(ALL_KEY)))
     }
 }
 
@@ -31,28 +44,38 @@ pub mod cla {
 pub struct Run {
     /// Target directory configuration.
     pub target: conf::Target,
+    /// If true, also delete TLC checkpoints and saved counterexamples.
+    pub all: bool,
 }
 impl Run {
     /// Constructor.
-    pub fn new() -> Res<Self> {
+    pub fn new(all: bool) -> Res<Self> {
         let project_path = conf::top_cla::project_path()?;
         let target = conf::Target::new_run(&project_path, false);
-        Ok(Self { target })
+        Ok(Self { target, all })
     }
 
-    /// Launches the uninstall mode.
+    /// Launches the clean mode.
     pub fn launch(&self) -> Res<()> {
         if self.target.target_path.is_dir() {
-            log::info!(
-                "recursively deleting target directory `{}`",
-                self.target.target_path.display()
-            );
-            io::remove_dir_all(&self.target.target_path).with_context(|| {
-                anyhow!(
-                    "failed to recursively delete target directory `{}`",
+            if self.all {
+                log::info!(
+                    "recursively deleting target directory `{}`",
+                    self.target.target_path.display()
+                );
+                io::remove_dir_all(&self.target.target_path).with_context(|| {
+                    anyhow!(
+                        "failed to recursively delete target directory `{}`",
+                        self.target.target_path.display()
+                    )
+                })?;
+            } else {
+                log::info!(
+                    "recursively deleting target directory `{}`, except checkpoints and cexs",
                     self.target.target_path.display()
-                )
-            })?;
+                );
+                Self::clean_dir(&self.target.target_path)?;
+            }
         } else {
             log::warn!(
                 "`{}` {}, nothing to clean",
@@ -67,6 +90,46 @@ impl Run {
 
         Ok(())
     }
+
+    /// Recursively deletes the content of a directory, except checkpoint and cex directories.
+    ///
+    /// Also deletes the directory itself if it ends up empty. Returns true if the directory was
+    /// deleted.
+    fn clean_dir(dir: &io::Path) -> Res<bool> {
+        if conf::target::checkpoint_date(dir)?.is_some() {
+            log::info!("keeping checkpoint `{}`", dir.display());
+            return Ok(false);
+        }
+        if dir
+            .file_name()
+            .map_or(false, |name| name == project::cex_store::DIR_NAME)
+        {
+            log::info!("keeping saved cexs `{}`", dir.display());
+            return Ok(false);
+        }
+        let mut empty = true;
+        for entry in dir
+            .read_dir()
+            .with_context(|| anyhow!("failed to read directory `{}`", dir.display()))?
+        {
+            let path = entry
+                .with_context(|| {
+                    anyhow!("failed to read an entry of directory `{}`", dir.display())
+                })?
+                .path();
+            if path.is_dir() {
+                empty = Self::clean_dir(&path)? && empty;
+            } else {
+                io::remove_file(&path)
+                    .with_context(|| anyhow!("failed to delete file `{}`", path.display()))?;
+            }
+        }
+        if empty {
+            io::remove_dir_all(dir)
+                .with_context(|| anyhow!("failed to delete directory `{}`", dir.display()))?;
+        }
+        Ok(empty)
+    }
 }
 
 #[cfg(feature = "with_clap")]
@@ -75,17 +138,27 @@ mod cla_spec {
 
     /// Clean subcommand name.
     const CMD_NAME: &str = "clean";
+    /// Key for also deleting checkpoints and saved counterexamples.
+    const ALL_KEY: &str = "CLEAN_ALL_KEY";
 
     impl mode::ClaMode for super::Run {
         const SUBCOMMAND_IDENT: &'static str = CMD_NAME;
         const PREREQ: mode::ClaModePrereq = mode::ClaModePrereq::Project;
 
         fn build_command(cmd: clap::Command<'static>) -> clap::Command<'static> {
-            cmd.about("Cleans the current project: deletes the `target` directory.")
-                .args(&[cla::top::project_path_arg()])
+            cmd.about(
+                "Cleans the current project: deletes the `target` directory, \
+                except TLC checkpoints and saved counterexamples.",
+            )
+            .args(&[
+                cla::top::project_path_arg(),
+                clap::Arg::new(ALL_KEY)
+                    .help("Also deletes TLC checkpoints and saved counterexamples")
+                    .long("all"),
+            ])
         }
-        fn build(_matches: &clap::ArgMatches) -> Res<Self> {
-            Self::new()
+        fn build(matches: &clap::ArgMatches) -> Res<Self> {
+            Self::new(matches.is_present(ALL_KEY))
         }
         fn run(self) -> Res<Option<i32>> {
             self.launch()?;
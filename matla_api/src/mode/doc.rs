@@ -0,0 +1,288 @@
+//! Doc mode, generates the documentation of a project in `target/doc`.
+//!
+//! Runs `tla2tex` on all the modules of the project and builds an HTML index of the modules, their
+//! operators and their documentation comments, see [`project::doc`].
+
+prelude!();
+
+/// CLAP stuff.
+#[cfg(feature = "with_clap")]
+pub mod cla {
+    use super::*;
+
+    /// Doc subcommand name.
+    const CMD_NAME: &str = "doc";
+    /// Key for only generating the HTML index.
+    const HTML_ONLY_KEY: &str = "DOC_HTML_ONLY_KEY";
+
+    /// Doc subcommand.
+    pub fn subcommand() -> clap::Command<'static> {
+        clap::Command::new(CMD_NAME)
+            .about("Generates the documentation of the current project in `target/doc`.")
+            .args(&[
+                crate::cla::top::project_path_arg(),
+                clap::Arg::new(HTML_ONLY_KEY)
+                    .help("Only generates the HTML index, does not run `tla2tex`")
+                    .long("html_only"),
+            ])
+    }
+
+    /// Constructs a [`Run`] if doc subcommand is active.
+    pub fn check_matches(matches: &clap::ArgMatches) -> Option<Res<Run>> {
+        matches
+            .subcommand_matches(CMD_NAME)
+            .map(|matches| Run::new(matches.is_present(HTML_ONLY_KEY)))
+    }
+}
+
+/// Name of the HTML index file.
+pub const INDEX_FILE_NAME: &str = "index.html";
+
+/// Runs doc mode.
+#[readonly]
+#[derive(Debug, Clone)]
+pub struct Run {
+    /// Target directory configuration.
+    pub target: conf::Target,
+    /// Source project.
+    pub src_project: project::SourceProject,
+    /// If true, do not run `tla2tex`.
+    pub html_only: bool,
+}
+impl Run {
+    /// Constructor.
+    pub fn new(html_only: bool) -> Res<Self> {
+        let project_path = conf::top_cla::project_path()?;
+        let src_project = project::SourceProject::from_path(&project_path)?;
+        let target = conf::Target::new_doc(&project_path);
+        Ok(Self {
+            target,
+            src_project,
+            html_only,
+        })
+    }
+
+    /// Launches doc mode.
+    pub fn launch(&self) -> Res<()> {
+        let build_path = &self.target.build_path;
+        io::create_dir_all(build_path).with_context(|| {
+            anyhow!(
+                "failed to create documentation directory `{}`",
+                build_path.display()
+            )
+        })?;
+
+        let mut modules = Vec::with_capacity(self.src_project.files.len());
+        for file in self.src_project.files.iter() {
+            if !file.is_tla() {
+                continue;
+            }
+            let doc = project::doc::ModuleDoc::load(file.path())?;
+
+            let mut target_file = build_path.clone();
+            target_file.push(file.file_name()?);
+            io::copy(file.path(), &target_file).with_context(|| {
+                anyhow!(
+                    "failed to copy `{}` to `{}`",
+                    file.path().display(),
+                    target_file.display()
+                )
+            })?;
+
+            if !self.html_only {
+                self.tla2tex(file)?;
+            }
+
+            modules.push(doc);
+        }
+        modules.sort_by(|m1, m2| m1.module.cmp(&m2.module));
+
+        let mut index_path = build_path.clone();
+        index_path.push(INDEX_FILE_NAME);
+        {
+            use io::Write;
+            let index = self.html_index(&modules);
+            let mut index_file = io::write_file(&index_path, true, false)?;
+            index_file.write_all(index.as_bytes()).with_context(|| {
+                anyhow!(
+                    "failed to write documentation index `{}`",
+                    index_path.display()
+                )
+            })?;
+        }
+
+        let styles = conf::Styles::new();
+        println!(
+            "documentation for {} module(s) written to `{}`",
+            modules.len(),
+            styles.bold.paint(index_path.display().to_string()),
+        );
+
+        Ok(())
+    }
+
+    /// Runs `tla2tex` on a file, which must have been copied to the build directory.
+    ///
+    /// Does not fail if `tla2tex` itself fails, which typically happens when LaTeX is not
+    /// installed, only issues a warning.
+    fn tla2tex(&self, file: &project::TlaFile) -> Res<()> {
+        let mut cmd = conf::toolchain::tla2tex_cmd()?;
+        cmd.current_dir(&self.target.build_path)
+            .arg(file.file_name()?);
+        log::debug!("running tla2tex with {:?}", cmd);
+        let output = cmd
+            .output()
+            .with_context(|| anyhow!("failed to run tla2tex on `{}`", file.path().display()))?;
+        if !output.status.success() {
+            log::warn!(
+                "tla2tex failed on module `{}`, is LaTeX installed?",
+                file.module()
+            );
+            for line in String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .chain(String::from_utf8_lossy(&output.stderr).lines())
+            {
+                log::warn!("| {}", line);
+            }
+        }
+        Ok(())
+    }
+
+    /// Links to the files generated for a module, if they exist in the build directory.
+    fn module_links(&self, module: &str) -> Vec<(&'static str, String)> {
+        ["tla", "pdf", "dvi", "tex"]
+            .into_iter()
+            .filter_map(|ext| {
+                let file_name = format!("{}.{}", module, ext);
+                let mut path = self.target.build_path.clone();
+                path.push(&file_name);
+                if path.is_file() {
+                    Some((ext, file_name))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Generates the HTML index.
+    fn html_index(&self, modules: &[project::doc::ModuleDoc]) -> String {
+        fn doc_block(res: &mut String, doc: &[String]) {
+            if !doc.is_empty() {
+                res.push_str("<div class=\"doc\">");
+                res.push_str(&html_escape(doc.join("\n").trim()));
+                res.push_str("</div>\n");
+            }
+        }
+
+        let project_name = self
+            .target
+            .project_path
+            .canonicalize()
+            .ok()
+            .and_then(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+            })
+            .unwrap_or_else(|| "project".into());
+        let title = html_escape(format!("{} documentation", project_name));
+
+        let mut res = String::with_capacity(4096);
+        res.push_str(&format!(
+            "\
+<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; max-width: 60em; margin: auto; padding: 1em; }}
+code, .doc {{ font-family: monospace; }}
+.doc {{ white-space: pre-wrap; margin: .5em 0 1em 1em; }}
+.links a {{ margin-right: 1em; }}
+.local {{ color: gray; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<ul>
+",
+            title = title,
+        ));
+        for module in modules {
+            let name = html_escape(&module.module);
+            res.push_str(&format!(
+                "<li><a href=\"#module-{0}\"><code>{0}</code></a></li>\n",
+                name
+            ));
+        }
+        res.push_str("</ul>\n");
+
+        for module in modules {
+            let name = html_escape(&module.module);
+            res.push_str(&format!(
+                "<h2 id=\"module-{0}\">module <code>{0}</code></h2>\n",
+                name
+            ));
+            let links = self.module_links(&module.module);
+            if !links.is_empty() {
+                res.push_str("<div class=\"links\">");
+                for (ext, file_name) in links {
+                    res.push_str(&format!(
+                        "<a href=\"{}\">{}</a>",
+                        html_escape(file_name),
+                        ext
+                    ));
+                }
+                res.push_str("</div>\n");
+            }
+            doc_block(&mut res, &module.doc);
+
+            for op in module.operators.iter() {
+                res.push_str(&format!(
+                    "<h3 id=\"{}-{}\"{}><code>{}</code></h3>\n",
+                    name,
+                    html_escape(&op.name),
+                    if op.local { " class=\"local\"" } else { "" },
+                    html_escape(&op.signature),
+                ));
+                doc_block(&mut res, &op.doc);
+            }
+        }
+
+        res.push_str("</body>\n</html>\n");
+        res
+    }
+}
+
+#[cfg(feature = "with_clap")]
+mod cla_spec {
+    prelude!();
+
+    /// Doc subcommand name.
+    const CMD_NAME: &str = "doc";
+    /// Key for only generating the HTML index.
+    const HTML_ONLY_KEY: &str = "DOC_HTML_ONLY_KEY";
+
+    impl mode::ClaMode for super::Run {
+        const SUBCOMMAND_IDENT: &'static str = CMD_NAME;
+        const PREREQ: mode::ClaModePrereq = mode::ClaModePrereq::Project;
+
+        fn build_command(cmd: clap::Command<'static>) -> clap::Command<'static> {
+            cmd.about("Generates the documentation of the current project in `target/doc`.")
+                .args(&[
+                    cla::top::project_path_arg(),
+                    clap::Arg::new(HTML_ONLY_KEY)
+                        .help("Only generates the HTML index, does not run `tla2tex`")
+                        .long("html_only"),
+                ])
+        }
+        fn build(matches: &clap::ArgMatches) -> Res<Self> {
+            Self::new(matches.is_present(HTML_ONLY_KEY))
+        }
+        fn run(self) -> Res<Option<i32>> {
+            self.launch()?;
+            Ok(None)
+        }
+    }
+}
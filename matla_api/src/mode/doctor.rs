@@ -0,0 +1,497 @@
+//! Doctor mode, diagnoses matla's environment.
+//!
+//! Checks, in order,
+//! - the user configuration (or the environment in portable mode);
+//! - the project configuration, if any;
+//! - the `tla2tools` jar;
+//! - the java runtime and its version;
+//! - a TLC run on a tiny built-in module, which reports TLC's version;
+//! - the freshness of the project's `Matla` module, if any.
+//!
+//! Each problem comes with a concrete fix. Checks that need a previous check to succeed are
+//! skipped when it fails.
+
+prelude!();
+
+use project::tlc::probe;
+
+/// CLAP stuff.
+#[cfg(feature = "with_clap")]
+pub mod cla {
+    use super::*;
+
+    /// Doctor subcommand name.
+    const CMD_NAME: &str = "doctor";
+
+    /// Doctor subcommand.
+    pub fn subcommand() -> clap::Command<'static> {
+        clap::Command::new(CMD_NAME)
+            .about("Diagnoses your matla setup and suggests fixes for the problems it finds.")
+            .args(&[crate::cla::top::project_path_arg()])
+    }
+
+    /// Constructs a [`Run`] if doctor subcommand is active.
+    pub fn check_matches(matches: &clap::ArgMatches) -> Option<Res<Run>> {
+        matches.subcommand_matches(CMD_NAME).map(|_| Run::new())
+    }
+}
+
+/// Oldest java version TLC runs on.
+pub const JAVA_MIN_VERSION: u32 = 11;
+
+/// Extracts the version and the major version from the output of `java -version`.
+///
+/// Handles both the legacy `1.<major>` scheme and the modern `<major>.<minor>` one.
+pub fn java_version(output: &str) -> Option<(String, u32)> {
+    let line = output.lines().find(|line| line.contains("version"))?;
+    let version = line.split('"').nth(1)?;
+    let mut numbers = version.split(|c: char| !c.is_ascii_digit());
+    let major = match numbers.next()? {
+        "1" => numbers.next()?,
+        major => major,
+    };
+    Some((version.to_string(), major.parse().ok()?))
+}
+
+/// Prints the diagnostics and counts problems.
+struct Report {
+    styles: conf::Styles,
+    errors: usize,
+    warnings: usize,
+}
+impl Report {
+    /// Prefix for all lines in doctor mode.
+    const PREF: &'static str = "| ";
+
+    /// Constructor.
+    fn new() -> Self {
+        Self {
+            styles: conf::Styles::new(),
+            errors: 0,
+            warnings: 0,
+        }
+    }
+
+    /// Reports a check that went fine.
+    fn ok(&self, what: &str, desc: impl fmt::Display) {
+        println!(
+            "{}{} {}: {}",
+            Self::PREF,
+            self.styles.good.paint("ok"),
+            what,
+            desc
+        )
+    }
+    /// Reports a check that could not run.
+    fn skipped(&self, what: &str, why: impl fmt::Display) {
+        println!(
+            "{}{} {}: {}",
+            Self::PREF,
+            self.styles.comment.paint("skipped"),
+            what,
+            why
+        )
+    }
+    /// Reports a problem that does not prevent matla from running.
+    fn warning(&mut self, what: &str, problem: impl fmt::Display, fix: impl fmt::Display) {
+        self.warnings += 1;
+        println!(
+            "{}{} {}: {}",
+            Self::PREF,
+            self.styles.bad.paint("warning"),
+            what,
+            problem
+        );
+        self.fix(fix)
+    }
+    /// Reports a problem that prevents matla from running.
+    fn error(&mut self, what: &str, problem: impl fmt::Display, fix: impl fmt::Display) {
+        self.errors += 1;
+        println!(
+            "{}{} {}: {}",
+            Self::PREF,
+            self.styles.fatal.paint("error"),
+            what,
+            problem
+        );
+        self.fix(fix)
+    }
+    /// Prints the fix for a problem.
+    fn fix(&self, fix: impl fmt::Display) {
+        for (idx, line) in fix.to_string().lines().enumerate() {
+            let pref = if idx == 0 { "fix: " } else { "     " };
+            println!("{}    {}{}", Self::PREF, self.styles.bold.paint(pref), line)
+        }
+    }
+}
+
+/// Runs doctor mode.
+#[readonly]
+#[derive(Debug, Clone)]
+pub struct Run {
+    /// Path to the project directory.
+    pub project_path: io::PathBuf,
+    /// True in portable mode.
+    pub portable: bool,
+}
+impl Run {
+    /// Constructor.
+    pub fn new() -> Res<Self> {
+        Ok(Self {
+            project_path: conf::top_cla::project_path()?,
+            portable: conf::top_cla::portable()?,
+        })
+    }
+
+    /// Launches doctor mode, returns the number of errors found.
+    pub fn launch(&self) -> Res<usize> {
+        println!("|===| matla doctor");
+        let mut report = Report::new();
+
+        let user_ok = self.check_user_conf(&mut report)?;
+        let in_project = self.check_project_conf(&mut report);
+        if user_ok {
+            let jar_ok = self.check_tla2tools(&mut report)?;
+            let java_ok = self.check_java(&mut report)?;
+            if jar_ok && java_ok {
+                self.check_tlc(&mut report)?;
+            } else {
+                report.skipped("TLC", "needs a working java runtime and `tla2tools` jar");
+            }
+        } else {
+            let why = "needs a working user configuration";
+            report.skipped("tla2tools", why);
+            report.skipped("java", why);
+            report.skipped("TLC", why);
+        }
+        if in_project {
+            self.check_matla_module(&mut report)?;
+        }
+
+        println!(
+            "|===| {} error(s), {} warning(s)",
+            report.errors, report.warnings
+        );
+        Ok(report.errors)
+    }
+
+    /// Checks and loads the user configuration, true if it is usable.
+    fn check_user_conf(&self, report: &mut Report) -> Res<bool> {
+        const WHAT: &str = "user configuration";
+        if self.portable {
+            return match conf::user::load_portable() {
+                Ok(()) => {
+                    report.ok(
+                        WHAT,
+                        "portable mode, toolchain retrieved from the environment",
+                    );
+                    Ok(true)
+                }
+                Err(e) => {
+                    report.error(
+                        WHAT,
+                        format!("{:#}", e),
+                        format!(
+                            "add `{}` to your `PATH`, or run `matla setup` to stop using \
+                            portable mode",
+                            conf::toolchain::TLA2TOOLS_DEFAULT_NAME,
+                        ),
+                    );
+                    Ok(false)
+                }
+            };
+        }
+
+        let conf_dir = conf::user::conf_path()?;
+        let toml_path = conf::user::toml_path()?;
+        if !toml_path.is_file() {
+            let problem = if !conf_dir.is_dir() {
+                format!("configuration directory `{}` not found", conf_dir.display())
+            } else if toml_path.exists() {
+                format!("`{}` is not a file", toml_path.display())
+            } else {
+                format!("configuration file `{}` not found", toml_path.display())
+            };
+            report.error(
+                WHAT,
+                problem,
+                "run `matla setup`, or use portable mode with `matla -p ...`",
+            );
+            return Ok(false);
+        }
+        match conf::user::raw_load(&toml_path) {
+            Ok(conf) => {
+                let _prev = conf.register()?;
+                report.ok(WHAT, format!("`{}`", toml_path.display()));
+                Ok(true)
+            }
+            Err(e) => {
+                report.error(
+                    WHAT,
+                    format!("{:#}", e),
+                    format!(
+                        "fix `{}`, or regenerate it with `matla setup --overwrite`",
+                        toml_path.display()
+                    ),
+                );
+                Ok(false)
+            }
+        }
+    }
+
+    /// Checks and loads the project configuration, true if the project path is a matla project.
+    fn check_project_conf(&self, report: &mut Report) -> bool {
+        const WHAT: &str = "project configuration";
+        let toml_path = self.project_path.join(conf::project::TOML_CONFIG_FILENAME);
+        if !toml_path.exists() {
+            report.skipped(
+                WHAT,
+                format!("`{}` is not a matla project", self.project_path.display()),
+            );
+            return false;
+        }
+        match conf::project::load_from(&self.project_path) {
+            Ok(_) => report.ok(WHAT, format!("`{}`", toml_path.display())),
+            Err(e) => report.error(
+                WHAT,
+                format!("{:#}", e),
+                format!(
+                    "fix `{0}`, or delete it and run `matla init` to generate a default `{0}`",
+                    conf::project::TOML_CONFIG_FILENAME,
+                ),
+            ),
+        }
+        true
+    }
+
+    /// Checks the `tla2tools` jar, true if it looks usable.
+    fn check_tla2tools(&self, report: &mut Report) -> Res<bool> {
+        const WHAT: &str = "tla2tools";
+        let jar = conf::toolchain::tla2tools()?;
+        let fix = if self.portable {
+            format!(
+                "add `{}` to your `PATH`",
+                conf::toolchain::TLA2TOOLS_DEFAULT_NAME
+            )
+        } else {
+            "run `matla update` to download it again, or `matla setup --overwrite` to use \
+            another jar"
+                .into()
+        };
+        let problem = if !jar.exists() {
+            format!("`{}` does not exist", jar.display())
+        } else if !jar.is_file() {
+            format!("`{}` is not a file", jar.display())
+        } else {
+            use io::Read;
+            // Jars are zip archives, which start with `PK`.
+            let mut magic = [0u8; 2];
+            let is_zip = io::File::open(&jar)
+                .and_then(|mut file| file.read_exact(&mut magic))
+                .map(|()| &magic == b"PK")
+                .unwrap_or(false);
+            if is_zip {
+                report.ok(WHAT, format!("`{}`", jar.display()));
+                return Ok(true);
+            }
+            format!("`{}` is not a jar, it might be corrupted", jar.display())
+        };
+        report.error(WHAT, problem, fix);
+        Ok(false)
+    }
+
+    /// Checks the java runtime, true if TLC can run.
+    ///
+    /// Problems are only warnings if the launcher has a custom command, since TLC does not need
+    /// java then.
+    fn check_java(&self, report: &mut Report) -> Res<bool> {
+        const WHAT: &str = "java";
+        let launcher = conf::toolchain::launcher()?;
+        let java = launcher
+            .java
+            .clone()
+            .unwrap_or_else(|| conf::launcher::JAVA_DEFAULT.into());
+        let install_fix = format!(
+            "install a java runtime (version {} or later) and make sure `java` is in your \
+            `PATH`,\nor set `java` in the `[launcher]` section of your configuration",
+            JAVA_MIN_VERSION,
+        );
+
+        let output = io::Command::new(&java).arg("-version").output();
+        let (problem, fix) = match output {
+            Err(e) => (format!("failed to run `{}`: {}", java, e), install_fix),
+            Ok(output) => {
+                // `java -version` writes on `stderr`.
+                let text = format!(
+                    "{}{}",
+                    String::from_utf8_lossy(&output.stderr),
+                    String::from_utf8_lossy(&output.stdout),
+                );
+                if !output.status.success() || text.contains("Unable to locate a Java Runtime") {
+                    let first_line = text.lines().next().unwrap_or("no output");
+                    (
+                        format!("`{} -version` failed: {}", java, first_line),
+                        install_fix,
+                    )
+                } else {
+                    match java_version(&text) {
+                        Some((version, major)) if major >= JAVA_MIN_VERSION => {
+                            report.ok(WHAT, format!("`{}`, version {}", java, version));
+                            return Ok(true);
+                        }
+                        Some((version, _)) => (
+                            format!(
+                                "`{}` has version {}, TLC needs version {} or later",
+                                java, version, JAVA_MIN_VERSION
+                            ),
+                            install_fix,
+                        ),
+                        None => {
+                            report.warning(
+                                WHAT,
+                                format!("failed to retrieve the version of `{}`", java),
+                                format!(
+                                    "make sure `{} -version` reports version {} or later",
+                                    java, JAVA_MIN_VERSION
+                                ),
+                            );
+                            return Ok(true);
+                        }
+                    }
+                }
+            }
+        };
+
+        if launcher.command.is_some() {
+            report.warning(
+                WHAT,
+                problem,
+                format!(
+                    "{}\n(TLC uses the launcher's `command`, only `matla doc` needs java)",
+                    fix
+                ),
+            );
+            Ok(true)
+        } else {
+            report.error(WHAT, problem, fix);
+            Ok(false)
+        }
+    }
+
+    /// Runs TLC on a tiny module and retrieves its version.
+    fn check_tlc(&self, report: &mut Report) -> Res<()> {
+        const WHAT: &str = "TLC";
+        let probe::Probe {
+            output,
+            version,
+            no_java,
+        } = match probe::run_current() {
+            Ok(probe) => probe,
+            Err(e) => {
+                report.error(
+                    WHAT,
+                    format!("{:#}", e),
+                    "check the `[launcher]` section of your configuration, in particular `command`",
+                );
+                return Ok(());
+            }
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        if no_java {
+            report.error(
+                WHAT,
+                "no java runtime found",
+                "install a java runtime, see the `java` check above",
+            );
+        } else if !output.status.success() {
+            let details = stderr
+                .lines()
+                .chain(stdout.lines())
+                .find(|line| !line.trim().is_empty() && !line.contains("@!@!@"))
+                .unwrap_or("no output");
+            report.error(
+                WHAT,
+                format!(
+                    "TLC failed on a trivial module ({}): {}",
+                    output.status, details
+                ),
+                "run `matla update` if the jar is corrupted, otherwise check the `[launcher]` \
+                section of your configuration",
+            );
+        } else if let Some(version) = version {
+            report.ok(WHAT, version);
+        } else {
+            report.warning(
+                WHAT,
+                "TLC ran but did not report its version",
+                "make sure the launcher runs TLC with its `-tool` option, \
+                and consider updating TLC with `matla update`",
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that the project's `Matla` module is the one this version of matla generates.
+    fn check_matla_module(&self, report: &mut Report) -> Res<()> {
+        const WHAT: &str = "Matla module";
+        let path = self
+            .project_path
+            .join(format!("{}.tla", project::matla::MATLA_MODULE_NAME));
+        if !path.is_file() {
+            report.skipped(WHAT, "project has no `Matla` module");
+            return Ok(());
+        }
+        let content = io::load_file(&path)?;
+        let mut fresh = false;
+        for release in [false, true] {
+            let mut expected: Vec<u8> = Vec::with_capacity(content.len());
+            project::matla::write_module(&mut expected, release)?;
+            fresh = fresh || content.as_bytes() == &expected[..];
+        }
+        if fresh {
+            report.ok(WHAT, format!("`{}` is up to date", path.display()));
+        } else {
+            report.warning(
+                WHAT,
+                format!(
+                    "`{}` differs from the one this version of matla generates",
+                    path.display()
+                ),
+                "delete it and run `matla init` to regenerate it\n\
+                (builds use a fresh copy, but tools reading the project directly see the old one)",
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "with_clap")]
+mod cla_spec {
+    prelude!();
+
+    /// Doctor subcommand name.
+    const CMD_NAME: &str = "doctor";
+
+    impl mode::ClaMode for super::Run {
+        const SUBCOMMAND_IDENT: &'static str = CMD_NAME;
+        const PREREQ: mode::ClaModePrereq = mode::ClaModePrereq::PreUser;
+
+        fn build_command(cmd: clap::Command<'static>) -> clap::Command<'static> {
+            cmd.about("Diagnoses your matla setup and suggests fixes for the problems it finds.")
+                .args(&[cla::top::project_path_arg()])
+        }
+        fn build(_matches: &clap::ArgMatches) -> Res<Self> {
+            Self::new()
+        }
+        fn run(self) -> Res<Option<i32>> {
+            let errors = self.launch()?;
+            if errors > 0 {
+                Ok(Some(conf::exit_code::ERROR))
+            } else {
+                Ok(None)
+            }
+        }
+    }
+}
@@ -15,6 +15,28 @@ pub mod cla {
     const MAIN_MODULE_KEY: &str = "RUN_MAIN_MODULE_KEY";
     /// Key for showing configuration before running.
     pub const SHOW_CONFIG_KEY: &str = "RUN_SHOW_CONFIG_KEY";
+    /// Key for the output format.
+    const FORMAT_KEY: &str = "RUN_FORMAT_KEY";
+    /// Output format default value.
+    const FORMAT_DEFAULT: &str = "text";
+    /// Key for recovering from a checkpoint.
+    const RECOVER_KEY: &str = "RUN_RECOVER_KEY";
+    /// Key for recording TLC's raw output.
+    const RECORD_KEY: &str = "RUN_RECORD_KEY";
+    /// Key for replaying a recording of TLC's raw output.
+    const REPLAY_KEY: &str = "RUN_REPLAY_KEY";
+    /// Key for the variables cexs only display.
+    const CEX_ONLY_KEY: &str = "RUN_CEX_ONLY_KEY";
+    /// Key for the variables cexs hide.
+    const CEX_HIDE_KEY: &str = "RUN_CEX_HIDE_KEY";
+    /// Key for only displaying the variables that changed in cexs.
+    const CEX_CHANGED_ONLY_KEY: &str = "RUN_CEX_CHANGED_ONLY_KEY";
+    /// Key for cex display hints.
+    const CEX_HINT_KEY: &str = "RUN_CEX_HINT_KEY";
+    /// Key for the cex format.
+    const CEX_FORMAT_KEY: &str = "RUN_CEX_FORMAT_KEY";
+    /// Cex format default value.
+    const CEX_FORMAT_DEFAULT: &str = "text";
 
     // TLC options.
 
@@ -50,6 +72,36 @@ pub mod cla {
     const TIMESTATS_KEY: &str = "RUN_TIMESTATS_KEY";
     /// Print timestats default value.
     const TIMESTATS_DEFAULT: &str = "off";
+    /// Key for the simulate argument.
+    const SIMULATE_KEY: &str = "RUN_SIMULATE_KEY";
+    /// Simulate default value.
+    const SIMULATE_DEFAULT: &str = "off";
+    /// Key for the simulation depth argument.
+    const DEPTH_KEY: &str = "RUN_DEPTH_KEY";
+    /// Simulation depth default value.
+    const DEPTH_DEFAULT: &str = "default";
+    /// Key for the number of simulation traces argument.
+    const NUM_TRACES_KEY: &str = "RUN_NUM_TRACES_KEY";
+    /// Number of simulation traces default value.
+    const NUM_TRACES_DEFAULT: &str = "default";
+    /// Key for the DFID depth argument.
+    const DFID_KEY: &str = "RUN_DFID_KEY";
+    /// DFID depth default value.
+    const DFID_DEFAULT: &str = "off";
+    /// Key for the checkpoint interval argument.
+    const CHECKPOINT_KEY: &str = "RUN_CHECKPOINT_KEY";
+    /// Checkpoint interval default value.
+    const CHECKPOINT_DEFAULT: &str = "default";
+    /// Key for the coverage interval argument.
+    const COVERAGE_KEY: &str = "RUN_COVERAGE_KEY";
+    /// Coverage interval default value.
+    const COVERAGE_DEFAULT: &str = "off";
+    /// Key for the keep going argument.
+    const KEEP_GOING_KEY: &str = "RUN_KEEP_GOING_KEY";
+    /// Keep going default value.
+    const KEEP_GOING_DEFAULT: &str = "off";
+    /// Key for the continue flag, same as keep going on.
+    const CONTINUE_KEY: &str = "RUN_CONTINUE_KEY";
 
     /// TLC arguments, separated for reusability.
     pub fn tlc_args(cmd: clap::Command<'static>) -> clap::Command {
@@ -131,6 +183,58 @@ pub mod cla {
                 .default_value(TIMESTATS_DEFAULT)
                 .value_name(crate::cla::utils::val_name::BOOL)
                 .validator(|s| crate::cla::utils::validate_bool(&s).map(|_| ())),
+            clap::Arg::new(SIMULATE_KEY)
+                .help("(De)activates simulation mode, where TLC checks random behaviors")
+                .long("simulate")
+                .takes_value(true)
+                .default_value(SIMULATE_DEFAULT)
+                .value_name(crate::cla::utils::val_name::BOOL)
+                .validator(|s| crate::cla::utils::validate_bool(&s).map(|_| ())),
+            clap::Arg::new(DEPTH_KEY)
+                .help("Maximum length of simulation traces")
+                .long("depth")
+                .takes_value(true)
+                .default_value(DEPTH_DEFAULT)
+                .value_name(crate::cla::utils::val_name::U64_OR_DEFAULT)
+                .validator(|s| crate::cla::utils::validate_u64_or_default(&s).map(|_| ())),
+            clap::Arg::new(NUM_TRACES_KEY)
+                .help("Number of simulation traces to generate, unbounded by default")
+                .long("num_traces")
+                .takes_value(true)
+                .default_value(NUM_TRACES_DEFAULT)
+                .value_name(crate::cla::utils::val_name::U64_OR_DEFAULT)
+                .validator(|s| crate::cla::utils::validate_u64_or_default(&s).map(|_| ())),
+            clap::Arg::new(DFID_KEY)
+                .help("Activates depth-first iterative deepening up to some depth")
+                .long("dfid")
+                .takes_value(true)
+                .default_value(DFID_DEFAULT)
+                .value_name(crate::cla::utils::val_name::U64_OR_OFF)
+                .validator(|s| crate::cla::utils::validate_u64_or_off(&s).map(|_| ())),
+            clap::Arg::new(CHECKPOINT_KEY)
+                .help("Minutes between two TLC checkpoints, `0` deactivates checkpoints")
+                .long("checkpoint")
+                .takes_value(true)
+                .default_value(CHECKPOINT_DEFAULT)
+                .value_name(crate::cla::utils::val_name::U64_OR_DEFAULT)
+                .validator(|s| crate::cla::utils::validate_u64_or_default(&s).map(|_| ())),
+            clap::Arg::new(COVERAGE_KEY)
+                .help("Minutes between two TLC coverage reports, deactivated by default")
+                .long("coverage")
+                .takes_value(true)
+                .default_value(COVERAGE_DEFAULT)
+                .value_name(crate::cla::utils::val_name::U64_OR_OFF)
+                .validator(|s| crate::cla::utils::validate_u64_or_off(&s).map(|_| ())),
+            clap::Arg::new(KEEP_GOING_KEY)
+                .help("If true, TLC keeps checking after the first violation")
+                .long("keep_going")
+                .takes_value(true)
+                .default_value(KEEP_GOING_DEFAULT)
+                .value_name(crate::cla::utils::val_name::BOOL)
+                .validator(|s| crate::cla::utils::validate_bool(&s).map(|_| ())),
+            clap::Arg::new(CONTINUE_KEY)
+                .help("Same as `--keep_going on`, overrides `--keep_going`")
+                .long("continue"),
             // Done, there's just the optional module to run left.
             clap::Arg::new(MAIN_MODULE_KEY)
                 .help(
@@ -145,13 +249,172 @@ pub mod cla {
         ])
     }
 
+    /// Output format argument.
+    pub fn format_arg() -> clap::Arg<'static> {
+        clap::Arg::new(FORMAT_KEY)
+            .help("Output format, `json` produces one JSON object per line")
+            .long("format")
+            .takes_value(true)
+            .default_value(FORMAT_DEFAULT)
+            .value_name(mode::run::Format::VALUES)
+            .validator(|arg| mode::run::Format::parse(&arg).map(|_| ()))
+    }
+
+    /// Checkpoint recovery argument.
+    pub fn recover_arg() -> clap::Arg<'static> {
+        clap::Arg::new(RECOVER_KEY)
+            .help("Recovers from a checkpoint, the latest one if none is given")
+            .long_help(
+                "\
+                Recovers from a TLC checkpoint, which must be passed as `--recover=<CHECKPOINT>`. \
+                The checkpoint is either a directory in the TLC metadir \
+                `target/<debug|release>/tlc_meta` or the path to a checkpoint directory. \
+                Without a checkpoint, matla recovers from the latest one in the TLC metadir.\
+            ",
+            )
+            .long("recover")
+            .takes_value(true)
+            .min_values(0)
+            .max_values(1)
+            .require_equals(true)
+            .value_name("CHECKPOINT")
+    }
+
+    /// Record and replay arguments.
+    pub fn record_args() -> [clap::Arg<'static>; 2] {
+        [
+            clap::Arg::new(RECORD_KEY)
+                .help("Records TLC's raw output to a file, for diagnosis")
+                .long("record")
+                .takes_value(true)
+                .value_name("FILE"),
+            clap::Arg::new(REPLAY_KEY)
+                .help("Replays a file produced by `--record` instead of running TLC")
+                .long("replay")
+                .takes_value(true)
+                .value_name("FILE")
+                .conflicts_with(RECORD_KEY),
+        ]
+    }
+
+    /// Cex display arguments.
+    pub fn cex_view_args() -> [clap::Arg<'static>; 4] {
+        [
+            clap::Arg::new(CEX_ONLY_KEY)
+                .help("Only displays these state variables in counterexamples")
+                .long("cex_only")
+                .takes_value(true)
+                .use_value_delimiter(true)
+                .value_name("VARS"),
+            clap::Arg::new(CEX_HIDE_KEY)
+                .help("Hides these state variables in counterexamples")
+                .long("cex_hide")
+                .takes_value(true)
+                .use_value_delimiter(true)
+                .value_name("VARS"),
+            clap::Arg::new(CEX_CHANGED_ONLY_KEY)
+                .help("Only displays the state variables that changed in counterexamples")
+                .long("cex_changed_only"),
+            clap::Arg::new(CEX_HINT_KEY)
+                .help("Display hint for a state variable in counterexamples")
+                .long_help(
+                    "\
+                    Display hint for a state variable in counterexamples, can be given several \
+                    times. `table` renders functions, records and sequences as a table, one \
+                    binding per line; `fold:<INT>` renders sets and bags with more than `<INT>` \
+                    elements as their cardinality.\
+                ",
+                )
+                .long("cex_hint")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .value_name("VAR=HINT")
+                .validator(|arg| conf::cex_view::parse_var_hint(&arg).map(|_| ())),
+        ]
+    }
+
+    /// Cex format argument.
+    pub fn cex_format_arg() -> clap::Arg<'static> {
+        clap::Arg::new(CEX_FORMAT_KEY)
+            .help("Format of the counterexamples displayed by text runs")
+            .long("cex-format")
+            .alias("cex_format")
+            .takes_value(true)
+            .default_value(CEX_FORMAT_DEFAULT)
+            .value_name(mode::run::CexFormat::VALUES)
+            .validator(|arg| mode::run::CexFormat::parse(&arg).map(|_| ()))
+    }
+
+    /// Retrieves the cex format from the matches of [`cex_format_arg`].
+    pub fn handle_cex_format_arg(matches: &clap::ArgMatches) -> mode::run::CexFormat {
+        let format = matches
+            .value_of(CEX_FORMAT_KEY)
+            .expect("argument with default value");
+        mode::run::CexFormat::parse(format).expect("fatal error during CLAP")
+    }
+
+    /// Retrieves the cex display configuration from the matches of [`cex_view_args`].
+    pub fn handle_cex_view_args(matches: &clap::ArgMatches) -> conf::CexView {
+        let vars = |key: &str| {
+            matches
+                .values_of(key)
+                .map(|vals| vals.map(|var| var.trim().to_string()).collect::<Set<_>>())
+        };
+        let mut view = conf::CexView::new();
+        view.only = vars(CEX_ONLY_KEY);
+        view.hide = vars(CEX_HIDE_KEY).unwrap_or_default();
+        view.changed_only = matches.is_present(CEX_CHANGED_ONLY_KEY);
+        for hint in matches.values_of(CEX_HINT_KEY).into_iter().flatten() {
+            let (var, hint) =
+                conf::cex_view::parse_var_hint(hint).expect("fatal error during CLAP");
+            let _prev = view.hints.insert(var, hint);
+        }
+        view
+    }
+
+    /// Retrieves the checkpoint to recover from, if any, from the matches of [`subcommand`].
+    ///
+    /// `Some(None)` means recovering from the latest checkpoint.
+    pub fn handle_recover_arg(matches: &clap::ArgMatches) -> Option<Option<String>> {
+        if matches.is_present(RECOVER_KEY) {
+            Some(matches.value_of(RECOVER_KEY).map(String::from))
+        } else {
+            None
+        }
+    }
+
+    /// Retrieves the record and replay files, if any, from the matches of [`record_args`].
+    pub fn handle_record_args(
+        matches: &clap::ArgMatches,
+    ) -> (Option<io::PathBuf>, Option<io::PathBuf>) {
+        (
+            matches.value_of(RECORD_KEY).map(io::PathBuf::from),
+            matches.value_of(REPLAY_KEY).map(io::PathBuf::from),
+        )
+    }
+
+    /// Retrieves the output format from the matches of [`subcommand`].
+    pub fn handle_format_arg(matches: &clap::ArgMatches) -> mode::run::Format {
+        let format = matches
+            .value_of(FORMAT_KEY)
+            .expect("argument with default value");
+        mode::run::Format::parse(format).expect("fatal error during CLAP")
+    }
+
     /// Run subcommand.
     pub fn subcommand() -> clap::Command<'static> {
         let cmd = clap::Command::new(CMD_NAME)
             .about("Runs TLC on a TLA module in a project directory.")
-            .args(&[clap::Arg::new(SHOW_CONFIG_KEY)
-                .help("Displays the options that matla will use to run TLC")
-                .long("show_tlc_config")]);
+            .args(&[
+                clap::Arg::new(SHOW_CONFIG_KEY)
+                    .help("Displays the options that matla will use to run TLC")
+                    .long("show_tlc_config"),
+                format_arg(),
+                recover_arg(),
+                cex_format_arg(),
+            ])
+            .args(&record_args())
+            .args(&cex_view_args());
         tlc_args(cmd)
     }
 
@@ -207,6 +470,38 @@ pub mod cla {
             TIMESTATS_KEY with
             |val| crate::cla::utils::validate_bool(val)
         );
+        let simulate = retrieve!(
+            SIMULATE_KEY with
+            |val| crate::cla::utils::validate_bool(val)
+        );
+        let depth = retrieve!(
+            DEPTH_KEY with
+            |val| crate::cla::utils::validate_u64_or_default(val)
+        );
+        let num_traces = retrieve!(
+            NUM_TRACES_KEY with
+            |val| crate::cla::utils::validate_u64_or_default(val)
+        );
+        let dfid = retrieve!(
+            DFID_KEY with
+            |val| crate::cla::utils::validate_u64_or_off(val)
+        );
+        let checkpoint = retrieve!(
+            CHECKPOINT_KEY with
+            |val| crate::cla::utils::validate_u64_or_default(val)
+        );
+        let coverage = retrieve!(
+            COVERAGE_KEY with
+            |val| crate::cla::utils::validate_u64_or_off(val)
+        );
+        let keep_going = if matches.is_present(CONTINUE_KEY) {
+            Some(true)
+        } else {
+            retrieve!(
+                KEEP_GOING_KEY with
+                |val| crate::cla::utils::validate_bool(val)
+            )
+        };
         let cla = conf::customl::TlcCla::new(
             conf::customl::Source::Cla,
             workers,
@@ -217,6 +512,13 @@ pub mod cla {
             check_deadlocks,
             print_callstack,
             timestats,
+            simulate,
+            depth,
+            num_traces,
+            dfid,
+            checkpoint,
+            coverage,
+            keep_going,
         );
 
         let main_module = if let Some(main) = matches.value_of(MAIN_MODULE_KEY) {
@@ -239,14 +541,89 @@ pub mod cla {
     pub fn check_matches(matches: &clap::ArgMatches) -> Option<Res<mode::run::Run>> {
         matches.subcommand_matches(CMD_NAME).map(|matches| {
             let show_config = matches.is_present(SHOW_CONFIG_KEY);
+            let format = handle_format_arg(matches);
+
+            let recover = handle_recover_arg(matches);
+            let cex_view = handle_cex_view_args(matches);
+            let cex_format = handle_cex_format_arg(matches);
+            let (record, replay) = handle_record_args(matches);
 
             let (tlc_cla, main_module, release) = handle_tlc_args(matches);
 
-            mode::run::Run::new(release, main_module, tlc_cla, show_config)
+            mode::run::Run::new(release, main_module, tlc_cla, show_config, format).map(|run| {
+                run.recover(recover)
+                    .cex_view(cex_view)
+                    .cex_format(cex_format)
+                    .record(record)
+                    .replay(replay)
+            })
         })
     }
 }
 
+/// Name of the HTML coverage report written in the build directory.
+pub const COVERAGE_REPORT_FILE_NAME: &str = "coverage.html";
+
+/// Output format of a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Human-oriented, styled text.
+    Text,
+    /// JSON-lines: one JSON object per event, see [`JsonOutputHandler`].
+    Json,
+}
+impl Format {
+    /// Value description for format arguments.
+    pub const VALUES: &'static str = "text|json";
+
+    /// Parses a format.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("expected {}", Self::VALUES)),
+        }
+    }
+}
+
+/// Format of the cexs displayed by text runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CexFormat {
+    /// Vertical layout of [`cex::pretty`].
+    Text,
+    /// One row per state, one column per variable, see [`cex::table`].
+    Table,
+    /// Graphviz graph, see [`cex::dot`].
+    Dot,
+}
+impl CexFormat {
+    /// Value description for cex format arguments.
+    pub const VALUES: &'static str = "text|table|dot";
+
+    /// Parses a cex format.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "text" => Ok(Self::Text),
+            "table" => Ok(Self::Table),
+            "dot" => Ok(Self::Dot),
+            _ => Err(format!("expected {}", Self::VALUES)),
+        }
+    }
+
+    /// Renders a cex.
+    pub fn render(self, spec: &cex::pretty::Spec, cex: &cex::Cex) -> String {
+        match self {
+            Self::Text => {
+                let mut buf = String::new();
+                spec.cex_to_ml_string(cex, &mut buf);
+                buf
+            }
+            Self::Table => cex::table::render(spec, cex, cex::table::Flavor::Terminal),
+            Self::Dot => cex::dot::render(spec, cex),
+        }
+    }
+}
+
 /// Runs setup mode.
 #[readonly]
 #[derive(Debug, Clone)]
@@ -263,6 +640,18 @@ pub struct Run {
     pub tlc_cla: conf::customl::TlcCla,
     /// If true, display the options passed to TLC.
     pub show_config: bool,
+    /// Output format.
+    pub format: Format,
+    /// Checkpoint to recover from, `Some(None)` for the latest one.
+    pub recover: Option<Option<String>>,
+    /// Cex display configuration, applied on top of the project's.
+    pub cex_view: conf::CexView,
+    /// Format of the cexs displayed by text runs.
+    pub cex_format: CexFormat,
+    /// File to record TLC's raw output to, if any.
+    pub record: Option<io::PathBuf>,
+    /// Recording to replay instead of running TLC, if any.
+    pub replay: Option<io::PathBuf>,
 }
 impl Run {
     /// Constructor.
@@ -274,6 +663,7 @@ impl Run {
         main_module: Option<String>,
         tlc_cla: conf::customl::TlcCla,
         show_config: bool,
+        format: Format,
     ) -> Res<Self> {
         let target = conf::Target::new_run(conf::top_cla::project_path()?, release);
         Ok(Self {
@@ -283,9 +673,69 @@ impl Run {
             error_count: 0,
             tlc_cla,
             show_config,
+            format,
+            recover: None,
+            cex_view: conf::CexView::new(),
+            cex_format: CexFormat::Text,
+            record: None,
+            replay: None,
         })
     }
 
+    /// Sets the checkpoint to recover from, `Some(None)` for the latest one.
+    pub fn recover(mut self, recover: Option<Option<String>>) -> Self {
+        self.recover = recover;
+        self
+    }
+
+    /// Sets the cex display configuration, applied on top of the project's.
+    pub fn cex_view(mut self, cex_view: conf::CexView) -> Self {
+        self.cex_view = cex_view;
+        self
+    }
+
+    /// Sets the format of the cexs displayed by text runs.
+    pub fn cex_format(mut self, cex_format: CexFormat) -> Self {
+        self.cex_format = cex_format;
+        self
+    }
+
+    /// Sets the file to record TLC's raw output to, see [`project::tlc::record`].
+    pub fn record(mut self, record: Option<io::PathBuf>) -> Self {
+        self.record = record;
+        self
+    }
+
+    /// Sets the recording to replay instead of running TLC, see [`project::tlc::record`].
+    pub fn replay(mut self, replay: Option<io::PathBuf>) -> Self {
+        self.replay = replay;
+        self
+    }
+
+    /// Cex pretty-printing spec, applies the project's cex display configuration and then
+    /// [`Self::cex_view`].
+    fn cex_spec(&self) -> cex::pretty::Spec {
+        let mut view = conf::project::read(|project| project.cex_view.clone()).unwrap_or_default();
+        view.receive(&self.cex_view);
+        cex::pretty::Spec::default().view(view)
+    }
+
+    /// Retrieves the checkpoint directory to recover from, if any.
+    fn checkpoint(&self) -> Res<Option<io::PathBuf>> {
+        let checkpoint = match self.recover.as_ref() {
+            None => return Ok(None),
+            Some(None) => self.target.latest_checkpoint()?.ok_or_else(|| {
+                anyhow!(
+                    "cannot recover, no checkpoint found in `{}`",
+                    self.target.metadir_path.display()
+                )
+            })?,
+            Some(Some(checkpoint)) => self.target.checkpoint(checkpoint)?,
+        };
+        log::info!("recovering from checkpoint `{}`", checkpoint.display());
+        io::try_canonicalize(checkpoint, true).map(Some)
+    }
+
     fn sorry_about_tlc() -> Error {
         let styles = conf::Styles::new();
         anyhow!(
@@ -316,48 +766,122 @@ impl Run {
         let project = project::SourceProject::from_path(&project_path)?;
 
         log::info!("creating actual build project");
-        let (project, tlc_cla) = project.into_full(
+        let (mut project, tlc_cla) = project.into_full(
             self.main_module.clone(),
             self.target.clone(),
             Some(&self.tlc_cla),
         )?;
+        project.tlc_cla.recover = self.checkpoint()?;
         log::debug!("- building to `{}`", project.target.path()?.display());
 
-        if self.show_config {
+        // TLC-level arguments and TLC command, if asked to show them.
+        let config = if self.show_config {
             let mut bytes = Vec::<u8>::with_capacity(666);
             tlc_cla
                 .ser_toml_source(&mut bytes, true)
                 .context("failed to write TLC CLAs to bytes")?;
-            let s = String::from_utf8_lossy(&bytes);
-            println!("|===| TLC-level arguments");
-            for line in s.lines() {
-                println!("| {}", line);
-            }
-            println!("|===|");
-            let cmd = project.full_tlc_cmd(true)?;
-            print!("> {}", cmd.get_program().to_string_lossy());
-            for arg in cmd.get_args() {
-                print!(" \\\n    {}", arg.to_string_lossy());
+            let cla = String::from_utf8_lossy(&bytes).into_owned();
+            Some((cla, project.full_tlc_cmd(true)?))
+        } else {
+            None
+        };
+
+        log::info!("starting run on `{}`", project.actual_entry);
+        match self.format {
+            Format::Text => {
+                if let Some((cla, cmd)) = config.as_ref() {
+                    Self::print_config(cla, cmd)
+                }
+                self.text_run(&project)
             }
-            println!();
-            if let Some(path) = cmd.get_current_dir() {
-                println!("| in `{}`", path.display());
+            Format::Json => self.json_run(&project, config.as_ref()),
+        }
+    }
+
+    /// Prints the TLC-level arguments and the TLC command of a run, for `--show_tlc_config`.
+    fn print_config(cla: &str, cmd: &io::Command) {
+        println!("|===| TLC-level arguments");
+        for line in cla.lines() {
+            println!("| {}", line);
+        }
+        println!("|===|");
+        print!("> {}", cmd.get_program().to_string_lossy());
+        for arg in cmd.get_args() {
+            print!(" \\\n    {}", arg.to_string_lossy());
+        }
+        println!();
+        if let Some(path) = cmd.get_current_dir() {
+            println!("| in `{}`", path.display());
+        }
+        println!()
+    }
+
+    /// Launches TLC, or replays [`Self::replay`] if any.
+    fn tlc_run<Out: project::tlc::Out>(
+        &self,
+        project: &project::FullProject,
+        output_handler: Out,
+    ) -> Res<project::tlc::TlcRun<Out>> {
+        if let Some(path) = self.replay.as_ref() {
+            log::info!("replaying `{}`", path.display());
+            let recording = project::tlc::record::Recording::load(path)?;
+            return Ok(project.replay_tlc(recording, output_handler));
+        }
+        let tlc = project.run_tlc_async(output_handler).with_context(|| {
+            anyhow!("failed to launch TLC on module `{}`", project.actual_entry)
+        })?;
+        match self.record.as_ref() {
+            Some(path) => {
+                log::info!("recording TLC's output to `{}`", path.display());
+                tlc.record(path)
             }
-            println!()
+            None => Ok(tlc),
         }
+    }
 
-        log::info!("starting run on `{}`", project.actual_entry);
-        let mut output_handler = TlcOutputHandler::new(log::LevelFilter::Warn, &project);
-        let tlc = project
-            .run_tlc_async(&mut output_handler)
-            .with_context(|| {
-                anyhow!("failed to launch TLC on module `{}`", project.actual_entry)
-            })?;
+    /// Runs TLC and reports everything as JSON lines on `stdout`.
+    ///
+    /// The TLC-level arguments and TLC command in `config`, if any, are reported as a `config`
+    /// event.
+    fn json_run(
+        &self,
+        project: &project::FullProject,
+        config: Option<&(String, io::Command)>,
+    ) -> Res<i32> {
+        let mut output_handler = JsonOutputHandler::new(std::io::stdout()).save_cexs(project);
+        if let Some((cla, cmd)) = config {
+            output_handler.config(cla, cmd)?;
+        }
+        let tlc = self.tlc_run(project, &mut output_handler)?;
         let outcome = tlc.run().with_context(Self::sorry_about_tlc)?;
         let concise = outcome.to_concise();
+        if concise.is_unknown() {
+            bail!("failed to retrieve TLC process exit code or run outcome");
+        }
+        output_handler.finalize(&outcome, concise)?;
+        Ok(concise.to_exit_code())
+    }
+
+    /// Runs TLC and reports everything as styled text.
+    fn text_run(&self, project: &project::FullProject) -> Res<i32> {
+        let mut output_handler = TlcOutputHandler::new(log::LevelFilter::Warn, project)
+            .cex_spec(self.cex_spec())
+            .cex_format(self.cex_format);
+        let tlc = self.tlc_run(project, &mut output_handler)?;
+        let outcome = tlc.run();
+        output_handler.clear_progress();
+        let outcome = outcome.with_context(Self::sorry_about_tlc)?;
+        let concise = outcome.to_concise();
 
         let style = conf::Styles::new();
 
+        if project.tlc_cla.coverage.is_some() {
+            self.report_coverage(project, output_handler.coverage(), &style)?;
+        }
+        if project.tlc_cla.keep_going && !output_handler.violations().is_empty() {
+            output_handler.violations().print(&style);
+        }
+
         let runtime = time::chrono_duration_fmt(&outcome.runtime);
         if project.tlc_cla.timestats {
             println!("done in {}", style.bold.paint(runtime));
@@ -403,6 +927,38 @@ impl Run {
 
         Ok(concise.to_exit_code())
     }
+
+    /// Prints the per-module coverage summary and writes the HTML coverage report.
+    fn report_coverage(
+        &self,
+        project: &project::FullProject,
+        coverage: &project::tlc::coverage::Coverage,
+        style: &conf::Styles,
+    ) -> Res<()> {
+        if coverage.is_empty() {
+            log::warn!("TLC did not report any coverage statistics");
+            return Ok(());
+        }
+        println!("{}:", style.uline.paint("coverage"));
+        for line in coverage.summary(project, style)? {
+            println!("  {}", line);
+        }
+
+        let mut path = self.target.build_path.clone();
+        path.push(COVERAGE_REPORT_FILE_NAME);
+        {
+            use io::Write;
+            let report = coverage.html_report(project)?;
+            let mut file = io::write_file(&path, true, false)?;
+            file.write_all(report.as_bytes())
+                .with_context(|| anyhow!("failed to write coverage report `{}`", path.display()))?;
+        }
+        println!(
+            "  report written to `{}`",
+            style.bold.paint(path.display().to_string()),
+        );
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -412,14 +968,36 @@ pub struct TlcOutputHandler<'a> {
     last_progress_update: time::Instant,
     progress_update_time_delta: time::Duration,
     style: conf::Styles,
+    /// Cex pretty-printing spec.
+    cex_spec: cex::pretty::Spec,
+    /// Cex format.
+    cex_format: CexFormat,
     cexs: Vec<cex::Cex>,
+    /// Number of cexs per falsified property.
+    violations: Violations,
     project: &'a project::FullProject,
+    /// True if progress is reported on a single line redrawn in place.
+    tty: bool,
+    /// True if the live progress line is currently displayed.
+    live_line: bool,
+    /// Latest search depth reported by TLC.
+    search_depth: Option<usize>,
+    /// Coverage statistics.
+    coverage: project::tlc::coverage::Coverage,
 }
 impl<'a> TlcOutputHandler<'a> {
     /// True if there are counterexamples.
     pub fn has_cexs(&self) -> bool {
         !self.cexs.is_empty()
     }
+    /// Coverage statistics reported by TLC.
+    pub fn coverage(&self) -> &project::tlc::coverage::Coverage {
+        &self.coverage
+    }
+    /// Number of counterexamples per falsified property.
+    pub fn violations(&self) -> &Violations {
+        &self.violations
+    }
 }
 impl<'a> TlcOutputHandler<'a> {
     /// Constructor.
@@ -430,15 +1008,162 @@ impl<'a> TlcOutputHandler<'a> {
             last_progress_update: time::Instant::now(),
             progress_update_time_delta: time::Duration::from_secs(1),
             style: conf::Styles::new(),
+            cex_spec: cex::pretty::Spec::default(),
+            cex_format: CexFormat::Text,
             cexs: vec![],
+            violations: Violations::new(),
             project,
+            tty: io::stdout_is_tty(),
+            live_line: false,
+            search_depth: None,
+            coverage: project::tlc::coverage::Coverage::new(),
         }
     }
 
+    /// Sets the cex pretty-printing spec.
+    pub fn cex_spec(mut self, cex_spec: cex::pretty::Spec) -> Self {
+        self.cex_spec = cex_spec;
+        self
+    }
+
+    /// Sets the cex format.
+    pub fn cex_format(mut self, cex_format: CexFormat) -> Self {
+        self.cex_format = cex_format;
+        self
+    }
+
     /// True if some TLC-output log-level is active.
     pub fn is_log_active(&self, level: log::Level) -> bool {
         self.tlc_log_level.map(|l| level <= l).unwrap_or(false)
     }
+
+    /// Erases the live progress line, if any.
+    ///
+    /// Must be called before anything else is written to the terminal.
+    pub fn clear_progress(&mut self) {
+        use io::Write;
+        if self.live_line {
+            print!("\r\x1b[2K");
+            let _ = std::io::stdout().flush();
+            self.live_line = false;
+        }
+    }
+
+    /// Displays a progress line made of some comma-separated items.
+    ///
+    /// The line starts with the elapsed time and ends with the search depth, if known. On a TTY the
+    /// line is redrawn in place, otherwise it is printed as a plain line. Does nothing if state stats
+    /// are not active or if the previous update is too recent.
+    fn show_progress(&mut self, items: Vec<String>) {
+        use io::Write;
+        let now = time::Instant::now();
+        if !vlog!(if state stats { true } else { false })
+            || now - self.last_progress_update < self.progress_update_time_delta
+        {
+            return;
+        }
+        self.last_progress_update = now;
+
+        let elapsed = (now - self.start_instant).as_secs();
+        let mut line = format!(
+            "[{:0>2}:{:0>2}:{:0>2}] ",
+            elapsed / 3600,
+            (elapsed / 60) % 60,
+            elapsed % 60,
+        );
+        line.push_str(&items.join(", "));
+        if let Some(depth) = self.search_depth {
+            line.push_str(&format!(
+                ", depth {}",
+                self.style.bold.paint(depth.to_string())
+            ));
+        }
+
+        if self.tty {
+            print!("\r\x1b[2K{}", line);
+            let _ = std::io::stdout().flush();
+            self.live_line = true;
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    /// Displays state-exploration progress.
+    ///
+    /// `left` is `None` in depth-first iterative deepening mode, which has no queue.
+    fn show_state_progress(
+        &mut self,
+        (gene, gene_spm): (&(Int, String), Option<&(Int, String)>),
+        (dist, dist_spm): (&(Int, String), Option<&(Int, String)>),
+        left: Option<&(Int, String)>,
+    ) {
+        let per_minute = |spm: Option<&(Int, String)>| {
+            spm.map(|(_, spm)| format!(" ({}/min)", self.style.ita.paint(spm)))
+                .unwrap_or_default()
+        };
+        let mut items = vec![
+            format!(
+                "{} generated{}",
+                self.style.bold.paint(&gene.1),
+                per_minute(gene_spm),
+            ),
+            format!(
+                "{} {}{}",
+                self.style.bold.paint(&dist.1),
+                self.style.good.paint("distinct"),
+                per_minute(dist_spm),
+            ),
+        ];
+        if let Some((_, left)) = left {
+            items.push(format!("{} on queue", self.style.bold.paint(left)));
+        }
+        self.show_progress(items)
+    }
+
+    /// Displays the final state statistics.
+    ///
+    /// `left` is `None` in depth-first iterative deepening mode, which has no queue.
+    fn show_final_stats(
+        &mut self,
+        gene: &(Int, String),
+        dist: &(Int, String),
+        left: Option<&(Int, String)>,
+    ) {
+        use std::cmp::max;
+        self.clear_progress();
+        let (gene, dist, left) = (&gene.1, &dist.1, left.map(|left| &left.1));
+        let align = max(
+            gene.len(),
+            max(dist.len(), left.map(String::len).unwrap_or(0)),
+        );
+        let runtime = vlog!(if time stats {
+            let runtime = time::Instant::now() - self.start_instant;
+            format!(
+                "\n  runtime: {}",
+                self.style.bold.paint(time::duration_fmt(runtime))
+            )
+        } else {
+            "".into()
+        });
+        let left = if let Some(left) = left {
+            format!(
+                "\n  {} left on queue",
+                self.style.bold.paint(format!("{left:>align$}")),
+            )
+        } else {
+            "".into()
+        };
+        vlog!(
+            state stats | "{header}:{runtime}\n  \
+            {gene} generated\n  \
+            {dist} {emph_dist}{left}\
+            ",
+            header = self.style.uline.paint("final state stats"),
+            gene = self.style.bold.paint(format!("{gene:>align$}")),
+            dist = self.style.bold.paint(format!("{dist:>align$}")),
+            emph_dist = self.style.good.paint("distinct"),
+        )
+    }
 }
 
 mod msg_handling {
@@ -452,6 +1177,7 @@ mod msg_handling {
             use code::Status;
             match msg {
                 Status::TlcInitGenerated1 { state_count, .. } => {
+                    self.clear_progress();
                     vlog!(state stats|
                         "{}:\n  {}",
                         self.style.uline.paint("distinct initial state(s)"),
@@ -459,6 +1185,7 @@ mod msg_handling {
                     )
                 }
                 Status::TlcFinished { runtime } => {
+                    self.clear_progress();
                     vlog!(state stats|
                         "\n{} in {}",
                         self.style.uline.paint("done"),
@@ -469,104 +1196,106 @@ mod msg_handling {
             }
         }
 
+        /// Handles checkpoint-related messages, `lines` are the lines of the TLC message.
+        pub fn handle_msg_checkpoint(&mut self, msg: &code::Tlc, lines: &[&str]) {
+            use code::Tlc;
+            let header = match msg {
+                Tlc::TlcCheckpointStart => "checkpointing",
+                Tlc::TlcCheckpointEnd => "checkpoint done",
+                Tlc::TlcCheckpointRecoverStart => "recovering from checkpoint",
+                Tlc::TlcCheckpointRecoverEnd | Tlc::TlcCheckpointRecoverEndDfid => "recovery done",
+                _ => return,
+            };
+            self.clear_progress();
+            vlog!(
+                state stats | "{}:\n  {}",
+                self.style.uline.paint(header),
+                lines.join("\n  "),
+            )
+        }
+
         /// Handles a [`code::Tlc`].
         pub fn handle_msg_tlc(&mut self, msg: &code::Tlc) {
             use code::Tlc;
-
-            macro_rules! state_stats {
-                (
-                    // bool
-                    last: $is_last:expr,
-                    gene: ($gene:expr, $gene_spm:expr),
-                    dist: ($dist:expr, $dist_spm:expr),
-                    left: $left:expr $(,)?
-                ) => {
-                    let now = time::Instant::now();
-                    let actually_output =
-                        vlog!(if state stats { true } else { false })
-                        && (
-                            $is_last
-                            || now - self.last_progress_update >= self.progress_update_time_delta
-                        );
-                    if actually_output {
-                        self.last_progress_update = now;
-                        use std::cmp::max;
-                        let (gene, dist, left) = (&$gene.1, &$dist.1, &$left.1);
-                        let (gene_spm, dist_spm): (&Option<(Int, String)>, &Option<(Int, String)>) =
-                            ($gene_spm, $dist_spm);
-                        let align = max(gene.len(), max(dist.len(), left.len()));
-                        let gene_spm = if let Some((_, gen)) = gene_spm {
-                            format!(", {} per minute", self.style.ita.paint(gen))
-                        } else {
-                            "".into()
-                        };
-                        let dist_spm = if let Some((_, dist)) = dist_spm {
-                            format!(", {} per minute", self.style.ita.paint(dist))
-                        } else {
-                            "".into()
-                        };
-                        let runtime = vlog!(
-                            if time stats {
-                                let runtime = time::Instant::now() - self.start_instant;
-                                format!("\n  runtime: {}", self.style.bold.paint(time::duration_fmt(runtime)))
-                            } else {
-                                "".into()
-                            }
-                        );
-                        let header = if $is_last {
-                            "final state stats"
-                        } else {
-                            "state stats"
-                        };
-                        vlog!(
-                            state stats
-                                | "{header}:{runtime}\n  \
-                                {gene:>align$} generated{gene_spm}\n  \
-                                {dist:>align$} {emph_dist}{dist_spm}\n  \
-                                {left:>align$} left on queue\
-                                ",
-                                header = self.style.uline.paint(header),
-                                gene = self.style.bold.paint(format!("{gene:>align$}")),
-                                dist = self.style.bold.paint(format!("{dist:>align$}")),
-                                emph_dist = self.style.good.paint("distinct"),
-                                left = self.style.bold.paint(format!("{left:>align$}")),
-                                align = align,
-                        )
-                    }
-                }
+            if self.coverage.handle(msg) {
+                return;
             }
-
             match msg {
-                Tlc::TlcSearchDepth { depth } => vlog!(
-                    state stats | "{}:\n  {}",
-                    self.style.uline.paint("search depth"),
-                    self.style.bold.paint(depth.to_string()),
-                ),
+                Tlc::TlcSearchDepth { depth } => {
+                    self.search_depth = Some(*depth);
+                    self.clear_progress();
+                    vlog!(
+                        state stats | "{}:\n  {}",
+                        self.style.uline.paint("search depth"),
+                        self.style.bold.paint(depth.to_string()),
+                    )
+                }
                 Tlc::TlcStats {
                     generated,
                     distinct,
                     left,
-                } => {
-                    state_stats! {
-                        last: true,
-                        gene: (generated, &None),
-                        dist: (distinct, &None),
-                        left: left,
-                    }
-                }
+                } => self.show_final_stats(generated, distinct, Some(left)),
                 Tlc::TlcProgressStats {
+                    depth,
                     generated,
                     gen_spm,
                     distinct,
                     dist_spm,
                     left,
                 } => {
-                    state_stats! {
-                        last: false,
-                        gene: (generated, gen_spm),
-                        dist: (distinct, dist_spm),
-                        left: left,
+                    self.search_depth = Some(*depth);
+                    self.show_state_progress(
+                        (generated, gen_spm.as_ref()),
+                        (distinct, dist_spm.as_ref()),
+                        Some(left),
+                    )
+                }
+                Tlc::TlcStatsDfid {
+                    generated,
+                    distinct,
+                } => self.show_final_stats(generated, distinct, None),
+                Tlc::TlcProgressStatsDfid {
+                    generated,
+                    gen_spm,
+                    distinct,
+                    dist_spm,
+                } => self.show_state_progress(
+                    (generated, gen_spm.as_ref()),
+                    (distinct, dist_spm.as_ref()),
+                    None,
+                ),
+                Tlc::TlcProgressStartStatsDfid {
+                    level,
+                    generated,
+                    distinct,
+                } => {
+                    self.search_depth = Some(*level);
+                    self.show_state_progress((generated, None), (distinct, None), None)
+                }
+                Tlc::TlcProgressSimu {
+                    checked,
+                    traces,
+                    mean_length,
+                } => {
+                    let mut items = vec![format!(
+                        "{} states checked",
+                        self.style.bold.paint(&checked.1)
+                    )];
+                    if let Some((_, traces)) = traces {
+                        items.push(format!("{} traces", self.style.bold.paint(traces)));
                     }
+                    if let Some(mean) = mean_length {
+                        items.push(format!("mean length {}", self.style.ita.paint(mean)));
+                    }
+                    self.show_progress(items)
+                }
+                Tlc::TlcStatsSimu { generated } => {
+                    self.clear_progress();
+                    vlog!(
+                        state stats | "{}:\n  {} states generated",
+                        self.style.uline.paint("final simulation stats"),
+                        self.style.bold.paint(&generated.1),
+                    )
                 }
                 _ => (),
             }
@@ -584,49 +1313,19 @@ impl<'a> project::tlc::Out for TlcOutputHandler<'a> {
         // Special handling for progress updates.
         use project::tlc::code::*;
         match msg.code.as_ref() {
+            Some(TopMsg::Msg(Msg::Tlc(TlcMsg::Msg(
+                tlc_msg @ (Tlc::TlcCheckpointStart
+                | Tlc::TlcCheckpointEnd
+                | Tlc::TlcCheckpointRecoverStart
+                | Tlc::TlcCheckpointRecoverEnd
+                | Tlc::TlcCheckpointRecoverEndDfid),
+            )))) => self.handle_msg_checkpoint(tlc_msg, &msg.lines()),
             Some(TopMsg::Msg(Msg::Tlc(TlcMsg::Msg(tlc_msg)))) => self.handle_msg_tlc(tlc_msg),
             Some(TopMsg::Msg(Msg::Status(tlc_status))) => self.handle_msg_status(tlc_status),
-            // Some(TopMsg::Msg(Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressStats {
-            //     generated,
-            //     gen_spm,
-            //     distinct,
-            //     dist_spm,
-            //     left,
-            // })))) => {
-            //     let now = time::Instant::now();
-            //     if self.last_progress_update - now >= self.progress_update_time_delta {
-            //         println!(
-            //             "generated {} states so far ({} distinct states)",
-            //             pretty_usize(*generated),
-            //             pretty_usize(*distinct),
-            //         );
-
-            //         // State-per-minute and states on queue update.
-            //         if self.is_log_active(log::Level::Debug) {
-            //             if gen_spm.is_some() || dist_spm.is_some() {
-            //                 print!("↪ ");
-            //                 let mut sep = "";
-            //                 if let Some(gen) = *gen_spm {
-            //                     print!("{} state per minute", pretty_usize(gen));
-            //                     sep = ", ";
-            //                 }
-            //                 if let Some(dist) = *dist_spm {
-            //                     print!(
-            //                         "{}{} distinct state per minute",
-            //                         sep,
-            //                         pretty_usize(dist),
-            //                     );
-            //                 }
-            //                 println!();
-            //             }
-            //             println!("{} states left on queue", left);
-            //         }
-            //     }
-            //     return;
-            // }
             Some(_) | None => (),
         }
         if self.is_log_active(log_level) {
+            self.clear_progress();
             for line in msg.lines() {
                 println!("{}", line);
             }
@@ -634,6 +1333,7 @@ impl<'a> project::tlc::Out for TlcOutputHandler<'a> {
     }
 
     fn handle_error(&mut self, error: impl Into<project::tlc::TlcError>) -> Res<()> {
+        self.clear_progress();
         let error = error.into();
         let styles = conf::Styles::new();
         let pretty = error.pretty(self.project, &styles)?;
@@ -651,43 +1351,473 @@ impl<'a> project::tlc::Out for TlcOutputHandler<'a> {
     }
 
     fn handle_cex(&mut self, cex: cex::Cex) {
-        let mut buf = String::new();
-        let spec = cex::pretty::Spec::default();
-        spec.cex_to_ml_string(&cex, &mut buf);
-        let (name_opt, is_temporal) = cex.falsified();
-        match name_opt {
-            Some(name) if is_temporal => println!(
-                "Temporal property {} {}.",
-                self.style.bad.paint(name),
-                self.style.fatal.paint("does not hold"),
-            ),
-            Some(name) => println!(
-                "Invariant {} {}.",
-                self.style.bad.paint(name),
-                self.style.fatal.paint("does not hold"),
-            ),
-            None if is_temporal => println!(
-                "Some temporal property(ies) {}.",
-                self.style.fatal.paint("do not hold"),
-            ),
-            None => println!(
-                "Some invariant(s) {}.",
-                self.style.fatal.paint("do not hold"),
-            ),
-        }
-        println!(
-            "{}",
-            self.style
-                .fatal
-                .paint(self.style.uline.paint("Counterexample:").to_string())
+        self.clear_progress();
+        print_cex(&self.style, &self.cex_spec, self.cex_format, &cex);
+        let saved = self.project.cex_store().save(
+            &self.project.actual_entry,
+            self.project.tlc_cla.seed,
+            cex.clone(),
         );
-        for line in buf.lines() {
-            println!("{}", line)
+        match saved {
+            Ok(saved) => println!(
+                "{}",
+                self.style
+                    .ita
+                    .paint(format!("saved as `{}`", saved.path.display()))
+            ),
+            Err(e) => log::warn!("failed to save counterexample: {:?}", e),
         }
+        self.violations.record(&cex);
         self.cexs.push(cex);
     }
 }
 
+/// Prints a cex and the property it falsifies.
+pub fn print_cex(
+    style: &conf::Styles,
+    spec: &cex::pretty::Spec,
+    format: CexFormat,
+    cex: &cex::Cex,
+) {
+    let buf = format.render(spec, cex);
+    let (name_opt, is_temporal) = cex.falsified();
+    match name_opt {
+        Some(name) if is_temporal => println!(
+            "Temporal property {} {}.",
+            style.bad.paint(name),
+            style.fatal.paint("does not hold"),
+        ),
+        Some(name) => println!(
+            "Invariant {} {}.",
+            style.bad.paint(name),
+            style.fatal.paint("does not hold"),
+        ),
+        None if is_temporal => println!(
+            "Some temporal property(ies) {}.",
+            style.fatal.paint("do not hold"),
+        ),
+        None => println!("Some invariant(s) {}.", style.fatal.paint("do not hold")),
+    }
+    let header = match cex.alias.as_ref() {
+        Some(alias) => format!("Counterexample (output of alias `{}`):", alias),
+        None => "Counterexample:".into(),
+    };
+    println!(
+        "{}",
+        style.fatal.paint(style.uline.paint(header).to_string())
+    );
+    for line in buf.lines() {
+        println!("{}", line)
+    }
+}
+
+/// Number of cexs per falsified property, summarized at the end of runs that keep going after the
+/// first violation.
+#[derive(Debug, Clone, Default)]
+pub struct Violations {
+    /// Maps `(is_temporal, falsified)` to a number of cexs, `falsified` is `None` if unknown.
+    counts: Map<(bool, Option<String>), usize>,
+}
+impl Violations {
+    /// Constructor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Counts a cex.
+    pub fn record(&mut self, cex: &cex::Cex) {
+        let (falsified, is_temporal) = cex.falsified();
+        *self
+            .counts
+            .entry((is_temporal, falsified.map(String::from)))
+            .or_insert(0) += 1;
+    }
+    /// True if no cex was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+    /// Iterates over the falsified properties, their temporal flag and number of cexs.
+    ///
+    /// Invariants come first.
+    pub fn iter(&self) -> impl Iterator<Item = (Option<&str>, bool, usize)> {
+        self.counts
+            .iter()
+            .map(|((is_temporal, falsified), count)| (falsified.as_deref(), *is_temporal, *count))
+    }
+
+    /// Prints the summary.
+    pub fn print(&self, style: &conf::Styles) {
+        println!("{}:", style.uline.paint("violations"));
+        for (falsified, is_temporal, count) in self.iter() {
+            let what = match falsified {
+                Some(name) if is_temporal => {
+                    format!("temporal property {}", style.bad.paint(name))
+                }
+                Some(name) => format!("invariant {}", style.bad.paint(name)),
+                None if is_temporal => "some temporal property(ies)".into(),
+                None => "some invariant(s)".into(),
+            };
+            println!(
+                "  {}: {} counterexample{}",
+                what,
+                style.bold.paint(count.to_string()),
+                if count > 1 { "s" } else { "" },
+            );
+        }
+    }
+    /// JSON representation, an array of objects with `property`, `temporal` and `cex_count`.
+    pub fn to_json(&self) -> serde_json::Value {
+        self.iter()
+            .map(|(falsified, is_temporal, count)| {
+                serde_json::json!({
+                    "property": falsified,
+                    "temporal": is_temporal,
+                    "cex_count": count,
+                })
+            })
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+/// Output handler producing JSON lines, used when the run's [`Format`] is [`Format::Json`].
+///
+/// Each line is a JSON object with an `event` field, one of
+/// - `"config"`: TLC-level arguments (as TOML) and TLC command, only with `--show_tlc_config`;
+/// - `"initial_states"`: number of distinct initial states;
+/// - `"search_depth"`: search depth reached;
+/// - `"dfid_level"`: depth-first iterative deepening level starting;
+/// - `"checkpoint"`: checkpoint or recovery step, with TLC's message;
+/// - `"progress"`: state statistics, `final` is true for the last one, intermediate breadth-first
+///   updates carry the current search `depth`;
+/// - `"simulation"`: simulation statistics, `final` is true for the last one;
+/// - `"coverage"`: latest coverage statistics, with the counts of each item and sub-expression;
+/// - `"error"`: a TLC error or warning, with its source spans;
+/// - `"cex"`: a counterexample, with its states and values, and the `file` it was saved in if
+///   the handler [saves cexs](Self::save_cexs);
+/// - `"outcome"`: final event, with the run outcome, matla's exit code and the number of cexs per
+///   falsified property.
+///
+/// Large integers that do not fit in an `i64` are written as strings.
+pub struct JsonOutputHandler<W: io::Write> {
+    out: W,
+    start_instant: time::Instant,
+    cex_count: usize,
+    error_count: usize,
+    violations: Violations,
+    coverage: project::tlc::coverage::Coverage,
+    /// First write error, reported by [`Self::finalize`].
+    write_error: Option<Error>,
+    /// Cex store, module and seed of the run, if cexs are saved.
+    cex_store: Option<(project::cex_store::Store, String, Option<u64>)>,
+}
+impl<W: io::Write> JsonOutputHandler<W> {
+    /// Constructor.
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            start_instant: time::Instant::now(),
+            cex_count: 0,
+            error_count: 0,
+            violations: Violations::new(),
+            coverage: project::tlc::coverage::Coverage::new(),
+            write_error: None,
+            cex_store: None,
+        }
+    }
+
+    /// Saves the cexs of a run of `project` in its cex store.
+    pub fn save_cexs(mut self, project: &project::FullProject) -> Self {
+        self.cex_store = Some((
+            project.cex_store(),
+            project.actual_entry.clone(),
+            project.tlc_cla.seed,
+        ));
+        self
+    }
+
+    /// Writes a JSON event as a single line.
+    fn write(&mut self, event: serde_json::Value) -> Res<()> {
+        serde_json::to_writer(&mut self.out, &event).context("failed to write JSON output")?;
+        writeln!(self.out).context("failed to write JSON output")?;
+        self.out.flush().context("failed to flush JSON output")?;
+        Ok(())
+    }
+
+    /// Writes a JSON event, remembering the first failure for [`Self::finalize`].
+    fn write_or_remember(&mut self, event: serde_json::Value) {
+        if let Err(e) = self.write(event) {
+            if self.write_error.is_none() {
+                self.write_error = Some(e)
+            }
+        }
+    }
+
+    /// Writes a `config` event: TLC-level arguments as TOML, and the TLC command.
+    pub fn config(&mut self, tlc_cla: &str, cmd: &io::Command) -> Res<()> {
+        let command: Vec<String> = Some(cmd.get_program())
+            .into_iter()
+            .chain(cmd.get_args())
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        let event = serde_json::json!({
+            "event": "config",
+            "tlc_cla": tlc_cla,
+            "command": command,
+            "dir": cmd.get_current_dir().map(|dir| dir.display().to_string()),
+        });
+        self.write(event)
+    }
+
+    /// JSON representation of an integer, see [`cex::value::serialize_int`].
+    fn int(i: &Int) -> serde_json::Value {
+        cex::value::serialize_int(i, serde_json::value::Serializer)
+            .expect("serializing an integer as a JSON value cannot fail")
+    }
+
+    /// Writes the final `outcome` event.
+    ///
+    /// Fails if writing any of the previous events failed.
+    pub fn finalize(&mut self, outcome: &Outcome, concise: ConciseOutcome) -> Res<()> {
+        if let Some(e) = self.write_error.take() {
+            return Err(e);
+        }
+        let (result, message) = match concise {
+            ConciseOutcome::Success => ("success", None),
+            ConciseOutcome::Unsafe => ("unsafe", None),
+            ConciseOutcome::IllDefined => ("ill_defined", None),
+            ConciseOutcome::Error(msg) => ("error", msg),
+            ConciseOutcome::AssertFailed => ("assert_failed", None),
+            ConciseOutcome::Unknown => ("unknown", None),
+        };
+        let event = serde_json::json!({
+            "event": "outcome",
+            "outcome": result,
+            "message": message,
+            "desc": concise.desc(),
+            "exit_code": concise.to_exit_code(),
+            "run_outcome": outcome.run,
+            "process": {
+                "code": outcome.process.code,
+                "status": outcome.process.status,
+            },
+            "runtime_ms": outcome.runtime.num_milliseconds(),
+            "start_time": outcome.start_time.to_rfc3339(),
+            "error_count": self.error_count,
+            "cex_count": self.cex_count,
+            "violations": self.violations.to_json(),
+        });
+        self.write(event)
+    }
+
+    /// Produces a `coverage` event from the latest coverage report.
+    fn coverage_event(&self) -> serde_json::Value {
+        let items: Vec<serde_json::Value> = self
+            .coverage
+            .items()
+            .iter()
+            .map(|item| {
+                let exprs: Vec<serde_json::Value> = item
+                    .exprs
+                    .iter()
+                    .map(|expr| {
+                        serde_json::json!({
+                            "level": expr.level,
+                            "span": expr.span,
+                            "count": Self::int(&expr.count.0),
+                            "cost": expr.cost.as_ref().map(|(i, _)| Self::int(i)),
+                        })
+                    })
+                    .collect();
+                serde_json::json!({
+                    "kind": item.kind.to_string(),
+                    "name": item.name,
+                    "span": item.span,
+                    "distinct": item.counts.as_ref().map(|((i, _), _)| Self::int(i)),
+                    "generated": item.counts.as_ref().map(|(_, (i, _))| Self::int(i)),
+                    "never_fired": item.never_fired(),
+                    "exprs": exprs,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "event": "coverage",
+            "items": items,
+            "elapsed_ms": self.start_instant.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Produces a `progress` event.
+    ///
+    /// There are no states left on queue in depth-first iterative deepening mode.
+    fn progress(
+        &self,
+        is_last: bool,
+        generated: &(Int, String),
+        gen_spm: Option<&(Int, String)>,
+        distinct: &(Int, String),
+        dist_spm: Option<&(Int, String)>,
+        left: Option<&(Int, String)>,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "event": "progress",
+            "final": is_last,
+            "generated": Self::int(&generated.0),
+            "generated_per_minute": gen_spm.map(|(i, _)| Self::int(i)),
+            "distinct": Self::int(&distinct.0),
+            "distinct_per_minute": dist_spm.map(|(i, _)| Self::int(i)),
+            "left": left.map(|(i, _)| Self::int(i)),
+            "elapsed_ms": self.start_instant.elapsed().as_millis() as u64,
+        })
+    }
+}
+impl<W: io::Write> project::tlc::Out for JsonOutputHandler<W> {
+    fn handle_outcome(&mut self, _outcome: RunOutcome) {}
+    fn handle_message(&mut self, msg: &project::tlc::msg::Msg, _log_level: log::Level) {
+        use project::tlc::code::*;
+        if let Some(TopMsg::Msg(Msg::Tlc(TlcMsg::Msg(tlc_msg)))) = msg.code.as_ref() {
+            if self.coverage.handle(tlc_msg) {
+                if let Tlc::TlcCoverageEnd | Tlc::TlcCoverageEndOverhead = tlc_msg {
+                    let event = self.coverage_event();
+                    self.write_or_remember(event)
+                }
+                return;
+            }
+        }
+        let event = match msg.code.as_ref() {
+            Some(TopMsg::Msg(Msg::Tlc(TlcMsg::Msg(tlc_msg)))) => match tlc_msg {
+                Tlc::TlcSearchDepth { depth } => serde_json::json!({
+                    "event": "search_depth",
+                    "depth": depth,
+                }),
+                Tlc::TlcStats {
+                    generated,
+                    distinct,
+                    left,
+                } => self.progress(true, generated, None, distinct, None, Some(left)),
+                Tlc::TlcProgressStats {
+                    depth,
+                    generated,
+                    gen_spm,
+                    distinct,
+                    dist_spm,
+                    left,
+                } => {
+                    let mut event = self.progress(
+                        false,
+                        generated,
+                        gen_spm.as_ref(),
+                        distinct,
+                        dist_spm.as_ref(),
+                        Some(left),
+                    );
+                    event["depth"] = (*depth).into();
+                    event
+                }
+                Tlc::TlcStatsDfid {
+                    generated,
+                    distinct,
+                } => self.progress(true, generated, None, distinct, None, None),
+                Tlc::TlcProgressStatsDfid {
+                    generated,
+                    gen_spm,
+                    distinct,
+                    dist_spm,
+                } => self.progress(
+                    false,
+                    generated,
+                    gen_spm.as_ref(),
+                    distinct,
+                    dist_spm.as_ref(),
+                    None,
+                ),
+                Tlc::TlcProgressStartStatsDfid { level, .. } => serde_json::json!({
+                    "event": "dfid_level",
+                    "level": level,
+                }),
+                Tlc::TlcCheckpointStart
+                | Tlc::TlcCheckpointEnd
+                | Tlc::TlcCheckpointRecoverStart
+                | Tlc::TlcCheckpointRecoverEnd
+                | Tlc::TlcCheckpointRecoverEndDfid => {
+                    let step = match tlc_msg {
+                        Tlc::TlcCheckpointStart => "start",
+                        Tlc::TlcCheckpointEnd => "end",
+                        Tlc::TlcCheckpointRecoverStart => "recover_start",
+                        _ => "recover_end",
+                    };
+                    serde_json::json!({
+                        "event": "checkpoint",
+                        "step": step,
+                        "message": msg.lines().join("\n"),
+                        "elapsed_ms": self.start_instant.elapsed().as_millis() as u64,
+                    })
+                }
+                Tlc::TlcProgressSimu {
+                    checked,
+                    traces,
+                    mean_length,
+                } => serde_json::json!({
+                    "event": "simulation",
+                    "final": false,
+                    "checked": Self::int(&checked.0),
+                    "traces": traces.as_ref().map(|(i, _)| Self::int(i)),
+                    "mean_length": mean_length,
+                    "elapsed_ms": self.start_instant.elapsed().as_millis() as u64,
+                }),
+                Tlc::TlcStatsSimu { generated } => serde_json::json!({
+                    "event": "simulation",
+                    "final": true,
+                    "checked": Self::int(&generated.0),
+                    "elapsed_ms": self.start_instant.elapsed().as_millis() as u64,
+                }),
+                _ => return,
+            },
+            Some(TopMsg::Msg(Msg::Status(Status::TlcInitGenerated1 { state_count, .. }))) => {
+                serde_json::json!({
+                    "event": "initial_states",
+                    "count": Self::int(&state_count.0),
+                })
+            }
+            Some(_) | None => return,
+        };
+        self.write_or_remember(event)
+    }
+
+    fn handle_error(&mut self, error: impl Into<project::tlc::TlcError>) -> Res<()> {
+        let error = error.into();
+        let is_warning = error.is_warning();
+        if !is_warning {
+            self.error_count += 1;
+        }
+        self.write(serde_json::json!({
+            "event": "error",
+            "warning": is_warning,
+            "error": error,
+        }))
+    }
+
+    fn handle_cex(&mut self, cex: cex::Cex) {
+        self.cex_count += 1;
+        self.violations.record(&cex);
+        let (_, is_temporal) = cex.falsified();
+        let file = self.cex_store.as_ref().and_then(|(store, module, seed)| {
+            match store.save(module, *seed, cex.clone()) {
+                Ok(saved) => Some(saved.path.display().to_string()),
+                Err(e) => {
+                    log::warn!("failed to save counterexample: {:?}", e);
+                    None
+                }
+            }
+        });
+        self.write_or_remember(serde_json::json!({
+            "event": "cex",
+            "temporal": is_temporal,
+            "cex": cex,
+            "file": file,
+        }))
+    }
+}
+
 #[cfg(feature = "with_clap")]
 mod cla_spec {
     prelude!();
@@ -702,6 +1832,28 @@ mod cla_spec {
     // const TLC_VERB_KEY: &str = "RUN_TLC_VERB_KEY";
     /// Key for showing configuration before running.
     pub const SHOW_CONFIG_KEY: &str = "RUN_SHOW_CONFIG_KEY";
+    /// Key for the output format.
+    const FORMAT_KEY: &str = "RUN_FORMAT_KEY";
+    /// Output format default value.
+    const FORMAT_DEFAULT: &str = "text";
+    /// Key for recovering from a checkpoint.
+    const RECOVER_KEY: &str = "RUN_RECOVER_KEY";
+    /// Key for recording TLC's raw output.
+    const RECORD_KEY: &str = "RUN_RECORD_KEY";
+    /// Key for replaying a recording of TLC's raw output.
+    const REPLAY_KEY: &str = "RUN_REPLAY_KEY";
+    /// Key for the variables cexs only display.
+    const CEX_ONLY_KEY: &str = "RUN_CEX_ONLY_KEY";
+    /// Key for the variables cexs hide.
+    const CEX_HIDE_KEY: &str = "RUN_CEX_HIDE_KEY";
+    /// Key for only displaying the variables that changed in cexs.
+    const CEX_CHANGED_ONLY_KEY: &str = "RUN_CEX_CHANGED_ONLY_KEY";
+    /// Key for cex display hints.
+    const CEX_HINT_KEY: &str = "RUN_CEX_HINT_KEY";
+    /// Key for the cex format.
+    const CEX_FORMAT_KEY: &str = "RUN_CEX_FORMAT_KEY";
+    /// Cex format default value.
+    const CEX_FORMAT_DEFAULT: &str = "text";
 
     // TLC options.
 
@@ -737,6 +1889,36 @@ mod cla_spec {
     const TIMESTATS_KEY: &str = "RUN_TIMESTATS_KEY";
     /// Print timestats default value.
     const TIMESTATS_DEFAULT: &str = "off";
+    /// Key for the simulate argument.
+    const SIMULATE_KEY: &str = "RUN_SIMULATE_KEY";
+    /// Simulate default value.
+    const SIMULATE_DEFAULT: &str = "off";
+    /// Key for the simulation depth argument.
+    const DEPTH_KEY: &str = "RUN_DEPTH_KEY";
+    /// Simulation depth default value.
+    const DEPTH_DEFAULT: &str = "default";
+    /// Key for the number of simulation traces argument.
+    const NUM_TRACES_KEY: &str = "RUN_NUM_TRACES_KEY";
+    /// Number of simulation traces default value.
+    const NUM_TRACES_DEFAULT: &str = "default";
+    /// Key for the DFID depth argument.
+    const DFID_KEY: &str = "RUN_DFID_KEY";
+    /// DFID depth default value.
+    const DFID_DEFAULT: &str = "off";
+    /// Key for the checkpoint interval argument.
+    const CHECKPOINT_KEY: &str = "RUN_CHECKPOINT_KEY";
+    /// Checkpoint interval default value.
+    const CHECKPOINT_DEFAULT: &str = "default";
+    /// Key for the coverage interval argument.
+    const COVERAGE_KEY: &str = "RUN_COVERAGE_KEY";
+    /// Coverage interval default value.
+    const COVERAGE_DEFAULT: &str = "off";
+    /// Key for the keep going argument.
+    const KEEP_GOING_KEY: &str = "RUN_KEEP_GOING_KEY";
+    /// Keep going default value.
+    const KEEP_GOING_DEFAULT: &str = "off";
+    /// Key for the continue flag, same as keep going on.
+    const CONTINUE_KEY: &str = "RUN_CONTINUE_KEY";
 
     /// TLC arguments, separated for reusability.
     pub fn tlc_args(cmd: clap::Command<'static>) -> clap::Command {
@@ -818,6 +2000,58 @@ mod cla_spec {
                 .default_value(TIMESTATS_DEFAULT)
                 .value_name(crate::cla::utils::val_name::BOOL)
                 .validator(|s| crate::cla::utils::validate_bool(&s).map(|_| ())),
+            clap::Arg::new(SIMULATE_KEY)
+                .help("(De)activates simulation mode, where TLC checks random behaviors")
+                .long("simulate")
+                .takes_value(true)
+                .default_value(SIMULATE_DEFAULT)
+                .value_name(crate::cla::utils::val_name::BOOL)
+                .validator(|s| crate::cla::utils::validate_bool(&s).map(|_| ())),
+            clap::Arg::new(DEPTH_KEY)
+                .help("Maximum length of simulation traces")
+                .long("depth")
+                .takes_value(true)
+                .default_value(DEPTH_DEFAULT)
+                .value_name(crate::cla::utils::val_name::U64_OR_DEFAULT)
+                .validator(|s| crate::cla::utils::validate_u64_or_default(&s).map(|_| ())),
+            clap::Arg::new(NUM_TRACES_KEY)
+                .help("Number of simulation traces to generate, unbounded by default")
+                .long("num_traces")
+                .takes_value(true)
+                .default_value(NUM_TRACES_DEFAULT)
+                .value_name(crate::cla::utils::val_name::U64_OR_DEFAULT)
+                .validator(|s| crate::cla::utils::validate_u64_or_default(&s).map(|_| ())),
+            clap::Arg::new(DFID_KEY)
+                .help("Activates depth-first iterative deepening up to some depth")
+                .long("dfid")
+                .takes_value(true)
+                .default_value(DFID_DEFAULT)
+                .value_name(crate::cla::utils::val_name::U64_OR_OFF)
+                .validator(|s| crate::cla::utils::validate_u64_or_off(&s).map(|_| ())),
+            clap::Arg::new(CHECKPOINT_KEY)
+                .help("Minutes between two TLC checkpoints, `0` deactivates checkpoints")
+                .long("checkpoint")
+                .takes_value(true)
+                .default_value(CHECKPOINT_DEFAULT)
+                .value_name(crate::cla::utils::val_name::U64_OR_DEFAULT)
+                .validator(|s| crate::cla::utils::validate_u64_or_default(&s).map(|_| ())),
+            clap::Arg::new(COVERAGE_KEY)
+                .help("Minutes between two TLC coverage reports, deactivated by default")
+                .long("coverage")
+                .takes_value(true)
+                .default_value(COVERAGE_DEFAULT)
+                .value_name(crate::cla::utils::val_name::U64_OR_OFF)
+                .validator(|s| crate::cla::utils::validate_u64_or_off(&s).map(|_| ())),
+            clap::Arg::new(KEEP_GOING_KEY)
+                .help("If true, TLC keeps checking after the first violation")
+                .long("keep_going")
+                .takes_value(true)
+                .default_value(KEEP_GOING_DEFAULT)
+                .value_name(crate::cla::utils::val_name::BOOL)
+                .validator(|s| crate::cla::utils::validate_bool(&s).map(|_| ())),
+            clap::Arg::new(CONTINUE_KEY)
+                .help("Same as `--keep_going on`, overrides `--keep_going`")
+                .long("continue"),
             // Done, there's just the optional module to run left.
             clap::Arg::new(MAIN_MODULE_KEY)
                 .help(
@@ -884,6 +2118,38 @@ mod cla_spec {
             TIMESTATS_KEY with
             |val| crate::cla::utils::validate_bool(val)
         );
+        let simulate = retrieve!(
+            SIMULATE_KEY with
+            |val| crate::cla::utils::validate_bool(val)
+        );
+        let depth = retrieve!(
+            DEPTH_KEY with
+            |val| crate::cla::utils::validate_u64_or_default(val)
+        );
+        let num_traces = retrieve!(
+            NUM_TRACES_KEY with
+            |val| crate::cla::utils::validate_u64_or_default(val)
+        );
+        let dfid = retrieve!(
+            DFID_KEY with
+            |val| crate::cla::utils::validate_u64_or_off(val)
+        );
+        let checkpoint = retrieve!(
+            CHECKPOINT_KEY with
+            |val| crate::cla::utils::validate_u64_or_default(val)
+        );
+        let coverage = retrieve!(
+            COVERAGE_KEY with
+            |val| crate::cla::utils::validate_u64_or_off(val)
+        );
+        let keep_going = if matches.is_present(CONTINUE_KEY) {
+            Some(true)
+        } else {
+            retrieve!(
+                KEEP_GOING_KEY with
+                |val| crate::cla::utils::validate_bool(val)
+            )
+        };
         let cla = conf::customl::TlcCla::new(
             conf::customl::Source::Cla,
             workers,
@@ -894,6 +2160,13 @@ mod cla_spec {
             check_deadlocks,
             print_callstack,
             timestats,
+            simulate,
+            depth,
+            num_traces,
+            dfid,
+            checkpoint,
+            coverage,
+            keep_going,
         );
 
         let main_module = if let Some(main) = matches.value_of(MAIN_MODULE_KEY) {
@@ -927,6 +2200,79 @@ mod cla_spec {
                     clap::Arg::new(SHOW_CONFIG_KEY)
                         .help("Displays the options that matla will use to run TLC")
                         .long("show_tlc_config"),
+                    clap::Arg::new(FORMAT_KEY)
+                        .help("Output format, `json` produces one JSON object per line")
+                        .long("format")
+                        .takes_value(true)
+                        .default_value(FORMAT_DEFAULT)
+                        .value_name(super::Format::VALUES)
+                        .validator(|arg| super::Format::parse(&arg).map(|_| ())),
+                    clap::Arg::new(RECOVER_KEY)
+                        .help("Recovers from a checkpoint, the latest one if none is given")
+                        .long_help(
+                            "\
+                            Recovers from a TLC checkpoint, which must be passed as \
+                            `--recover=<CHECKPOINT>`. The checkpoint is either a directory in \
+                            the TLC metadir `target/<debug|release>/tlc_meta` or the path to a \
+                            checkpoint directory. Without a checkpoint, matla recovers from the \
+                            latest one in the TLC metadir.\
+                        ",
+                        )
+                        .long("recover")
+                        .takes_value(true)
+                        .min_values(0)
+                        .max_values(1)
+                        .require_equals(true)
+                        .value_name("CHECKPOINT"),
+                    clap::Arg::new(CEX_ONLY_KEY)
+                        .help("Only displays these state variables in counterexamples")
+                        .long("cex_only")
+                        .takes_value(true)
+                        .use_value_delimiter(true)
+                        .value_name("VARS"),
+                    clap::Arg::new(CEX_HIDE_KEY)
+                        .help("Hides these state variables in counterexamples")
+                        .long("cex_hide")
+                        .takes_value(true)
+                        .use_value_delimiter(true)
+                        .value_name("VARS"),
+                    clap::Arg::new(CEX_CHANGED_ONLY_KEY)
+                        .help("Only displays the state variables that changed in counterexamples")
+                        .long("cex_changed_only"),
+                    clap::Arg::new(CEX_HINT_KEY)
+                        .help("Display hint for a state variable in counterexamples")
+                        .long_help(
+                            "\
+                            Display hint for a state variable in counterexamples, can be given \
+                            several times. `table` renders functions, records and sequences as a \
+                            table, one binding per line; `fold:<INT>` renders sets and bags with \
+                            more than `<INT>` elements as their cardinality.\
+                        ",
+                        )
+                        .long("cex_hint")
+                        .takes_value(true)
+                        .multiple_occurrences(true)
+                        .value_name("VAR=HINT")
+                        .validator(|arg| conf::cex_view::parse_var_hint(&arg).map(|_| ())),
+                    clap::Arg::new(CEX_FORMAT_KEY)
+                        .help("Format of the counterexamples displayed by text runs")
+                        .long("cex-format")
+                        .alias("cex_format")
+                        .takes_value(true)
+                        .default_value(CEX_FORMAT_DEFAULT)
+                        .value_name(super::CexFormat::VALUES)
+                        .validator(|arg| super::CexFormat::parse(&arg).map(|_| ())),
+                    clap::Arg::new(RECORD_KEY)
+                        .help("Records TLC's raw output to a file, for diagnosis")
+                        .long("record")
+                        .takes_value(true)
+                        .value_name("FILE"),
+                    clap::Arg::new(REPLAY_KEY)
+                        .help("Replays a file produced by `--record` instead of running TLC")
+                        .long("replay")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .conflicts_with(RECORD_KEY),
                 ]);
             tlc_args(cmd)
         }
@@ -939,10 +2285,48 @@ mod cla_spec {
             // };
 
             let show_config = matches.is_present(SHOW_CONFIG_KEY);
+            let format = matches
+                .value_of(FORMAT_KEY)
+                .expect("argument with default value");
+            let format = super::Format::parse(format).expect("fatal error during CLAP");
+            let recover = if matches.is_present(RECOVER_KEY) {
+                Some(matches.value_of(RECOVER_KEY).map(String::from))
+            } else {
+                None
+            };
+
+            let cex_vars = |key: &str| {
+                matches
+                    .values_of(key)
+                    .map(|vals| vals.map(|var| var.trim().to_string()).collect::<Set<_>>())
+            };
+            let mut cex_view = conf::CexView::new();
+            cex_view.only = cex_vars(CEX_ONLY_KEY);
+            cex_view.hide = cex_vars(CEX_HIDE_KEY).unwrap_or_default();
+            cex_view.changed_only = matches.is_present(CEX_CHANGED_ONLY_KEY);
+            for hint in matches.values_of(CEX_HINT_KEY).into_iter().flatten() {
+                let (var, hint) =
+                    conf::cex_view::parse_var_hint(hint).expect("fatal error during CLAP");
+                let _prev = cex_view.hints.insert(var, hint);
+            }
+
+            let cex_format = matches
+                .value_of(CEX_FORMAT_KEY)
+                .expect("argument with default value");
+            let cex_format = super::CexFormat::parse(cex_format).expect("fatal error during CLAP");
+
+            let record = matches.value_of(RECORD_KEY).map(io::PathBuf::from);
+            let replay = matches.value_of(REPLAY_KEY).map(io::PathBuf::from);
 
             let (tlc_cla, main_module, release) = handle_tlc_args(matches);
 
-            Self::new(release, main_module, tlc_cla, show_config)
+            Self::new(release, main_module, tlc_cla, show_config, format).map(|run| {
+                run.recover(recover)
+                    .cex_view(cex_view)
+                    .cex_format(cex_format)
+                    .record(record)
+                    .replay(replay)
+            })
         }
         fn run(self) -> Res<Option<i32>> {
             self.launch().map(Some)
@@ -1,6 +1,9 @@
 //! Setup mode.
 //!
 //! Creates the user config file.
+//!
+//! In standalone mode, installs the `tla2tools` jar from the source of the [`conf::Install`]
+//! configuration, read from the user config file if it exists already.
 
 prelude!();
 
@@ -50,8 +53,7 @@ pub mod cla {
 }
 
 /// URL for the latest release of the TLA toolbox.
-pub const TLA_TOOLBOX_URL: &str =
-    "https://github.com/tlaplus/tlaplus/releases/latest/download/tla2tools.jar";
+pub const TLA_TOOLBOX_URL: &str = conf::install::DEFAULT_SOURCE;
 
 /// Runs setup mode.
 #[readonly]
@@ -69,6 +71,8 @@ pub struct Run {
     pub from_env: bool,
     /// If true, overwrite files when needed.
     pub overwrite: bool,
+    /// Installation configuration, from the user config file if any.
+    pub install: conf::Install,
 }
 impl Run {
     /// Prefix for all lines in setup mode.
@@ -82,13 +86,26 @@ impl Run {
                 "trying to create setup mode in both standalone and from_env flag active"
             ))
         }
+        let toml_path = conf::user::toml_path()?;
+        let install = if toml_path.is_file() {
+            match conf::user::raw_load(&toml_path) {
+                Ok(conf) => conf.toolchain.install,
+                Err(e) => {
+                    log::warn!("failed to load installation configuration: {:?}", e);
+                    conf::Install::new()
+                }
+            }
+        } else {
+            conf::Install::new()
+        };
         Ok(Self {
             conf_dir: conf::user::conf_path()?,
-            toml_path: conf::user::toml_path()?,
+            toml_path,
             tla2tools_jar_path: conf::user::tla2tools_jar_path()?,
             standalone,
             from_env,
             overwrite,
+            install,
         })
     }
     /// Launches setup.
@@ -304,7 +321,7 @@ impl Run {
 
     /// Toolchain config setup, standalone mode.
     ///
-    /// Downloads the TLA toolbox to [`Self::tla2tools_jar_path`].
+    /// Installs the TLA toolbox to [`Self::tla2tools_jar_path`], see [`Self::update_toolbox`].
     fn toolchain_download(&self) -> Res<Option<conf::Toolchain>> {
         if self.tla2tools_jar_path.is_dir() {
             bail!(
@@ -325,31 +342,36 @@ impl Run {
             true
         };
 
-        if force {
-            self.update_toolbox()?;
+        let install = if force {
+            self.update_toolbox()?
         } else {
             println!(
                 "{}Skipping toolchain download, keeping existing one.",
                 Self::PREF,
-            )
-        }
+            );
+            self.install.clone()
+        };
 
-        self.toolchain_from_env_with(&self.tla2tools_jar_path)
-            .map(Some)
+        let mut toolchain = self.toolchain_from_env_with(&self.tla2tools_jar_path)?;
+        toolchain.install = install;
+        Ok(Some(toolchain))
     }
 
     /// Updates the tla2tool jar in the user directory.
-    pub fn update_toolbox(&self) -> Res<()> {
-        println!(
-            "{}Downloading toolbox from `{}`...",
-            Self::PREF,
-            TLA_TOOLBOX_URL,
-        );
-        let body = io::download(TLA_TOOLBOX_URL)?
-            .bytes()
-            .with_context(|| format!("accessing the body of `{}`", TLA_TOOLBOX_URL))?;
+    ///
+    /// Retrieves the jar from the [source][conf::Install::source] of [`Self::install`] and checks
+    /// its SHA-256 hash if one is expected. Returns [`Self::install`] with the source and hash of
+    /// the new jar recorded as the installed ones.
+    pub fn update_toolbox(&self) -> Res<conf::Install> {
+        let mut install = self.install.clone();
+        let source = install.source()?;
+        println!("{}Retrieving toolbox from `{}`...", Self::PREF, source);
+        let body = install.fetch()?;
         let body_byte_count = body.len();
-        println!("{}Download completed successfully.", Self::PREF);
+        println!("{}Toolbox retrieved successfully.", Self::PREF);
+        if install.expected_sha256().is_some() {
+            println!("{}SHA-256 hash verified.", Self::PREF);
+        }
 
         println!(
             "{}Writing downloaded file to `{}`...",
@@ -359,10 +381,10 @@ impl Run {
         let mut target = io::write_file(&self.tla2tools_jar_path, true, true)?;
         let bytes_written = {
             use io::Write;
-            target.write(body.as_ref()).with_context(|| {
+            target.write(&body).with_context(|| {
                 format!(
                     "writing `{}` to `{}`",
-                    TLA_TOOLBOX_URL,
+                    source,
                     self.tla2tools_jar_path.display(),
                 )
             })?
@@ -370,14 +392,14 @@ impl Run {
         if bytes_written != body_byte_count {
             bail!(
                 "discrepancy writing `{}` to `{}`: wrote {} bytes of {}",
-                TLA_TOOLBOX_URL,
+                source,
                 self.tla2tools_jar_path.display(),
                 bytes_written,
                 body_byte_count,
             )
         }
 
-        Ok(())
+        Ok(install)
     }
 }
 
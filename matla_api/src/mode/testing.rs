@@ -17,6 +17,8 @@ pub mod cla {
     const RUN_PARALLEL_DEFAULT: &str = crate::cla::utils::BOOL_FALSE;
     /// Key for the modules to run.
     const MAIN_MODULES_KEY: &str = "TEST_MAIN_MODULES_KEY";
+    /// Key for the test report.
+    const REPORT_KEY: &str = "TEST_REPORT_KEY";
 
     /// Test subcommand.
     pub fn subcommand() -> clap::Command<'static> {
@@ -43,6 +45,12 @@ pub mod cla {
                     ",
                     )
                     .long("release"),
+                clap::Arg::new(REPORT_KEY)
+                    .help("Writes a test report, for instance `junit=report.xml`")
+                    .long("report")
+                    .takes_value(true)
+                    .value_name(testing::report::Report::VALUES)
+                    .validator(|arg| testing::report::Report::parse(&arg).map(|_| ())),
                 clap::Arg::new(MAIN_MODULES_KEY)
                     .help(
                         "\
@@ -77,11 +85,32 @@ pub mod cla {
             } else {
                 None
             };
-            Run::new(filter, release, parallel)
+            let report = matches
+                .value_of(REPORT_KEY)
+                .map(|arg| {
+                    testing::report::Report::parse(arg)
+                        .map_err(Error::msg)
+                        .with_context(|| anyhow!("failed to parse argument despite validation"))
+                })
+                .transpose()?;
+            Run::new(filter, release, parallel, report)
         })
     }
 }
 
+/// Results of running some tests.
+type TestResults<'a, T> = Vec<(Res<testing::integration::TestRun>, &'a T)>;
+
+/// Summary of some test results, see [`Run::summary`].
+struct Summary {
+    /// Number of tests passed.
+    passed: usize,
+    /// Total number of tests.
+    total: usize,
+    /// Number of tests that could not run because of an unexpected error.
+    unexpected: usize,
+}
+
 /// Runs setup mode.
 #[readonly]
 #[derive(Debug, Clone)]
@@ -96,10 +125,17 @@ pub struct Run {
     pub parallel: bool,
     /// Path to the project directory.
     pub project_path: io::PathBuf,
+    /// Optional report to write after running the tests.
+    pub report: Option<testing::report::Report>,
 }
 impl Run {
     /// Constructor.
-    pub fn new(filter: Option<testing::Filter>, release: bool, parallel: bool) -> Res<Self> {
+    pub fn new(
+        filter: Option<testing::Filter>,
+        release: bool,
+        parallel: bool,
+        report: Option<testing::report::Report>,
+    ) -> Res<Self> {
         let project_path = conf::top_cla::project_path()?;
         let src_project = project::SourceProject::from_path(&project_path)?;
         Ok(Self {
@@ -108,89 +144,151 @@ impl Run {
             release,
             parallel,
             project_path,
+            report,
         })
     }
 
     /// Launches a plain TLC command.
     pub fn launch(self) -> Res<()> {
-        let (passed, total) = self.integration()?;
-        println!("integration tests: {} successful of {}", passed, total);
-        if passed < total {
-            bail!("{} integration test(s) failed", total - passed);
-        } else if passed > total {
-            panic!(
-                "{} test(s) passed, but total number of tests is {}",
-                passed, total
-            );
-        }
-        Ok(())
-    }
-
-    /// Runs integration tests.
-    ///
-    /// Returns the number of tests passed and the total number of tests.
-    pub fn integration(&self) -> Res<(usize, usize)> {
         let test_path = {
             let mut path = self.project_path.clone();
             path.push("tests");
             path
         };
-        if !test_path.is_dir() {
-            return Ok((0, 0));
-        }
-        let cxt = testing::integration::Cxt::dir_load(
-            &test_path,
-            &self.src_project,
-            self.filter.as_ref(),
-            self.release,
-            true,
-        )
-        .context("failed to load integration tests")?;
-
-        let styles = conf::Styles::new();
-
-        let total = cxt.test_count();
-        if total < 2 {
-            println!("running {} integration test", total);
-        } else if self.parallel {
-            println!(
-                "running {} integration tests {}",
-                total,
-                styles.bold.paint("concurrently")
+        let cxt = if test_path.is_dir() {
+            let cxt = testing::integration::Cxt::dir_load(
+                &test_path,
+                &self.src_project,
+                self.filter.as_ref(),
+                self.release,
+                true,
             )
+            .context("failed to load integration tests")?;
+            Some(cxt)
         } else {
+            None
+        };
+        let doc_tests = testing::doc::load(&self.src_project, self.release, self.filter.as_ref())
+            .context("failed to load doc tests")?;
+        let unit_tests = testing::unit::load(&self.src_project, self.release, self.filter.as_ref())
+            .context("failed to load unit tests")?;
+        let regression_tests =
+            testing::regression::load(&self.src_project, self.release, self.filter.as_ref())
+                .context("failed to load cex regression tests")?;
+
+        let integration = match cxt.as_ref() {
+            Some(cxt) => self.integration(cxt)?,
+            None => vec![],
+        };
+        let doc = self.wrapped("doc", &doc_tests);
+        let unit = self.wrapped("unit", &unit_tests);
+        let regression = self.wrapped("regression", &regression_tests);
+
+        if let Some(report) = self.report.as_ref() {
+            use testing::report::Case;
+            report.write(&[
+                (
+                    "integration",
+                    integration
+                        .iter()
+                        .map(|(res, test)| Case::new(test.module_path(), res))
+                        .collect(),
+                ),
+                (
+                    "doc",
+                    doc.iter()
+                        .map(|(res, test)| Case::new(test.name(), res))
+                        .collect(),
+                ),
+                (
+                    "unit",
+                    unit.iter()
+                        .map(|(res, test)| Case::new(test.name(), res))
+                        .collect(),
+                ),
+                (
+                    "regression",
+                    regression
+                        .iter()
+                        .map(|(res, test)| Case::new(test.name(), res))
+                        .collect(),
+                ),
+            ])?;
             println!(
-                "running {} integration tests {}",
-                total,
-                styles.bold.paint("sequentially")
-            )
+                "{} report written to `{}`",
+                report.format,
+                report.path.display()
+            );
         }
 
-        let res = cxt
-            .run(self.parallel, |res, test| {
-                let outcome = match &res {
-                    Ok(Ok(())) => format!("{} 😺", styles.good.paint("success")),
-                    Ok(Err(_)) => format!("{} 😿", styles.fatal.paint("failure")),
-                    Err(_) => format!("{} 🙀", styles.bad.paint("unexpected error")),
-                };
+        // All summaries are printed before failing, so that every failure is reported.
+        let summaries = [
+            (
+                "integration",
+                Self::summary(integration, |test| test.tla_path.display().to_string()),
+            ),
+            (
+                "doc",
+                Self::summary(doc, |test| test.kind.source.path.display().to_string()),
+            ),
+            (
+                "unit",
+                Self::summary(unit, |test| test.kind.pos.to_string()),
+            ),
+            (
+                "cex regression",
+                Self::summary(regression, |test| test.kind.path.display().to_string()),
+            ),
+        ];
+
+        let (mut failed, mut unexpected) = (0, 0);
+        let mut failed_kinds = vec![];
+        for (kind, summary) in summaries {
+            // Integration tests are always reported, other kinds only if there are some.
+            if summary.total > 0 || kind == "integration" {
                 println!(
-                    "    test {}: {}",
-                    styles.uline.paint(format!(
-                        "`{}/{}`",
-                        test.module_path_pref.display(),
-                        test.module_name,
-                    )),
-                    outcome,
+                    "{} tests: {} successful of {}",
+                    kind, summary.passed, summary.total
                 );
-                (res, test)
-            })
-            .context("failed to run integration tests")?;
+            }
+            if summary.passed > summary.total {
+                panic!(
+                    "{} test(s) passed, but total number of tests is {}",
+                    summary.passed, summary.total
+                );
+            } else if summary.passed < summary.total {
+                failed += summary.total - summary.passed;
+                failed_kinds.push(format!("{} {}", summary.total - summary.passed, kind));
+            }
+            unexpected += summary.unexpected;
+        }
+
+        if failed > 0 {
+            let unexpected = if unexpected > 0 {
+                format!(", {} of them with unexpected error(s)", unexpected)
+            } else {
+                String::new()
+            };
+            bail!(
+                "{} test(s) failed ({}){}",
+                failed,
+                failed_kinds.join(", "),
+                unexpected
+            );
+        }
+        Ok(())
+    }
 
+    /// Prints the errors of some test results.
+    ///
+    /// Tests that could not run count as failed, see [`Summary::unexpected`].
+    fn summary<T>(res: TestResults<T>, path: impl Fn(&T) -> String) -> Summary {
+        let total = res.len();
         let mut passed = 0;
-        let mut fatal_errors = false;
+        let mut unexpected = 0;
         for (res, test) in res {
             match res {
-                Ok(sub) => match sub {
+                Ok(run) => match run.res {
                     Ok(()) => passed += 1,
                     Err(lines) => {
                         println!();
@@ -201,27 +299,100 @@ impl Run {
                     }
                 },
                 Err(e) => {
-                    fatal_errors = true;
+                    unexpected += 1;
                     println!();
-                    log::error!(
-                        "an unexpected error occurred on `{}`\n{:?}",
-                        test.tla_path.display(),
-                        e,
-                    );
+                    log::error!("an unexpected error occurred on `{}`\n{:?}", path(test), e,);
                 }
             }
         }
 
-        if fatal_errors {
-            bail!(
-                "some unexpected error(s) occurred, {} test{} passed of {}",
-                passed,
-                if passed > 1 { "s" } else { "" },
+        Summary {
+            passed,
+            total,
+            unexpected,
+        }
+    }
+
+    /// Prints what the tests are about to run.
+    fn announce(&self, kind: &str, total: usize) {
+        let styles = conf::Styles::new();
+        if total < 2 {
+            println!("running {} {} test", total, kind);
+        } else if self.parallel {
+            println!(
+                "running {} {} tests {}",
                 total,
+                kind,
+                styles.bold.paint("concurrently")
             )
+        } else {
+            println!(
+                "running {} {} tests {}",
+                total,
+                kind,
+                styles.bold.paint("sequentially")
+            )
+        }
+    }
+
+    /// Pretty outcome of a test run.
+    fn outcome(res: &Res<testing::integration::TestRun>) -> String {
+        let styles = conf::Styles::new();
+        match res {
+            Ok(run) if run.is_ok() => format!("{} 😺", styles.good.paint("success")),
+            Ok(_) => format!("{} 😿", styles.fatal.paint("failure")),
+            Err(_) => format!("{} 🙀", styles.bad.paint("unexpected error")),
         }
+    }
 
-        Ok((passed, total))
+    /// Runs tests over wrapper modules, *i.e.* doc, unit or cex regression tests.
+    pub fn wrapped<'a, K>(
+        &self,
+        kind: &str,
+        tests: &'a [testing::Test<K>],
+    ) -> TestResults<'a, testing::Test<K>>
+    where
+        K: testing::wrapper::Kind + Sync,
+    {
+        if tests.is_empty() {
+            return vec![];
+        }
+        let styles = conf::Styles::new();
+        self.announce(kind, tests.len());
+        testing::wrapper::run(tests, self.parallel, |res, test| {
+            println!(
+                "    {} test {}: {}",
+                kind,
+                styles.uline.paint(format!("`{}`", test.name())),
+                Self::outcome(&res),
+            );
+            (res, test)
+        })
+    }
+
+    /// Runs integration tests.
+    pub fn integration<'a>(
+        &self,
+        cxt: &'a testing::integration::Cxt,
+    ) -> Res<TestResults<'a, testing::integration::Test>> {
+        let styles = conf::Styles::new();
+
+        self.announce("integration", cxt.test_count());
+
+        cxt.run(self.parallel, |res, test| {
+            let outcome = Self::outcome(&res);
+            println!(
+                "    test {}: {}",
+                styles.uline.paint(format!(
+                    "`{}/{}`",
+                    test.module_path_pref.display(),
+                    test.module_name,
+                )),
+                outcome,
+            );
+            (res, test)
+        })
+        .context("failed to run integration tests")
     }
 }
 
@@ -239,6 +410,8 @@ mod cla_spec {
     const RUN_PARALLEL_DEFAULT: &str = crate::cla::utils::BOOL_FALSE;
     /// Key for the modules to run.
     const MAIN_MODULES_KEY: &str = "TEST_MAIN_MODULES_KEY";
+    /// Key for the test report.
+    const REPORT_KEY: &str = "TEST_REPORT_KEY";
 
     impl mode::ClaMode for super::Run {
         const SUBCOMMAND_IDENT: &'static str = CMD_NAME;
@@ -269,6 +442,12 @@ mod cla_spec {
                     ",
                         )
                         .long("release"),
+                    clap::Arg::new(REPORT_KEY)
+                        .help("Writes a test report, for instance `junit=report.xml`")
+                        .long("report")
+                        .takes_value(true)
+                        .value_name(testing::report::Report::VALUES)
+                        .validator(|arg| testing::report::Report::parse(&arg).map(|_| ())),
                     clap::Arg::new(MAIN_MODULES_KEY)
                         .help(
                             "\
@@ -300,7 +479,15 @@ mod cla_spec {
             } else {
                 None
             };
-            Self::new(filter, release, parallel)
+            let report = matches
+                .value_of(REPORT_KEY)
+                .map(|arg| {
+                    testing::report::Report::parse(arg)
+                        .map_err(Error::msg)
+                        .with_context(|| anyhow!("failed to parse argument despite validation"))
+                })
+                .transpose()?;
+            Self::new(filter, release, parallel, report)
         }
         fn run(self) -> Res<Option<i32>> {
             self.launch()?;
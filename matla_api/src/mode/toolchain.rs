@@ -0,0 +1,399 @@
+//! Toolchain mode, manages the local store of named `tla2tools` jars.
+//!
+//! See [`conf::toolchain_store`]. The default toolchain is the one the user configuration points
+//! to, projects can pin another one in their configuration file.
+
+prelude!();
+
+use conf::toolchain_store as store;
+
+/// CLAP stuff.
+#[cfg(feature = "with_clap")]
+pub mod cla {
+    use super::*;
+
+    /// Toolchain subcommand name.
+    const CMD_NAME: &str = "toolchain";
+    /// List subcommand name.
+    const LIST_CMD_NAME: &str = "list";
+    /// Add subcommand name.
+    const ADD_CMD_NAME: &str = "add";
+    /// Remove subcommand name.
+    const REMOVE_CMD_NAME: &str = "remove";
+    /// Default subcommand name.
+    const DEFAULT_CMD_NAME: &str = "default";
+    /// Key for the jar to add.
+    const JAR_KEY: &str = "TOOLCHAIN_JAR_KEY";
+    /// Key for the name of a toolchain.
+    const NAME_KEY: &str = "TOOLCHAIN_NAME_KEY";
+    /// Key for making an added toolchain the default one.
+    const DEFAULT_KEY: &str = "TOOLCHAIN_DEFAULT_KEY";
+
+    /// Toolchain subcommand.
+    pub fn subcommand() -> clap::Command<'static> {
+        let name_arg = || {
+            clap::Arg::new(NAME_KEY)
+                .help("Name of the toolchain")
+                .value_name("NAME")
+                .required(true)
+        };
+        clap::Command::new(CMD_NAME)
+            .about("Manages the local store of named `tla2tools` jars.")
+            .arg(crate::cla::top::project_path_arg())
+            .subcommand_required(true)
+            .subcommand(
+                clap::Command::new(LIST_CMD_NAME)
+                    .about("Lists the toolchains of the store.")
+                    .arg(crate::cla::top::project_path_arg()),
+            )
+            .subcommand(
+                clap::Command::new(ADD_CMD_NAME)
+                    .about("Adds a `tla2tools` jar to the store.")
+                    .args([
+                        crate::cla::top::project_path_arg(),
+                        clap::Arg::new(JAR_KEY)
+                            .help("Path to the jar")
+                            .value_name(crate::cla::utils::val_name::FILE)
+                            .required(true),
+                        clap::Arg::new(NAME_KEY)
+                            .help("Name of the toolchain, defaults to the name of the jar")
+                            .long("name")
+                            .takes_value(true)
+                            .value_name("NAME"),
+                        clap::Arg::new(DEFAULT_KEY)
+                            .help("Makes the toolchain the default one")
+                            .long("default"),
+                    ]),
+            )
+            .subcommand(
+                clap::Command::new(REMOVE_CMD_NAME)
+                    .about("Removes a toolchain from the store.")
+                    .args([crate::cla::top::project_path_arg(), name_arg()]),
+            )
+            .subcommand(
+                clap::Command::new(DEFAULT_CMD_NAME)
+                    .about("Makes a toolchain of the store the default one.")
+                    .args([crate::cla::top::project_path_arg(), name_arg()]),
+            )
+    }
+
+    /// Constructs a [`Run`] if toolchain subcommand is active.
+    pub fn check_matches(matches: &clap::ArgMatches) -> Option<Res<Run>> {
+        matches
+            .subcommand_matches(CMD_NAME)
+            .map(|matches| match matches.subcommand() {
+                Some((LIST_CMD_NAME, _)) => Ok(Run::new(Action::List)),
+                Some((ADD_CMD_NAME, matches)) => Ok(Run::new(Action::Add {
+                    jar: matches
+                        .value_of(JAR_KEY)
+                        .expect("unreachable: required CLA")
+                        .into(),
+                    name: matches.value_of(NAME_KEY).map(String::from),
+                    default: matches.is_present(DEFAULT_KEY),
+                })),
+                Some((REMOVE_CMD_NAME, matches)) => Ok(Run::new(Action::Remove {
+                    name: matches
+                        .value_of(NAME_KEY)
+                        .expect("unreachable: required CLA")
+                        .into(),
+                })),
+                Some((DEFAULT_CMD_NAME, matches)) => Ok(Run::new(Action::Default {
+                    name: matches
+                        .value_of(NAME_KEY)
+                        .expect("unreachable: required CLA")
+                        .into(),
+                })),
+                Some((sub, _)) => bail!("unexpected toolchain command `{}`", sub),
+                None => bail!("expected toolchain command, found nothing"),
+            })
+    }
+}
+
+/// Action performed by the toolchain mode.
+#[derive(Debug, Clone)]
+pub enum Action {
+    /// Lists the toolchains of the store.
+    List,
+    /// Adds a jar to the store.
+    Add {
+        /// Path to the jar.
+        jar: io::PathBuf,
+        /// Name of the toolchain, the stem of the jar's file name if `None`.
+        name: Option<String>,
+        /// If true, make the toolchain the default one.
+        default: bool,
+    },
+    /// Removes a toolchain from the store.
+    Remove {
+        /// Name of the toolchain.
+        name: String,
+    },
+    /// Makes a toolchain of the store the default one.
+    Default {
+        /// Name of the toolchain.
+        name: String,
+    },
+}
+
+/// Runs toolchain mode.
+#[readonly]
+#[derive(Debug, Clone)]
+pub struct Run {
+    /// Action to perform.
+    pub action: Action,
+}
+impl Run {
+    /// Constructor.
+    pub fn new(action: Action) -> Self {
+        Self { action }
+    }
+
+    /// Launches the toolchain mode.
+    pub fn launch(&self) -> Res<()> {
+        match &self.action {
+            Action::List => Self::list(),
+            Action::Add { jar, name, default } => {
+                let name = match name {
+                    Some(name) => name.clone(),
+                    None => io::file_stem(jar)?,
+                };
+                let path = store::add(jar, &name)?;
+                println!("Added toolchain `{}` as `{}`.", name, path.display());
+                Self::record_version(&name, &path);
+                if *default {
+                    Self::set_default(&name)?;
+                }
+                Ok(())
+            }
+            Action::Remove { name } => {
+                if Self::default_jar()? == store::jar_path(name)? {
+                    bail!(
+                        "cannot remove toolchain `{}`, it is the default one: \
+                        make another toolchain the default one first",
+                        name,
+                    )
+                }
+                store::remove(name)?;
+                println!("Removed toolchain `{}`.", name);
+                Ok(())
+            }
+            Action::Default { name } => Self::set_default(name),
+        }
+    }
+
+    /// Runs TLC once with a toolchain and records the version it reports.
+    ///
+    /// Only warns on failure, the version is then recorded the first time TLC runs with the
+    /// toolchain.
+    fn record_version(name: &str, jar: &io::Path) {
+        let version = conf::toolchain::launcher()
+            .and_then(|launcher| launcher.tlc_cmd(jar))
+            .and_then(project::tlc::probe::run)
+            .and_then(project::tlc::probe::Probe::into_version)
+            .and_then(|version| store::record_tlc_version(name, &version).map(|()| version));
+        match version {
+            Ok(version) => println!("TLC reports version `{}`.", version),
+            Err(e) => log::warn!(
+                "{:?}\nfailed to retrieve the TLC version of toolchain `{}`",
+                e,
+                name
+            ),
+        }
+    }
+
+    /// Path to the jar of the user's configuration.
+    fn default_jar() -> Res<io::PathBuf> {
+        conf::user::try_read(|conf| conf.toolchain.tla2tools.clone())
+    }
+
+    /// Lists the toolchains of the store.
+    fn list() -> Res<()> {
+        // Toolchain mode does not require a project, load its configuration to show its pin.
+        if let Err(e) = conf::project::load() {
+            log::warn!("failed to load project configuration: {:?}", e)
+        }
+        let pinned = store::pinned();
+        let default_jar = Self::default_jar()?;
+        let entries = store::list()?;
+
+        if entries.is_empty() {
+            println!(
+                "No toolchain in the store, add one with `matla toolchain add <JAR>`. \
+                Default toolchain is `{}`.",
+                default_jar.display()
+            );
+            return Ok(());
+        }
+        let styles = conf::Styles::new();
+        let width = entries
+            .iter()
+            .map(|entry| entry.name.len())
+            .max()
+            .unwrap_or(0);
+        for entry in entries.iter() {
+            let mut tags = vec![];
+            if entry.jar == default_jar {
+                tags.push("default");
+            }
+            if pinned.as_ref() == Some(&entry.name) {
+                tags.push("pinned by project");
+            }
+            let tags = if tags.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", tags.join(", "))
+            };
+            println!(
+                "{:width$}  {}{}",
+                styles.bold.paint(&entry.name),
+                entry
+                    .tlc_version
+                    .as_deref()
+                    .unwrap_or("unknown TLC version"),
+                styles.good.paint(tags),
+                width = width,
+            );
+        }
+        if entries.iter().all(|entry| entry.jar != default_jar) {
+            println!(
+                "Default toolchain `{}` is not in the store.",
+                default_jar.display()
+            );
+        }
+        if let Some(pinned) = pinned {
+            if entries.iter().all(|entry| entry.name != pinned) {
+                println!(
+                    "Toolchain `{}` pinned by the project is not in the store.",
+                    pinned
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes a toolchain the default one by updating the user's configuration.
+    fn set_default(name: &str) -> Res<()> {
+        if conf::top_cla::portable()? {
+            bail!(
+                "cannot set the default toolchain in portable mode, there is no user configuration"
+            )
+        }
+        let jar = store::get(name)?;
+        let mut conf = conf::user::try_read(|conf| conf.clone())?;
+        conf.toolchain.tla2tools = jar;
+        conf::user::dump(&conf, true).context("failed to write user configuration")?;
+        let _prev = conf.register()?;
+        println!("Toolchain `{}` is now the default one.", name);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "with_clap")]
+mod cla_spec {
+    prelude!();
+
+    use super::Action;
+
+    /// Toolchain subcommand name.
+    const CMD_NAME: &str = "toolchain";
+    /// List subcommand name.
+    const LIST_CMD_NAME: &str = "list";
+    /// Add subcommand name.
+    const ADD_CMD_NAME: &str = "add";
+    /// Remove subcommand name.
+    const REMOVE_CMD_NAME: &str = "remove";
+    /// Default subcommand name.
+    const DEFAULT_CMD_NAME: &str = "default";
+    /// Key for the jar to add.
+    const JAR_KEY: &str = "TOOLCHAIN_JAR_KEY";
+    /// Key for the name of a toolchain.
+    const NAME_KEY: &str = "TOOLCHAIN_NAME_KEY";
+    /// Key for making an added toolchain the default one.
+    const DEFAULT_KEY: &str = "TOOLCHAIN_DEFAULT_KEY";
+
+    /// Name argument, for subcommands working on a toolchain of the store.
+    fn name_arg() -> clap::Arg<'static> {
+        clap::Arg::new(NAME_KEY)
+            .help("Name of the toolchain")
+            .value_name("NAME")
+            .required(true)
+    }
+    /// Extracts the name argument.
+    fn name(matches: &clap::ArgMatches) -> String {
+        matches
+            .value_of(NAME_KEY)
+            .expect("unreachable: required CLA")
+            .into()
+    }
+
+    impl mode::ClaMode for super::Run {
+        const SUBCOMMAND_IDENT: &'static str = CMD_NAME;
+        const PREREQ: mode::ClaModePrereq = mode::ClaModePrereq::PreProject;
+
+        fn build_command(cmd: clap::Command<'static>) -> clap::Command<'static> {
+            cmd.about("Manages the local store of named `tla2tools` jars.")
+                .arg(cla::top::project_path_arg())
+                .subcommand_required(true)
+                .subcommand(
+                    clap::Command::new(LIST_CMD_NAME)
+                        .about("Lists the toolchains of the store.")
+                        .arg(cla::top::project_path_arg()),
+                )
+                .subcommand(
+                    clap::Command::new(ADD_CMD_NAME)
+                        .about("Adds a `tla2tools` jar to the store.")
+                        .args([
+                            cla::top::project_path_arg(),
+                            clap::Arg::new(JAR_KEY)
+                                .help("Path to the jar")
+                                .value_name(cla::utils::val_name::FILE)
+                                .required(true),
+                            clap::Arg::new(NAME_KEY)
+                                .help("Name of the toolchain, defaults to the name of the jar")
+                                .long("name")
+                                .takes_value(true)
+                                .value_name("NAME"),
+                            clap::Arg::new(DEFAULT_KEY)
+                                .help("Makes the toolchain the default one")
+                                .long("default"),
+                        ]),
+                )
+                .subcommand(
+                    clap::Command::new(REMOVE_CMD_NAME)
+                        .about("Removes a toolchain from the store.")
+                        .args([cla::top::project_path_arg(), name_arg()]),
+                )
+                .subcommand(
+                    clap::Command::new(DEFAULT_CMD_NAME)
+                        .about("Makes a toolchain of the store the default one.")
+                        .args([cla::top::project_path_arg(), name_arg()]),
+                )
+        }
+        fn build(matches: &clap::ArgMatches) -> Res<Self> {
+            let action = match matches.subcommand() {
+                Some((LIST_CMD_NAME, _)) => Action::List,
+                Some((ADD_CMD_NAME, matches)) => Action::Add {
+                    jar: matches
+                        .value_of(JAR_KEY)
+                        .expect("unreachable: required CLA")
+                        .into(),
+                    name: matches.value_of(NAME_KEY).map(String::from),
+                    default: matches.is_present(DEFAULT_KEY),
+                },
+                Some((REMOVE_CMD_NAME, matches)) => Action::Remove {
+                    name: name(matches),
+                },
+                Some((DEFAULT_CMD_NAME, matches)) => Action::Default {
+                    name: name(matches),
+                },
+                Some((sub, _)) => bail!("unexpected toolchain command `{}`", sub),
+                None => bail!("expected toolchain command, found nothing"),
+            };
+            Ok(Self::new(action))
+        }
+        fn run(self) -> Res<Option<i32>> {
+            self.launch()?;
+            Ok(None)
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! Update mode.
 //!
-//! Overwrites the TLA toolbox with the most recent release.
+//! Overwrites the TLA toolbox with the most recent release, or with the jar from the source of
+//! the user's [`conf::Install`] configuration.
 
 prelude!();
 
@@ -39,7 +40,7 @@ impl Run {
     /// Runs update mode.
     pub fn launch(&self) -> Res<()> {
         println!("Updating TLA toolbox...");
-        let conf = match conf::user::try_read(|conf| conf.clone()) {
+        let mut conf = match conf::user::try_read(|conf| conf.clone()) {
             Ok(conf) => conf,
             Err(e) => {
                 report_error(e, ": problem retrieving user configuration file");
@@ -64,7 +65,9 @@ impl Run {
                 self.setup.tla2tools_jar_path.display()
             )
         }
-        self.setup.update_toolbox()?;
+        conf.toolchain.install = self.setup.update_toolbox()?;
+        conf::user::dump(&conf, true).context("failed to record the installed toolbox")?;
+        let _prev = conf.register()?;
 
         println!();
         println!("Updating process completed successfully.");
@@ -0,0 +1,230 @@
+//! Evaluation of TLA+ expressions over the states of a cex, see `matla cex eval`.
+//!
+//! Evaluation generates a [`TraceModule`] following the states of the cex, without restricting
+//! them to the behaviors of the original module. Its `cfg` file checks an invariant that `PrintT`s
+//! a tuple `<<"matla_eval", <position>, <value>>>` in each state, with `<position>` the (1-indexed)
+//! position of the state in the trace. The tuples are then parsed back from TLC's output.
+//!
+//! Trace modules for evaluation are generated in `target/<debug|release>/cex_eval/sources`, and
+//! built in `target/<debug|release>/cex_eval`.
+
+prelude!();
+
+use crate::trace_module::{self, CfgSpec, TraceModule};
+
+/// Name of the sub-directory of the build directory where trace modules are generated.
+pub const SOURCES_DIR: &str = "sources";
+
+/// Name of the invariant printing the value of the expression, also tags the output tuples.
+pub const MARKER: &str = "matla_eval";
+
+/// Generates the trace module evaluating an expression.
+///
+/// - `module` is the module the cex comes from;
+/// - `state`, if any, is the (0-indexed) state to evaluate the expression in, all states if
+///   `None`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use project::{cex_eval::module, trace_module::CfgSpec};
+/// # use cex::{Cex, State, Value};
+/// # use base::Int;
+/// let mut cex = Cex::new();
+/// for cnt in [-1, 0] {
+///     let mut state = State::new(None);
+///     let _ = state.insert("cnt".into(), Value::from(Int::from(cnt)));
+///     let _ = cex.push(state);
+/// }
+/// let spec = CfgSpec::parse("INIT init NEXT next");
+/// let (tla, cfg) = module("matla_eval__sw", "sw", &cex, &spec, "cnt - 1", Some(0))
+///     .render()
+///     .unwrap();
+/// assert!(tla.contains("\nEXTENDS sw, Integers, TLC\n"));
+/// assert!(tla.contains("    [cnt |-> -1],\n    [cnt |-> 0]\n"));
+/// assert!(tla.contains("    IF matla_trace_idx # 1 THEN TRUE\n"));
+/// assert!(tla.contains("        cnt - 1\n"));
+/// assert!(cfg.contains("INVARIANT matla_eval"));
+/// ```
+pub fn module<'a>(
+    name: impl Into<String>,
+    module: impl Into<String>,
+    cex: &'a cex::Cex,
+    cfg: &'a CfgSpec,
+    expr: &str,
+    state: Option<usize>,
+) -> TraceModule<'a> {
+    let idx = format!("{}_idx", trace_module::PREF);
+    // Positions after the last state close lassos, they are not states of the trace.
+    let skip = match state {
+        Some(state) => format!("{} # {}", idx, state + 1),
+        None => format!("{} > {}_len", idx, trace_module::PREF),
+    };
+    let mut def = format!("{} ==\n", MARKER);
+    def.push_str(&format!("    IF {} THEN TRUE\n", skip));
+    def.push_str(&format!("    ELSE PrintT(<<\"{}\", {}, (\n", MARKER, idx));
+    for line in expr.lines() {
+        def.push_str(&format!("        {}\n", line));
+    }
+    def.push_str("    )>>)");
+    TraceModule::new(name, module, cex, cfg)
+        .comment(format!(
+            "Evaluation of an expression over a cex generated by matla, see `{}`.",
+            MARKER
+        ))
+        .extends("TLC")
+        .def(def)
+        .check(format!("INVARIANT {}", MARKER))
+}
+
+/// Extracts the values printed by an evaluation from TLC's output lines.
+///
+/// Returns a map from (0-indexed) states to values.
+///
+/// # Examples
+///
+/// ```rust
+/// # use project::cex_eval::parse_values;
+/// # use cex::value::{Plain, Value};
+/// let lines = [
+///     "Starting...",
+///     r#"<<"matla_eval", 1, {1, 2}>>"#,
+///     r#"<<"matla_eval", 2, [a |-> 1,"#,
+///     r#"  b |-> "b"]>>"#,
+///     "Model checking completed. No error has been found.",
+/// ];
+/// let values = parse_values(lines).unwrap();
+/// assert_eq!(values.len(), 2);
+/// assert_eq!(values[&0].tla().to_string(), "{1, 2}");
+/// assert!(matches!(values[&1], Value::Plain(Plain::SMap(_))));
+/// ```
+pub fn parse_values<'a>(lines: impl IntoIterator<Item = &'a str>) -> Res<Map<usize, cex::Value>> {
+    use cex::value::{Cst, Plain};
+    let start = format!("<<\"{}\"", MARKER);
+    let mut values = Map::new();
+    let mut pending: Option<String> = None;
+
+    for line in lines {
+        let parsed = {
+            let buf = match pending.as_mut() {
+                Some(buf) => {
+                    buf.push('\n');
+                    buf.push_str(line);
+                    buf
+                }
+                None if line.trim_start().starts_with(&start) => pending.insert(line.to_string()),
+                None => continue,
+            };
+            match tlc::parse::cex_value(buf.trim()) {
+                Ok(value) => value,
+                // Value spans more lines.
+                Err(_) => continue,
+            }
+        };
+        pending = None;
+
+        let (pos, value) = match parsed {
+            cex::Value::Plain(Plain::Tuple(tuple)) if tuple.len() == 3 => match &tuple[1] {
+                Plain::Cst(Cst::I(pos)) => (pos.to_string(), tuple[2].clone()),
+                _ => bail!("unexpected evaluation output, expected a state position"),
+            },
+            value => bail!("unexpected evaluation output `{}`", value.tla()),
+        };
+        let pos: usize = pos
+            .parse()
+            .with_context(|| anyhow!("illegal state position `{}`", pos))?;
+        if pos == 0 {
+            bail!("illegal state position `0`")
+        }
+        let _prev = values.insert(pos - 1, value.into());
+    }
+
+    if let Some(buf) = pending {
+        bail!("failed to parse evaluation output `{}`", buf)
+    }
+    Ok(values)
+}
+
+/// Output handler collecting TLC's output lines and errors.
+#[derive(Debug, Clone)]
+pub struct Collector {
+    /// Output lines.
+    pub lines: Vec<String>,
+    /// Errors, including warnings.
+    pub errors: Vec<tlc::TlcError>,
+}
+impl Collector {
+    /// Constructor.
+    pub fn new() -> Self {
+        Self {
+            lines: Vec::with_capacity(113),
+            errors: vec![],
+        }
+    }
+}
+impl tlc::Out for Collector {
+    fn handle_message(&mut self, msg: &tlc::msg::Msg, _log_level: log::Level) {
+        self.lines.extend(msg.lines().into_iter().map(String::from))
+    }
+    fn handle_outcome(&mut self, _outcome: RunOutcome) {}
+    fn handle_error(&mut self, error: impl Into<tlc::TlcError>) -> Res<()> {
+        self.errors.push(error.into());
+        Ok(())
+    }
+    fn handle_cex(&mut self, _cex: cex::Cex) {}
+}
+
+/// Result of an evaluation.
+pub struct Evaluation {
+    /// Project the evaluation ran on, needed to report errors.
+    pub project: FullProject,
+    /// Value of the expression in each (0-indexed) state it was evaluated in.
+    pub values: Map<usize, cex::Value>,
+    /// Errors and warnings raised by TLC.
+    pub errors: Vec<tlc::TlcError>,
+}
+impl Evaluation {
+    /// Values of the expression for each state of a trace of length `len`.
+    pub fn column(&self, len: usize) -> Vec<Option<cex::Value>> {
+        (0..len).map(|idx| self.values.get(&idx).cloned()).collect()
+    }
+}
+
+/// Evaluates an expression over the states of a saved cex.
+///
+/// - `state`, if any, is the (0-indexed) state to evaluate the expression in, all states if
+///   `None`.
+pub fn eval(
+    src_project: &SourceProject,
+    release: bool,
+    saved: &crate::cex_store::SavedCex,
+    expr: &str,
+    state: Option<usize>,
+) -> Res<Evaluation> {
+    saved.cex.check_not_alias("evaluations")?;
+    if let Some(state) = state {
+        let _ = saved.cex.idx_of(state)?;
+    }
+    let target = conf::Target::new_cex_eval(src_project.path()?, release);
+    let name = format!("{}__{}", MARKER, saved.module);
+    let cfg = CfgSpec::load(src_project, &saved.module)?;
+    let (tla_path, cfg_path) = module(&name, &saved.module, &saved.cex, &cfg, expr, state)
+        .write(&target.build_path.join(SOURCES_DIR))?;
+
+    let mut proj = src_project.clone();
+    let _tla_idx = proj.add_file(tla_path)?;
+    let _cfg_idx = proj.add_file(cfg_path)?;
+    let tlc_cla = conf::TlcCla::default()
+        .workers(Some(1))
+        .into_customl(conf::customl::Source::Custom("cex evaluation"));
+    let (project, _) = proj.into_full(Some(name), target, Some(&tlc_cla))?;
+
+    let mut out = Collector::new();
+    let _outcome = project.run_tlc_async(&mut out)?.run()?;
+    let values = parse_values(out.lines.iter().map(|line| line as &str))?;
+    Ok(Evaluation {
+        project,
+        values,
+        errors: out.errors,
+    })
+}
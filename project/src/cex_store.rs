@@ -0,0 +1,341 @@
+//! Persistent storage of the counterexamples produced by TLC runs.
+//!
+//! Cexs are saved in the `cex` directory of the build path of a target, *i.e.*
+//! `target/<debug|release>/cex`, one file per cex. Files are named `<module>-<date>-<n>.cex`: the
+//! module that was run, the date of the run and a number making the name unique.
+//!
+//! A cex file is a header of TLA+ comments followed by the states of the trace, in TLC's syntax:
+//!
+//! ```text
+//! \* matla counterexample
+//! \* module: sw
+//! \* falsified: cnt_leq_10
+//! \* seed: 0
+//! \* date: 2022-05-04T14:02:31+00:00
+//! \* shape: finite
+//!
+//! 1: <Initial predicate>
+//! /\ cnt = 0
+//!
+//! 2: <inc line 14, col 1 to line 16, col 10 of module sw>
+//! /\ cnt = 1
+//! ```
+//!
+//! The `shape` is `finite`, `stuttering` or `loop <n>` where `<n>` is the (1-indexed) state the
+//! trace loops back to. Cexs produced with an `ALIAS` (see [`conf::ModuleConf`]) have an `alias`
+//! entry, their states are the output of the alias rather than valuations of the state variables.
+//! Other header entries are kept in [`SavedCex::extra`], regression tests use them to specify what
+//! they expect.
+
+prelude!();
+
+/// Name of the directory storing cexs in the build path.
+pub const DIR_NAME: &str = "cex";
+/// Extension of cex files.
+pub const FILE_EXT: &str = "cex";
+
+/// First line of cex files.
+const HEADER: &str = "\\* matla counterexample";
+/// Prefix of header lines.
+const PREF: &str = "\\* ";
+
+/// A counterexample saved in a [`Store`].
+#[derive(Debug, Clone)]
+pub struct SavedCex {
+    /// Identifier, the stem of the cex file.
+    pub id: String,
+    /// Path to the cex file.
+    pub path: io::PathBuf,
+    /// Module that was run.
+    pub module: String,
+    /// Seed of the run, if any.
+    pub seed: Option<u64>,
+    /// Date of the run.
+    pub date: chrono::DateTime<chrono::Utc>,
+    /// The actual cex.
+    pub cex: cex::Cex,
+    /// Additional header entries.
+    pub extra: Map<String, String>,
+}
+impl SavedCex {
+    /// Writes a cex file.
+    fn write(&self, w: &mut impl io::Write) -> Res<()> {
+        writeln!(w, "{}", HEADER)?;
+        writeln!(w, "{}module: {}", PREF, self.module)?;
+        if let Some(falsified) = self.cex.falsified.as_ref() {
+            writeln!(w, "{}falsified: {}", PREF, falsified)?;
+        }
+        if let Some(seed) = self.seed {
+            writeln!(w, "{}seed: {}", PREF, seed)?;
+        }
+        writeln!(w, "{}date: {}", PREF, self.date.to_rfc3339())?;
+        let shape = match self.cex.shape {
+            cex::Shape::Finite => "finite".to_string(),
+            cex::Shape::Stuttering => "stuttering".to_string(),
+            cex::Shape::Loop(idx) => format!("loop {}", *idx + 1),
+        };
+        writeln!(w, "{}shape: {}", PREF, shape)?;
+        if let Some(alias) = self.cex.alias.as_ref() {
+            writeln!(w, "{}alias: {}", PREF, alias)?;
+        }
+        for (key, val) in self.extra.iter() {
+            writeln!(w, "{}{}: {}", PREF, key, val)?;
+        }
+
+        for (idx, state) in self.cex.states.index_iter() {
+            writeln!(w)?;
+            match state.info.as_ref() {
+                None => writeln!(w, "{}: <Initial predicate>", *idx + 1)?,
+                Some(info) => writeln!(
+                    w,
+                    "{}: <{} line {}, col {} to line {}, col {} of module {}>",
+                    *idx + 1,
+                    info.action,
+                    info.span.0.row,
+                    info.span.0.col,
+                    info.span.1.row,
+                    info.span.1.col,
+                    info.module,
+                )?,
+            }
+            for (var, val) in state.values.iter() {
+                writeln!(w, "/\\ {} = {}", var, val.tla())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes itself to a file, does **not** update [`Self::path`].
+    pub fn write_to(&self, path: impl AsRef<io::Path>, overwrite: bool) -> Res<()> {
+        let path = path.as_ref();
+        let mut file = io::write_file(path, overwrite, false)?;
+        self.write(&mut file)
+            .with_context(|| anyhow!("failed to write cex file `{}`", path.display()))
+    }
+
+    /// Loads a cex file.
+    pub fn load(path: impl Into<io::PathBuf>) -> Res<Self> {
+        let path = path.into();
+        let content = io::load_file(&path)?;
+        Self::parse(path, &content)
+    }
+
+    /// Parses the content of a cex file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use project::cex_store::SavedCex;
+    /// let content = r#"\* matla counterexample
+    /// \* module: sw
+    /// \* falsified: cnt_leq_10
+    /// \* date: 2022-05-04T14:02:31+00:00
+    /// \* shape: loop 1
+    /// \* alias: DebugAlias
+    ///
+    /// 1: <Initial predicate>
+    /// /\ cnt = 0
+    /// /\ name = "sw"
+    ///
+    /// 2: <inc line 14, col 1 to line 16, col 10 of module sw>
+    /// /\ cnt = 1
+    /// /\ name = "sw"
+    /// "#;
+    /// let saved = SavedCex::parse("sw-20220504140231-0.cex", content).unwrap();
+    /// assert_eq!(saved.id, "sw-20220504140231-0");
+    /// assert_eq!(saved.module, "sw");
+    /// assert_eq!(saved.seed, None);
+    /// assert_eq!(saved.cex.falsified.as_deref(), Some("cnt_leq_10"));
+    /// assert_eq!(saved.cex.shape, cex::Shape::Loop(saved.cex.idx_of(0).unwrap()));
+    /// assert_eq!(saved.cex.alias.as_deref(), Some("DebugAlias"));
+    /// assert_eq!(saved.cex.states.len(), 2);
+    /// let info = saved.cex.states[saved.cex.idx_of(1).unwrap()].info.as_ref().unwrap();
+    /// assert_eq!(info.action, "inc");
+    /// assert!(info.span.1.is(16, 10));
+    /// ```
+    pub fn parse(path: impl Into<io::PathBuf>, content: &str) -> Res<Self> {
+        let path = path.into();
+        let id = io::file_stem(&path)?;
+        let mut lines = content.lines().peekable();
+
+        if lines.next().map(str::trim) != Some(HEADER) {
+            bail!("expected cex file to start with `{}`", HEADER)
+        }
+        let (mut module, mut falsified, mut seed, mut date, mut shape, mut alias) =
+            (None, None, None, None, None, None);
+        let mut extra = Map::new();
+        while let Some(line) = lines
+            .peek()
+            .copied()
+            .and_then(|line| line.strip_prefix(PREF))
+        {
+            let _ = lines.next();
+            let (key, val) = line
+                .split_once(':')
+                .map(|(key, val)| (key.trim(), val.trim()))
+                .ok_or_else(|| anyhow!("illegal cex file header line `{}`", line))?;
+            match key {
+                "module" => module = Some(val.to_string()),
+                "falsified" => falsified = Some(val.to_string()),
+                "seed" => {
+                    seed = Some(
+                        val.parse::<u64>()
+                            .map_err(|_| anyhow!("illegal seed `{}`", val))?,
+                    )
+                }
+                "date" => {
+                    date = Some(
+                        chrono::DateTime::parse_from_rfc3339(val)
+                            .map_err(|_| anyhow!("illegal date `{}`", val))?
+                            .with_timezone(&chrono::Utc),
+                    )
+                }
+                "shape" => shape = Some(val.to_string()),
+                "alias" => alias = Some(val.to_string()),
+                _ => {
+                    let _prev = extra.insert(key.to_string(), val.to_string());
+                }
+            }
+        }
+        let module = module.ok_or_else(|| anyhow!("cex file header has no module"))?;
+        let date = date.ok_or_else(|| anyhow!("cex file header has no date"))?;
+
+        let mut cex = cex::Cex::new();
+        cex.falsified = falsified;
+        cex.alias = alias;
+        let states = lines.collect::<Vec<_>>().join("\n");
+        for block in states.split("\n\n") {
+            let block = block.trim();
+            if block.is_empty() {
+                continue;
+            }
+            let (index, state) = tlc::parse::trace_state(block)
+                .map_err(|e| anyhow!("illegal cex state: {}", e))??;
+            if index != cex.states.len() + 1 {
+                bail!("expected state #{}, got #{}", cex.states.len() + 1, index)
+            }
+            let _idx = cex.push(state);
+        }
+
+        cex.shape = match shape.as_deref() {
+            None | Some("finite") => cex::Shape::Finite,
+            Some("stuttering") => cex::Shape::Stuttering,
+            Some(loop_shape) => {
+                let target = loop_shape
+                    .strip_prefix("loop")
+                    .and_then(|n| n.trim().parse::<usize>().ok())
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| anyhow!("illegal cex shape `{}`", loop_shape))?;
+                cex::Shape::Loop(cex.idx_of(target - 1)?)
+            }
+        };
+
+        Ok(Self {
+            id,
+            path,
+            module,
+            seed,
+            date,
+            cex,
+            extra,
+        })
+    }
+}
+
+/// Stores the cexs of a target.
+#[derive(Debug, Clone)]
+pub struct Store {
+    /// Directory cex files live in.
+    pub dir: io::PathBuf,
+}
+impl Store {
+    /// Constructor, stores cexs in the `cex` directory of the target's build path.
+    pub fn new(target: &conf::Target) -> Self {
+        Self {
+            dir: target.build_path.join(DIR_NAME),
+        }
+    }
+
+    /// Saves a cex, returns the saved cex.
+    pub fn save(
+        &self,
+        module: impl Into<String>,
+        seed: Option<u64>,
+        cex: cex::Cex,
+    ) -> Res<SavedCex> {
+        let module = module.into();
+        io::create_dir_all(&self.dir)
+            .with_context(|| anyhow!("failed to create cex directory `{}`", self.dir.display()))?;
+        let date = chrono::Utc::now();
+        let pref = format!("{}-{}", module, date.format("%Y%m%d%H%M%S"));
+        let (id, path) = (0..)
+            .map(|n| {
+                let id = format!("{}-{}", pref, n);
+                let path = self.dir.join(&id).with_extension(FILE_EXT);
+                (id, path)
+            })
+            .find(|(_, path)| !path.exists())
+            .expect("unreachable: infinite iterator");
+
+        let saved = SavedCex {
+            id,
+            path,
+            module,
+            seed,
+            date,
+            cex,
+            extra: Map::new(),
+        };
+        saved.write_to(&saved.path, false)?;
+        Ok(saved)
+    }
+
+    /// Loads all the cexs of the store, oldest first.
+    ///
+    /// Files that cannot be loaded are ignored with a warning.
+    pub fn list(&self) -> Res<Vec<SavedCex>> {
+        let mut res = vec![];
+        if !self.dir.is_dir() {
+            return Ok(res);
+        }
+        let entries = self
+            .dir
+            .read_dir()
+            .with_context(|| anyhow!("failed to read cex directory `{}`", self.dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if io::file_ext(&path).as_ref().and_then(|ext| ext.to_str()) != Some(FILE_EXT) {
+                continue;
+            }
+            match SavedCex::load(&path) {
+                Ok(saved) => res.push(saved),
+                Err(e) => log::warn!("ignoring cex file `{}`: {:?}", path.display(), e),
+            }
+        }
+        res.sort_by(|c1, c2| (c1.date, &c1.id).cmp(&(c2.date, &c2.id)));
+        Ok(res)
+    }
+
+    /// Most recent cex, if any.
+    pub fn latest(&self) -> Res<Option<SavedCex>> {
+        Ok(self.list()?.pop())
+    }
+
+    /// Loads a cex from its identifier or the path to its file.
+    pub fn load(&self, id_or_path: impl AsRef<str>) -> Res<SavedCex> {
+        let id_or_path = id_or_path.as_ref();
+        let path = self.dir.join(id_or_path).with_extension(FILE_EXT);
+        if path.is_file() {
+            return SavedCex::load(path);
+        }
+        let path = io::PathBuf::from(id_or_path);
+        if path.is_file() {
+            return SavedCex::load(path);
+        }
+        bail!(
+            "unknown cex `{}`, not a cex identifier in `{}` nor a cex file",
+            id_or_path,
+            self.dir.display(),
+        )
+    }
+}
@@ -0,0 +1,254 @@
+//! Documentation extraction from TLA modules.
+//!
+//! Documentation comments are (consecutive) line comments (`\*`) or block comments (`(* ... *)`)
+//! placed right before a top-level operator definition, or right after the module header (blank
+//! lines aside) for the module's own documentation. Banner lines only made of `*`s are treated as
+//! empty lines, and a definition can start on the line closing a block comment.
+//!
+//! ```rust
+//! # use project::doc::ModuleDoc;
+//! let content = r#"
+//! ---- MODULE sw ----
+//! \* A stopwatch.
+//!
+//! EXTENDS Naturals
+//!
+//! \* Initial predicate.
+//! \*
+//! \* The counter starts at zero.
+//! init == cnt = 0
+//!
+//! \* Not a doc comment, not right before a definition.
+//!
+//! (*************)
+//! (* Adds `n`. *)
+//! (*************)
+//! add(n) == cnt' = cnt + n
+//! LOCAL helper[x \in Nat] == x + 1
+//! ====
+//! "#;
+//! let doc = ModuleDoc::new(content).unwrap();
+//! assert_eq!(doc.module, "sw");
+//! assert_eq!(doc.doc, vec!["A stopwatch."]);
+//! assert_eq!(doc.operators.len(), 3);
+//!
+//! assert_eq!(doc.operators[0].name, "init");
+//! assert_eq!(doc.operators[0].signature, "init");
+//! assert_eq!(doc.operators[0].row, 10);
+//! assert_eq!(doc.operators[0].doc_row, 7);
+//! assert_eq!(doc.operators[0].doc, vec!["Initial predicate.", "", "The counter starts at zero."]);
+//!
+//! assert_eq!(doc.operators[1].name, "add");
+//! assert_eq!(doc.operators[1].signature, "add(n)");
+//! assert_eq!(doc.operators[1].row, 17);
+//! assert_eq!(doc.operators[1].doc, vec!["", "Adds `n`.", ""]);
+//!
+//! assert_eq!(doc.operators[2].name, "helper");
+//! assert_eq!(doc.operators[2].signature, r"helper[x \in Nat]");
+//! assert!(doc.operators[2].local);
+//! assert_eq!(doc.operators[2].col, 7);
+//! ```
+
+prelude!();
+
+lazy_static! {
+    /// Matches a module header, captures the module's name.
+    static ref MODULE_REGEX: Regex = Regex::new(r"^\s*-{4,}\s*MODULE\s+(\w+)\s*-{4,}\s*$").unwrap();
+    /// Matches a top-level operator or function definition.
+    ///
+    /// Captures `LOCAL` (if any), the whole signature, and the name.
+    static ref DEF_REGEX: Regex = Regex::new(
+        r"^(LOCAL\s+)?(([A-Za-z_][A-Za-z0-9_]*)\s*(\([^)]*\)|\[[^\]]*\])?)\s*=="
+    )
+    .unwrap();
+}
+
+/// Documentation of an operator.
+#[derive(Debug, Clone)]
+pub struct OperatorDoc {
+    /// Operator name.
+    pub name: String,
+    /// Operator signature: name and parameters, if any.
+    pub signature: String,
+    /// True if the operator is `LOCAL`.
+    pub local: bool,
+    /// Line of the definition (1-indexed).
+    pub row: usize,
+    /// Column of the operator's name in the definition (1-indexed).
+    pub col: usize,
+    /// Line of the first documentation line (1-indexed), irrelevant if `doc` is empty.
+    pub doc_row: usize,
+    /// Documentation lines, without the comment delimiters.
+    ///
+    /// There is exactly one documentation line per line of comment, starting at `doc_row`.
+    pub doc: Vec<String>,
+}
+
+/// Documentation of a module.
+#[derive(Debug, Clone)]
+pub struct ModuleDoc {
+    /// Module name.
+    pub module: String,
+    /// Module documentation lines, without the comment delimiters.
+    pub doc: Vec<String>,
+    /// Top-level operators, in order of appearance.
+    pub operators: Vec<OperatorDoc>,
+}
+impl ModuleDoc {
+    /// Extracts the documentation of a module from its content.
+    ///
+    /// Fails if the content has no module header.
+    ///
+    /// ```rust
+    /// # use project::doc::ModuleDoc;
+    /// let content = r#"
+    /// ---- MODULE sw_0 ----
+    ///
+    /// \* A stopwatch counting up to ten.
+    ///
+    /// EXTENDS Naturals
+    /// ====
+    /// "#;
+    /// let doc = ModuleDoc::new(content).unwrap();
+    /// assert_eq!(doc.doc, vec!["A stopwatch counting up to ten."]);
+    /// ```
+    ///
+    /// Inside multi-line block comments, banner lines are empty lines and code can follow the end
+    /// of the comment.
+    ///
+    /// ```rust
+    /// # use project::doc::ModuleDoc;
+    /// let content = r#"
+    /// ---- MODULE sw_1 ----
+    /// EXTENDS Naturals
+    /// (*****************
+    ///   Resets the counter.
+    ///  ****************
+    ///   Never disabled.
+    /// ******************) reset == cnt' = 0
+    /// ====
+    /// "#;
+    /// let doc = ModuleDoc::new(content).unwrap();
+    /// assert_eq!(doc.operators.len(), 1);
+    /// let reset = &doc.operators[0];
+    /// assert_eq!(reset.name, "reset");
+    /// assert_eq!((reset.row, reset.col), (8, 21));
+    /// assert_eq!(reset.doc_row, 4);
+    /// assert_eq!(reset.doc, vec!["", "  Resets the counter.", "", "  Never disabled.", ""]);
+    /// ```
+    pub fn new(content: &str) -> Res<Self> {
+        let mut lines = content.lines().enumerate();
+
+        let module = loop {
+            if let Some((_, line)) = lines.next() {
+                if let Some(caps) = MODULE_REGEX.captures(line) {
+                    break caps[1].to_string();
+                }
+            } else {
+                bail!("failed to find module header")
+            }
+        };
+
+        let mut doc = vec![];
+        let mut operators = vec![];
+        // True until we see anything that's not a comment after the header.
+        let mut in_header = true;
+        let mut pending: Vec<String> = vec![];
+        let mut pending_row = 0;
+        // True if we are inside a multi-line block comment.
+        let mut in_block = false;
+
+        for (row, mut line) in lines {
+            // Column of `line` in the actual line, non-zero for code after the end of a block.
+            let mut offset = 0;
+            if in_block {
+                let content = if let Some(end) = line.find("*)") {
+                    in_block = false;
+                    offset = end + 2;
+                    &line[..end]
+                } else {
+                    line
+                };
+                let content = if content.trim().chars().all(|c| c == '*') {
+                    ""
+                } else {
+                    content.trim_end()
+                };
+                pending.push(content.to_string());
+                if in_block || line[offset..].trim().is_empty() {
+                    continue;
+                }
+                let code = line[offset..].trim_start();
+                offset = line.len() - code.len();
+                line = code;
+            }
+
+            let trimmed = line.trim();
+            // Blank lines between the header and the module's documentation.
+            if in_header && pending.is_empty() && trimmed.is_empty() {
+                continue;
+            }
+            let comment = if let Some(comment) = trimmed.strip_prefix("\\*") {
+                Some(comment)
+            } else if let Some(comment) = trimmed.strip_prefix("(*") {
+                if let Some(end) = comment.find("*)") {
+                    Some(&comment[..end])
+                } else {
+                    in_block = true;
+                    Some(comment)
+                }
+            } else {
+                None
+            };
+            if let Some(comment) = comment {
+                let comment = if comment.chars().all(|c| c == '*') {
+                    ""
+                } else {
+                    comment.strip_prefix(' ').unwrap_or(comment)
+                };
+                if pending.is_empty() {
+                    pending_row = row + 1;
+                }
+                pending.push(comment.trim_end().to_string());
+                continue;
+            }
+
+            if in_header && !pending.is_empty() {
+                doc = mem::take(&mut pending);
+            }
+            in_header = false;
+
+            if trimmed.starts_with("====") {
+                break;
+            }
+
+            if let Some(caps) = DEF_REGEX.captures(line) {
+                operators.push(OperatorDoc {
+                    name: caps[3].to_string(),
+                    signature: caps[2].trim().to_string(),
+                    local: caps.get(1).is_some(),
+                    row: row + 1,
+                    col: offset + caps.get(3).map(|name| name.start() + 1).unwrap_or(1),
+                    doc_row: pending_row,
+                    doc: mem::take(&mut pending),
+                })
+            } else {
+                pending.clear()
+            }
+        }
+
+        Ok(Self {
+            module,
+            doc,
+            operators,
+        })
+    }
+
+    /// Loads the documentation of a module from a file.
+    pub fn load(path: impl AsRef<io::Path>) -> Res<Self> {
+        let path = path.as_ref();
+        let content = io::load_file(path)?;
+        Self::new(&content)
+            .with_context(|| anyhow!("failed to extract documentation of `{}`", path.display()))
+    }
+}
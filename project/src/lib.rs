@@ -73,9 +73,13 @@
 //!
 //! # Sub-modules
 //!
+//! - [`cex_eval`]: evaluates TLA+ expressions over the states of a cex;
+//! - [`cex_store`]: persists the counterexamples produced by TLC runs;
+//! - [`doc`]: extracts module/operator documentation from TLA modules;
 //! - [`matla`]: generates the debug/release version of the `Matla` TLA module, which contains
 //!   conditionally-compiled assertion helpers;
-//! - [`tlc`]: handles TLC runs.
+//! - [`tlc`]: handles TLC runs;
+//! - [`trace_module`]: generates TLA+ modules following the states of a cex.
 //!
 //! [`runtime`]: tlc::runtime (runtime module)
 //! [`Control`]: tlc::runtime::control::Control (Control type)
@@ -102,8 +106,12 @@ macro_rules! prelude {
     };
 }
 
+pub mod cex_eval;
+pub mod cex_store;
+pub mod doc;
 pub mod matla;
 pub mod tlc;
+pub mod trace_module;
 
 prelude!();
 
@@ -132,7 +140,8 @@ pub mod idx {
 }
 
 /// Either stores a module or unit-variants representing either the top TLA or the top CFG file.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ModuleOrTop {
     Module(String),
     TopTla,
@@ -782,8 +791,8 @@ impl SourceProject {
     ) -> Res<(FullProject, conf::customl::TlcCla)> {
         let target = &target_conf.build_path;
         let release = target_conf.release;
-        let target = self.to_target(target, release)?;
         let conf = self.load_toml_config()?;
+        let target = self.to_target(target, release, &conf)?;
         FullProject::new(entry, target_conf, self, conf, target, tlc_cla)
     }
 
@@ -792,7 +801,14 @@ impl SourceProject {
     /// - Recursively creates the target directory if needed.
     /// - Deletes any and all tla/cfg files not present in `self`.
     /// - Only copies files that either don't exist in the target, or are older in the target.
-    pub fn to_target(&self, target: impl Into<io::PathBuf>, release: bool) -> Res<TargetProject> {
+    /// - Cfg files are compared by content instead, as their target version depends on `config`:
+    ///   the `ALIAS`/`VIEW` of their module's [`conf::ModuleConf`], if any, are injected in them.
+    pub fn to_target(
+        &self,
+        target: impl Into<io::PathBuf>,
+        release: bool,
+        config: &conf::Project,
+    ) -> Res<TargetProject> {
         let target = target.into();
         if !target.is_dir() {
             log::trace!("creating target directory `{}`", target.display());
@@ -846,6 +862,20 @@ impl SourceProject {
                 bail!("target file `{}` is a directory", file_target.display());
             }
 
+            if file.is_cfg() {
+                use io::Write;
+                let content = Self::target_cfg_content(file, config.module(file.module()))?;
+                if file_target.is_file() && io::load_file(&file_target)? == content {
+                    continue 'copy_new_or_newer;
+                }
+                log::trace!("writing target cfg file `{}`", file_target.display());
+                let mut w = io::write_file(&file_target, true, false)?;
+                w.write_all(content.as_bytes()).with_context(|| {
+                    anyhow!("failed to write cfg file `{}`", file_target.display())
+                })?;
+                continue 'copy_new_or_newer;
+            }
+
             if file_target.is_file() {
                 if io::file_modified(&file_target)? >= io::file_modified(file.path())? {
                     continue 'copy_new_or_newer;
@@ -865,6 +895,39 @@ impl SourceProject {
         target_project.overwrite_matla_module(release)?;
         Ok(target_project)
     }
+
+    /// Content of the target version of a cfg file, with the `ALIAS`/`VIEW` of its module injected.
+    fn target_cfg_content(file: &TlaFile, module_conf: Option<&conf::ModuleConf>) -> Res<String> {
+        let mut content = io::load_file(file.path())?;
+        let lines = module_conf.map(|conf| conf.cfg_lines()).unwrap_or_default();
+        if lines.is_empty() {
+            return Ok(content);
+        }
+        for line in content.lines() {
+            let keyword = line.split_whitespace().next();
+            if keyword == Some("ALIAS") || keyword == Some("VIEW") {
+                bail!(
+                    "cfg file `{}` already specifies `{}`, \
+                    please remove it or the `[modules.{}]` section of the project's configuration",
+                    file.path().display(),
+                    line.trim(),
+                    file.module(),
+                )
+            }
+        }
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&format!(
+            "\n\\* Injected by matla from `[modules.{}]`.\n",
+            file.module()
+        ));
+        for line in lines {
+            content.push_str(&line);
+            content.push('\n');
+        }
+        Ok(content)
+    }
 }
 
 impl TargetProject {
@@ -939,6 +1002,7 @@ impl FullProject {
                 res.receive(&tlc_cla);
                 // println!("receives top-level TLC CLA:\n{:#?}", res);
             }
+            res.check().context("inconsistent TLC-level configuration")?;
             res
         };
         Ok((
@@ -955,6 +1019,16 @@ impl FullProject {
         ))
     }
 
+    /// Target configuration.
+    pub fn target_conf(&self) -> &conf::Target {
+        &self.target_conf
+    }
+
+    /// Store for the cexs produced by runs of this project.
+    pub fn cex_store(&self) -> cex_store::Store {
+        cex_store::Store::new(&self.target_conf)
+    }
+
     /// Source TLA file from a module name.
     pub fn source_tla_file(&self, module: impl AsRef<str>) -> Option<&TlaFile> {
         let module = module.as_ref();
@@ -1015,8 +1089,13 @@ impl FullProject {
     }
 
     /// Runs TLC on a module, async version.
+    ///
+    /// Requests a [`tlc::dump`] of the trace in the build directory if TLC supports it, and records
+    /// the version of TLC there. Cexs are marked as the output of the module's `ALIAS`, if any.
     pub fn run_tlc_async<Out: tlc::Out>(&self, handler: Out) -> Res<tlc::TlcRun<Out>> {
-        let tlc_cmd = self.tlc_cmd()?;
+        let mut tlc_cmd = self.tlc_cmd()?;
+        let trace_dump = tlc::dump::request(&mut tlc_cmd, &self.target_conf.build_path)?;
+        let alias = self.entry_alias();
         let module = &self.actual_entry;
         if let Some(idx) = self.target.top_modules.get(module) {
             if !self.target.tla_to_cfg.contains_key(idx) {
@@ -1025,12 +1104,42 @@ impl FullProject {
                     module,
                 )
             }
-            self.target.files[*idx].run_tlc_async(tlc_cmd, handler)
+            self.target.files[*idx]
+                .run_tlc_async(tlc_cmd, handler)
+                .map(|run| {
+                    let run = run.record_version(&self.target_conf.build_path);
+                    let run = match trace_dump {
+                        Some(trace_dump) => run.trace_dump(trace_dump),
+                        None => run,
+                    };
+                    run.keep_going(self.tlc_cla.keep_going).alias(alias)
+                })
         } else {
             bail!("cannot run TLC on unknown module `{}`", module)
         }
     }
 
+    /// Replays a [`tlc::record::Recording`] of a TLC run on a module, does not run TLC.
+    ///
+    /// Same as [`Self::run_tlc_async`], except that there is no trace dump. The recording should
+    /// come from a run with the same options, in particular [`conf::TlcCla::keep_going`].
+    pub fn replay_tlc<Out: tlc::Out>(
+        &self,
+        recording: tlc::record::Recording,
+        handler: Out,
+    ) -> tlc::TlcRun<Out> {
+        tlc::TlcRun::replay(recording, handler)
+            .keep_going(self.tlc_cla.keep_going)
+            .alias(self.entry_alias())
+    }
+
+    /// `ALIAS` of the entry module, if any, see [`conf::ModuleConf`].
+    fn entry_alias(&self) -> Option<String> {
+        self.config
+            .module(&self.actual_entry)
+            .and_then(|conf| conf.alias.clone())
+    }
+
     /// Completes a TLC command.
     pub fn complete_tlc_cmd(&self) -> Res<io::Command> {
         let tlc_cmd = self.tlc_cmd()?;
@@ -16,12 +16,16 @@
 //!
 //! - [`parse`]: parsers for TLC (`code`) messages;
 //! - [`code`]: hierarchy of TLC raw messages, each have a specific message *code*;
+//! - [`coverage`]: aggregates the coverage statistics TLC reports with `-coverage`;
+//! - [`dump`]: builds cexs from the JSON trace dump TLC writes with `-dumpTrace json`;
 //! - [`msg`]: builds a higher-level notion of message on top of `code`, along with a [`TlcHandler`]
 //!   which wraps TLC's output and parses its messages;
 //! - [`err`]: error types, from low-level to top-level;
 //! - [`warn`]: warning type, separate from errors;
+//! - [`probe`]: runs TLC on a trivial module, to check it works and retrieve its version;
 //! - [`outcome`]: all outcome types: (`runtime`) *mode*, raw analysis outcome, failed outcome, top
 //!   analysis outcome;
+//! - [`record`]: recordings of TLC's raw output, replayable without running TLC;
 //! - [`runtime`]: handles the logics of TLC runs, in particular runtime modes (warmup, parsing,
 //!   semantic analysis...).
 //!
@@ -31,10 +35,14 @@
 prelude!();
 
 pub mod code;
+pub mod coverage;
+pub mod dump;
 pub mod err;
 pub mod msg;
 pub mod outcome;
 pub mod parse;
+pub mod probe;
+pub mod record;
 pub mod runtime;
 pub mod warn;
 
@@ -82,26 +90,164 @@ impl Out for () {
     fn handle_cex(&mut self, _cex: cex::Cex) {}
 }
 
+/// Output handler wrapper holding cexs back, see [`TlcRun::trace_dump`] and [`TlcRun::alias`].
+struct HoldCexs<'a, O> {
+    out: &'a mut O,
+    cexs: &'a mut Vec<cex::Cex>,
+}
+impl<'a, O: Out> Out for HoldCexs<'a, O> {
+    fn handle_message(&mut self, msg: &msg::Msg, log_level: log::Level) {
+        self.out.handle_message(msg, log_level)
+    }
+    fn handle_outcome(&mut self, outcome: RunOutcome) {
+        self.out.handle_outcome(outcome)
+    }
+    fn handle_error(&mut self, error: impl Into<tlc::err::TlcError>) -> Res<()> {
+        self.out.handle_error(error)
+    }
+    fn handle_cex(&mut self, cex: cex::Cex) {
+        self.cexs.push(cex)
+    }
+}
+
 /// Handles the whole TLC run.
 pub struct TlcRun<O> {
     tlc: msg::TlcHandler,
     tlc_lines: Option<Vec<String>>,
     out_handler: O,
     runtime: runtime::Runtime,
+    trace_dump: Option<io::PathBuf>,
+    version_dir: Option<io::PathBuf>,
+    alias: Option<String>,
+    held_cexs: Vec<cex::Cex>,
 }
 impl<O: Out> TlcRun<O> {
     /// Constructor.
     pub fn new(cmd: io::Command, out_handler: O) -> Self {
         log::debug!("running TLC with {:?}", cmd);
-        let tlc = msg::TlcHandler::new(cmd);
+        Self::of_handler(msg::TlcHandler::new(cmd), out_handler)
+    }
+
+    /// Constructor replaying a [`record::Recording`], does not run TLC.
+    pub fn replay(recording: record::Recording, out_handler: O) -> Self {
+        log::debug!("replaying TLC run ({} lines)", recording.lines.len());
+        Self::of_handler(msg::TlcHandler::replay(recording), out_handler)
+    }
+
+    /// Constructor from a TLC handler.
+    fn of_handler(tlc: msg::TlcHandler, out_handler: O) -> Self {
         Self {
             tlc,
             tlc_lines: None,
             out_handler,
             runtime: runtime::Runtime::init(),
+            trace_dump: None,
+            version_dir: None,
+            alias: None,
+            held_cexs: vec![],
         }
     }
 
+    /// Sets the path to the [`dump`] of the trace requested from TLC.
+    ///
+    /// Cexs are then held back until the end of the run, since TLC writes the dump after printing
+    /// the trace. If TLC produced a single cex and the dump can be loaded, the cex comes from the
+    /// dump and TLC's text output only fills in what the dump lacks, see [`dump::merge`]. Values
+    /// matla fails to parse in TLC's text output are resolved from the dump.
+    pub fn trace_dump(mut self, path: impl Into<io::PathBuf>) -> Self {
+        self.trace_dump = Some(path.into());
+        self.runtime.trace_dump = true;
+        self
+    }
+
+    /// Records the version TLC reports in `build_path`, see [`dump::record_version`].
+    ///
+    /// Lets the next run know whether TLC supports [`dump::request`].
+    pub fn record_version(mut self, build_path: impl Into<io::PathBuf>) -> Self {
+        self.version_dir = Some(build_path.into());
+        self
+    }
+
+    /// Sets the `ALIAS` TLC runs with, see [`conf::ModuleConf::alias`].
+    ///
+    /// TLC then prints the output of the alias instead of the states of the trace: cexs are marked
+    /// as such, and never built from the trace dump which has the actual states.
+    pub fn alias(mut self, alias: Option<String>) -> Self {
+        self.alias = alias;
+        self
+    }
+
+    /// Keeps analyzing after a cex, see [`conf::TlcCla::keep_going`].
+    ///
+    /// Only makes sense if TLC runs with `-continue`, in which case every cex is reported.
+    pub fn keep_going(mut self, keep_going: bool) -> Self {
+        self.runtime.keep_going = keep_going;
+        self
+    }
+
+    /// Reports the cexs held back while waiting for the trace dump.
+    fn release_cexs(&mut self) {
+        let mut cexs = mem::take(&mut self.held_cexs);
+        if let Some(alias) = self.alias.as_ref() {
+            for cex in cexs {
+                self.out_handler.handle_cex(cex.alias(alias.clone()))
+            }
+            return;
+        }
+        if let (Some(path), 1) = (self.trace_dump.as_ref(), cexs.len()) {
+            match dump::load(path) {
+                Ok(Some(dumped)) => {
+                    let text = cexs.pop().expect("unreachable");
+                    cexs.push(dump::merge(dumped, text))
+                }
+                Ok(None) => log::debug!(
+                    "no trace dump at `{}`, using TLC's text output",
+                    path.display()
+                ),
+                Err(e) => log::warn!("{:?}\nusing TLC's text output for the cex", e),
+            }
+        }
+        for cex in cexs.iter() {
+            let unresolved = cex
+                .states
+                .index_iter()
+                .any(|(_, state)| state.values.values().any(|val| *val == cex::Value::Null));
+            if unresolved {
+                log::warn!(
+                    "failed to parse some values of the cex and to resolve them from the trace \
+                    dump, they show as `null`"
+                )
+            }
+        }
+        for cex in cexs {
+            self.out_handler.handle_cex(cex)
+        }
+    }
+
+    /// Records the version of TLC if `msg` reports it, see [`Self::record_version`].
+    fn record_version_of(&self, msg: &msg::Msg) {
+        use code::{Msg, Tlc, TlcMsg, TopMsg};
+        let build_path = match self.version_dir.as_ref() {
+            Some(build_path) => build_path,
+            None => return,
+        };
+        if !matches!(msg.code, Some(TopMsg::Msg(Msg::Tlc(TlcMsg::Msg(Tlc::TlcVersion))))) {
+            return;
+        }
+        let lines = msg.lines();
+        if let Some(version) = lines.iter().map(|line| line.trim()).find(|l| !l.is_empty()) {
+            if let Err(e) = dump::record_version(build_path, version) {
+                log::warn!("{:?}\nfailed to record TLC's version", e)
+            }
+        }
+    }
+
+    /// Records TLC's raw output to a file, see [`record`].
+    pub fn record(mut self, path: impl Into<io::PathBuf>) -> Res<Self> {
+        self.tlc.record(record::Recorder::new(path)?);
+        Ok(self)
+    }
+
     /// Activates line collection from TLC's output.
     pub fn collect_tlc_lines(mut self) -> Self {
         self.tlc_lines = Some(Vec::with_capacity(113));
@@ -146,13 +292,24 @@ impl<O: Out> TlcRun<O> {
             } else {
                 break 'doit;
             };
-            let maybe_done = try_break!(self.runtime.handle(&mut self.out_handler, &msg));
+            self.record_version_of(&msg);
+            let maybe_done = if self.trace_dump.is_some() || self.alias.is_some() {
+                let mut out = HoldCexs {
+                    out: &mut self.out_handler,
+                    cexs: &mut self.held_cexs,
+                };
+                try_break!(self.runtime.handle(&mut out, &msg))
+            } else {
+                try_break!(self.runtime.handle(&mut self.out_handler, &msg))
+            };
             if let Some(nu_outcome) = maybe_done {
+                self.release_cexs();
                 self.out_handler.handle_outcome(nu_outcome.clone());
                 outcome = Some(nu_outcome);
                 break 'doit;
             }
         }
+        self.release_cexs();
         let runtime = chrono::Utc::now() - start_time;
         {
             let error_count = self.runtime.tlc_error_fold(
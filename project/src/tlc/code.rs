@@ -179,6 +179,19 @@ macro_rules! code_enums {
                     Display { |&self, fmt| self.desc().fmt(fmt) }
                 }
             }
+            impl serde::Serialize for $enum_name {
+                /// Serializes as `{ "code": <code>, "desc": <description> }`.
+                fn serialize<S: serde::Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    use serde::ser::SerializeStruct;
+                    let mut code = serializer.serialize_struct(stringify!($enum_name), 2)?;
+                    code.serialize_field("code", &self.code().get())?;
+                    code.serialize_field("desc", self.desc())?;
+                    code.end()
+                }
+            }
 
             $(
                 impl $_enum_name {
@@ -486,9 +499,13 @@ code_enums! {
             ///   the final state is printed with:
             ///   - [`Self::TlcBackToState`] for liveness traces ending in a lasso;
             ///   - [`Self::TlcStuttering`] for liveness traces ending in stuttering.
+            ///
+            /// Values matla fails to parse are not in `state`, they are kept as is in `raw` so
+            /// that they can be resolved from the [trace dump](tlc::dump), if any.
             TlcTraceState = (2217, "[tlc cex] trace state") {
                 index: usize,
                 state: cex::State,
+                raw: Map<String, String>,
             } => |contents| {
                 let mut lines = contents.only_plain_str_slices();
                 let state_info = lines.next().ok_or_else(|| anyhow!("expected at least one line"))?
@@ -496,16 +513,44 @@ code_enums! {
                 let (index, state_info) = tlc::parse::state_info(state_info)
                     .with_context(|| anyhow!("failed to parse state info"))?;
                 let mut state = cex::State::new(state_info);
+                let mut raw: Map<String, String> = Map::new();
+                let mut last_raw: Option<String> = None;
                 for id_value in lines {
                     let id_value = id_value.with_context(|| anyhow!("expected ident/value cex line"))?;
-                    let (id, value) = tlc::parse::cex_ident_value(id_value)
-                        .with_context(|| anyhow!("failed to parse ident/value pair"))?;
+                    let (id, value) = match tlc::parse::cex_ident_value(id_value) {
+                        Ok(id_value) => id_value,
+                        Err(e) => match (tlc::parse::cex_ident_raw(id_value), last_raw.as_ref()) {
+                            (Ok((id, value)), _) => {
+                                if state.contains_key(id) || raw.contains_key(id) {
+                                    bail!("TLC produced a cex state that mentions `{}` twice", id)
+                                }
+                                let _prev = raw.insert(id.into(), value.into());
+                                last_raw = Some(id.into());
+                                continue;
+                            }
+                            // Value of the previous (unparsed) variable spanning several lines.
+                            (Err(_), Some(id)) => {
+                                let value = raw.get_mut(id).expect("unreachable");
+                                value.push('\n');
+                                value.push_str(id_value);
+                                continue;
+                            }
+                            (Err(_), None) => {
+                                return Err(e)
+                                    .with_context(|| anyhow!("failed to parse ident/value pair"))
+                            }
+                        },
+                    };
+                    last_raw = None;
+                    if raw.contains_key(id) {
+                        bail!("TLC produced a cex state that mentions `{}` twice", id)
+                    }
                     let _prev = state.insert(id.into(), value);
                     if let Some(_prev) = _prev {
                         bail!("TLC produced a cex state that mentions `{}` twice", id)
                     }
                 }
-                Ok(Self::TlcTraceState { index, state })
+                Ok(Self::TlcTraceState { index, state, raw })
             },
         }
     }
@@ -581,9 +626,26 @@ code_enums! {
                 tlc::parse::stats(line)
                     .with_context(|| anyhow!("failed to parse contents"))
             },
-            TlcStatsDfid = (2204, "[tlc msg] stats dfid"),
-            TlcStatsSimu = (2210, "[tlc msg] stats simu"),
+            TlcStatsDfid = (2204, "[tlc msg] stats dfid") {
+                generated: (Int, String),
+                distinct: (Int, String),
+            } => |contents| {
+                let line = contents.get_1_plain_str()?;
+                tlc::parse::stats_dfid(line)
+                    .with_context(|| anyhow!("failed to parse contents"))
+            },
+            TlcStatsSimu = (2210, "[tlc msg] stats simu") {
+                generated: (Int, String),
+            } => |contents| {
+                let line = contents
+                    .plain_str_slices()
+                    .next()
+                    .ok_or_else(|| anyhow!("expected at least one line"))?;
+                tlc::parse::stats_simu(line)
+                    .with_context(|| anyhow!("failed to parse contents"))
+            },
             TlcProgressStats = (2200, "[tlc msg] progress stats") {
+                depth: usize,
                 generated: (Int, String),
                 gen_spm: Option<(Int, String)>,
                 distinct: (Int, String),
@@ -597,18 +659,92 @@ code_enums! {
             TlcCoverageStart = (2201, "[tlc msg] coverage start"),
             TlcCoverageEnd = (2202, "[tlc msg] coverage end"),
             TlcCheckpointRecoverEndDfid = (2203, "[tlc msg] checkpoint recover end dfid"),
-            TlcProgressStartStatsDfid = (2205, "[tlc msg] progress start stats dfid"),
-            TlcProgressStatsDfid = (2206, "[tlc msg] progress stats dfid"),
-            TlcProgressSimu = (2209, "[tlc msg] progress simu"),
+            TlcProgressStartStatsDfid = (2205, "[tlc msg] progress start stats dfid") {
+                level: usize,
+                generated: (Int, String),
+                distinct: (Int, String),
+            } => |contents| {
+                let line = contents.get_1_plain_str()?;
+                tlc::parse::progress_start_stats_dfid(line)
+                    .with_context(|| anyhow!("failed to parse contents"))
+            },
+            TlcProgressStatsDfid = (2206, "[tlc msg] progress stats dfid") {
+                generated: (Int, String),
+                gen_spm: Option<(Int, String)>,
+                distinct: (Int, String),
+                dist_spm: Option<(Int, String)>,
+            } => |contents| {
+                let line = contents.get_1_plain_str()?;
+                tlc::parse::progress_stats_dfid(line)
+                    .with_context(|| anyhow!("failed to parse contents"))
+            },
+            TlcProgressSimu = (2209, "[tlc msg] progress simu") {
+                checked: (Int, String),
+                traces: Option<(Int, String)>,
+                mean_length: Option<String>,
+            } => |contents| {
+                let line = contents.get_1_plain_str()?;
+                tlc::parse::progress_simu(line)
+                    .with_context(|| anyhow!("failed to parse contents"))
+            },
             TlcFpCompleted = (2211, "[tlc msg] fp completed"),
 
             TlcCoverageMismatch = (2776, "[tlc msg] coverage mismatch"),
-            TlcCoverageValue = (2221, "[tlc msg] coverage value"),
-            TlcCoverageValueCost = (2775, "[tlc msg] coverage value cost"),
-            TlcCoverageNext = (2772, "[tlc msg] coverage next"),
-            TlcCoverageInit = (2773, "[tlc msg] coverage init"),
-            TlcCoverageProperty = (2774, "[tlc msg] coverage property"),
-            TlcCoverageConstraint = (2778, "[tlc msg] coverage constraint"),
+            TlcCoverageValue = (2221, "[tlc msg] coverage value") {
+                level: usize,
+                span: source::FileSpan,
+                count: (Int, String),
+            } => |contents| {
+                let line = contents.get_1_plain_str()?;
+                tlc::parse::coverage_value(line)
+                    .with_context(|| anyhow!("failed to parse contents"))
+            },
+            TlcCoverageValueCost = (2775, "[tlc msg] coverage value cost") {
+                level: usize,
+                span: source::FileSpan,
+                count: (Int, String),
+                cost: (Int, String),
+            } => |contents| {
+                let line = contents.get_1_plain_str()?;
+                tlc::parse::coverage_value_cost(line)
+                    .with_context(|| anyhow!("failed to parse contents"))
+            },
+            TlcCoverageNext = (2772, "[tlc msg] coverage next") {
+                name: String,
+                span: source::FileSpan,
+                distinct: (Int, String),
+                generated: (Int, String),
+            } => |contents| {
+                let line = contents.get_1_plain_str()?;
+                tlc::parse::coverage_next(line)
+                    .with_context(|| anyhow!("failed to parse contents"))
+            },
+            TlcCoverageInit = (2773, "[tlc msg] coverage init") {
+                name: String,
+                span: source::FileSpan,
+                distinct: (Int, String),
+                generated: (Int, String),
+            } => |contents| {
+                let line = contents.get_1_plain_str()?;
+                tlc::parse::coverage_init(line)
+                    .with_context(|| anyhow!("failed to parse contents"))
+            },
+            TlcCoverageProperty = (2774, "[tlc msg] coverage property") {
+                name: String,
+                span: source::FileSpan,
+            } => |contents| {
+                let line = contents.get_1_plain_str()?;
+                tlc::parse::coverage_property(line)
+                    .with_context(|| anyhow!("failed to parse contents"))
+            },
+            TlcCoverageConstraint = (2778, "[tlc msg] coverage constraint") {
+                name: String,
+                span: source::FileSpan,
+            } => |contents| {
+                let line = contents.get_1_plain_str()?;
+                tlc::parse::coverage_constraint(line)
+                    .with_context(|| anyhow!("failed to parse contents"))
+            },
             TlcCoverageEndOverhead = (2777, "[tlc msg] coverage end overhead"),
 
             TlcVersion = (2262, "[tlc msg] version"),
@@ -0,0 +1,407 @@
+//! Coverage statistics, produced by TLC when running with `-coverage`.
+//!
+//! TLC reports coverage periodically as a block of messages opened by
+//! [`code::Tlc::TlcCoverageStart`] and closed by [`code::Tlc::TlcCoverageEnd`]. A block lists the
+//! init and next actions with their distinct and generated state counts, then the properties and
+//! constraints. Each of these items is followed by the evaluation counts of its sub-expressions.
+//! [`Coverage`] aggregates these messages and only remembers the latest complete block.
+
+prelude!();
+
+use tlc::code;
+
+/// Kind of a coverage item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Init action.
+    Init,
+    /// Next action.
+    Next,
+    /// Invariant or temporal property.
+    Property,
+    /// State or action constraint.
+    Constraint,
+}
+impl Kind {
+    /// True for init and next actions.
+    pub fn is_action(self) -> bool {
+        matches!(self, Self::Init | Self::Next)
+    }
+}
+implem! {
+    for Kind {
+        Display {
+            |&self, fmt| write!(fmt, "{}", match self {
+                Self::Init => "init",
+                Self::Next => "next",
+                Self::Property => "property",
+                Self::Constraint => "constraint",
+            })
+        }
+    }
+}
+
+/// Evaluation count of a sub-expression of an [`Item`].
+#[derive(Debug, Clone)]
+pub struct Expr {
+    /// Nesting level, `0` for top-level sub-expressions.
+    pub level: usize,
+    /// Sub-expression's span.
+    pub span: source::FileSpan,
+    /// Number of evaluations.
+    pub count: (Int, String),
+    /// Evaluation cost, if reported.
+    pub cost: Option<(Int, String)>,
+}
+
+/// Coverage of an action, a property or a constraint.
+#[derive(Debug, Clone)]
+pub struct Item {
+    /// Item kind.
+    pub kind: Kind,
+    /// Item name.
+    pub name: String,
+    /// Item's span.
+    pub span: source::FileSpan,
+    /// Distinct and generated state counts, only for actions.
+    pub counts: Option<((Int, String), (Int, String))>,
+    /// Evaluation counts of the item's sub-expressions.
+    pub exprs: Vec<Expr>,
+}
+impl Item {
+    /// Constructor.
+    pub fn new(
+        kind: Kind,
+        name: impl Into<String>,
+        span: source::FileSpan,
+        counts: Option<((Int, String), (Int, String))>,
+    ) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+            span,
+            counts,
+            exprs: vec![],
+        }
+    }
+
+    /// Module the item is defined in.
+    pub fn module(&self) -> &str {
+        &self.span.file
+    }
+
+    /// True if the item is an action that never fired, *i.e.* never generated any state.
+    pub fn never_fired(&self) -> bool {
+        self.counts
+            .as_ref()
+            .map_or(false, |(_, (generated, _))| generated.is_zero())
+    }
+
+    /// True if `row` is in the item's span.
+    pub fn contains_row(&self, row: usize) -> bool {
+        self.span.pos.row <= row && row <= self.span.end.row
+    }
+}
+
+/// Coverage statistics of a run.
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    /// Items of the latest complete report.
+    items: Vec<Item>,
+    /// Items of the report being received, if any.
+    pending: Option<Vec<Item>>,
+    /// Number of complete reports received.
+    reports: usize,
+}
+impl Coverage {
+    /// Constructor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True if no complete report was received.
+    pub fn is_empty(&self) -> bool {
+        self.reports == 0
+    }
+    /// Number of complete reports received.
+    pub fn reports(&self) -> usize {
+        self.reports
+    }
+    /// Items of the latest complete report.
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    /// Integrates a TLC message.
+    ///
+    /// Returns `true` if the message is coverage-related.
+    ///
+    /// ```rust
+    /// # use project::tlc::{code::Tlc, coverage::Coverage, parse};
+    /// let msgs = [
+    ///     Tlc::TlcCoverageStart,
+    ///     parse::coverage_init("<Init line 9, col 1 to line 9, col 8 of module sw>: 1:1")
+    ///         .unwrap(),
+    ///     parse::coverage_value("  |line 9, col 9 to line 9, col 15 of module sw: 1").unwrap(),
+    ///     parse::coverage_next("<Inc line 11, col 1 to line 12, col 20 of module sw>: 10:11")
+    ///         .unwrap(),
+    ///     parse::coverage_next("<Reset line 14, col 1 to line 15, col 12 of module sw>: 0:0")
+    ///         .unwrap(),
+    ///     parse::coverage_next("<Tick line 3, col 1 to line 3, col 20 of module clock>: 5:5")
+    ///         .unwrap(),
+    ///     Tlc::TlcCoverageEnd,
+    /// ];
+    /// let mut coverage = Coverage::new();
+    /// for msg in msgs.iter() {
+    ///     assert!(coverage.handle(msg));
+    ///     // nothing is visible until the report is complete
+    ///     assert_eq!(coverage.is_empty(), !matches!(msg, Tlc::TlcCoverageEnd));
+    /// }
+    /// assert_eq!(coverage.reports(), 1);
+    ///
+    /// let never_fired: Vec<&str> = coverage
+    ///     .never_fired()
+    ///     .map(|item| item.name.as_str())
+    ///     .collect();
+    /// assert_eq!(never_fired, vec!["Reset"]);
+    ///
+    /// let by_module = coverage.by_module();
+    /// assert_eq!(by_module.len(), 2);
+    /// assert_eq!(by_module["clock"].len(), 1);
+    /// let sw: Vec<&str> = by_module["sw"].iter().map(|item| item.name.as_str()).collect();
+    /// assert_eq!(sw, vec!["Init", "Inc", "Reset"]);
+    /// assert_eq!(by_module["sw"][0].exprs.len(), 1);
+    /// ```
+    pub fn handle(&mut self, msg: &code::Tlc) -> bool {
+        use code::Tlc;
+        match msg {
+            Tlc::TlcCoverageStart => self.pending = Some(vec![]),
+            Tlc::TlcCoverageEnd | Tlc::TlcCoverageEndOverhead => {
+                if let Some(items) = self.pending.take() {
+                    self.items = items;
+                    self.reports += 1;
+                }
+            }
+            Tlc::TlcCoverageInit {
+                name,
+                span,
+                distinct,
+                generated,
+            } => self.push_item(Item::new(
+                Kind::Init,
+                name,
+                span.clone(),
+                Some((distinct.clone(), generated.clone())),
+            )),
+            Tlc::TlcCoverageNext {
+                name,
+                span,
+                distinct,
+                generated,
+            } => self.push_item(Item::new(
+                Kind::Next,
+                name,
+                span.clone(),
+                Some((distinct.clone(), generated.clone())),
+            )),
+            Tlc::TlcCoverageProperty { name, span } => {
+                self.push_item(Item::new(Kind::Property, name, span.clone(), None))
+            }
+            Tlc::TlcCoverageConstraint { name, span } => {
+                self.push_item(Item::new(Kind::Constraint, name, span.clone(), None))
+            }
+            Tlc::TlcCoverageValue { level, span, count } => self.push_expr(Expr {
+                level: *level,
+                span: span.clone(),
+                count: count.clone(),
+                cost: None,
+            }),
+            Tlc::TlcCoverageValueCost {
+                level,
+                span,
+                count,
+                cost,
+            } => self.push_expr(Expr {
+                level: *level,
+                span: span.clone(),
+                count: count.clone(),
+                cost: Some(cost.clone()),
+            }),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Pushes an item in the pending report.
+    fn push_item(&mut self, item: Item) {
+        self.pending.get_or_insert_with(Vec::new).push(item)
+    }
+    /// Pushes a sub-expression in the last item of the pending report.
+    fn push_expr(&mut self, expr: Expr) {
+        match self.pending.as_mut().and_then(|items| items.last_mut()) {
+            Some(item) => item.exprs.push(expr),
+            None => log::warn!(
+                "ignoring coverage value at {} outside of any item",
+                expr.span
+            ),
+        }
+    }
+
+    /// Items of the latest complete report grouped by module.
+    pub fn by_module(&self) -> Map<&str, Vec<&Item>> {
+        let mut res: Map<&str, Vec<&Item>> = Map::new();
+        for item in self.items.iter() {
+            res.entry(item.module()).or_default().push(item)
+        }
+        res
+    }
+
+    /// Actions of the latest complete report that never fired.
+    pub fn never_fired(&self) -> impl Iterator<Item = &Item> {
+        self.items.iter().filter(|item| item.never_fired())
+    }
+
+    /// Per-module summary of the latest complete report.
+    ///
+    /// Gives the number of actions of each module and how many of them never fired, followed by the
+    /// position of each action that never fired.
+    pub fn summary(&self, project: &FullProject, styles: &conf::Styles) -> Res<Vec<String>> {
+        let mut res = vec![];
+        for (module, items) in self.by_module() {
+            let actions = items.iter().filter(|item| item.kind.is_action()).count();
+            let never_fired: Vec<&Item> = items
+                .iter()
+                .cloned()
+                .filter(|item| item.never_fired())
+                .collect();
+            let never_fired_count = if never_fired.is_empty() {
+                "".into()
+            } else {
+                format!(
+                    ", {} {}",
+                    styles.bad.paint(never_fired.len().to_string()),
+                    styles.bad.paint("never fired"),
+                )
+            };
+            res.push(format!(
+                "module {}: {} action(s){}",
+                styles.bold.paint(module),
+                actions,
+                never_fired_count,
+            ));
+            for item in never_fired {
+                res.push(format!(
+                    "  {} action {} never fired",
+                    item.kind,
+                    styles.bad.paint(&item.name),
+                ));
+                let pretty = item.span.start.pretty(
+                    |module, buf| project.load_module(module, buf),
+                    Some("never fires"),
+                )?;
+                res.extend(pretty.into_iter().map(|line| format!("  {}", line)));
+            }
+        }
+        Ok(res)
+    }
+
+    /// Generates an HTML report of the latest complete report.
+    ///
+    /// The report annotates the source of each module mentioned in the coverage statistics with the
+    /// evaluation counts of the sub-expressions starting on each line. Lines of actions that never
+    /// fired and of sub-expressions never evaluated are highlighted.
+    pub fn html_report(&self, project: &FullProject) -> Res<String> {
+        let title = html_escape(format!("{} coverage", project.actual_entry));
+        let mut res = String::with_capacity(4096);
+        res.push_str(&format!(
+            "\
+<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; max-width: 80em; margin: auto; padding: 1em; }}
+code, pre, .row, .count {{ font-family: monospace; }}
+table {{ border-collapse: collapse; }}
+td {{ padding: 0 .5em; vertical-align: top; }}
+pre {{ margin: 0; }}
+.row, .count {{ color: gray; text-align: right; }}
+.item {{ color: gray; font-style: italic; }}
+.never {{ background-color: #fdd; }}
+.zero {{ background-color: #fed; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+",
+            title = title,
+        ));
+
+        let by_module = self.by_module();
+        res.push_str("<ul>\n");
+        for (module, items) in by_module.iter() {
+            let never_fired = items.iter().filter(|item| item.never_fired()).count();
+            res.push_str(&format!(
+                "<li><a href=\"#module-{0}\"><code>{0}</code></a>{1}</li>\n",
+                html_escape(module),
+                if never_fired > 0 {
+                    format!(", {} action(s) never fired", never_fired)
+                } else {
+                    "".into()
+                },
+            ));
+        }
+        res.push_str("</ul>\n");
+
+        for (module, items) in by_module {
+            let content = project.module_content(module)?;
+            res.push_str(&format!(
+                "<h2 id=\"module-{0}\">module <code>{0}</code></h2>\n<table>\n",
+                html_escape(module),
+            ));
+            for (idx, line) in content.lines().enumerate() {
+                let row = idx + 1;
+                let mut class = "";
+                let mut counts = vec![];
+                for item in items.iter() {
+                    if item.never_fired() && item.contains_row(row) {
+                        class = "never";
+                    }
+                    if item.span.pos.row == row {
+                        let desc = match item.counts.as_ref() {
+                            Some(((_, distinct), (_, generated))) => format!(
+                                "{} {}: {} distinct, {} generated",
+                                item.kind, item.name, distinct, generated,
+                            ),
+                            None => format!("{} {}", item.kind, item.name),
+                        };
+                        res.push_str(&format!(
+                            "<tr><td></td><td></td><td class=\"item\">{}</td></tr>\n",
+                            html_escape(desc),
+                        ));
+                    }
+                    for expr in item.exprs.iter().filter(|expr| expr.span.pos.row == row) {
+                        if expr.count.0.is_zero() && class.is_empty() {
+                            class = "zero";
+                        }
+                        counts.push(expr.count.1.as_str());
+                    }
+                }
+                counts.dedup();
+                res.push_str(&format!(
+                    "<tr class=\"{}\"><td class=\"row\">{}</td><td class=\"count\">{}</td>\
+                    <td><pre>{}</pre></td></tr>\n",
+                    class,
+                    row,
+                    counts.join(" "),
+                    html_escape(line),
+                ));
+            }
+            res.push_str("</table>\n");
+        }
+
+        res.push_str("</body>\n</html>\n");
+        Ok(res)
+    }
+}
@@ -0,0 +1,397 @@
+//! TLC's JSON trace dump, requested with `-dumpTrace json <file>`.
+//!
+//! Recent versions of TLC can dump the counterexample trace of a run as the JSON serialization of
+//! the `CounterExample` operator of the `TLCExt` module:
+//!
+//! ```text
+//! {
+//!     "state": [ [1, { "cnt": 0 }], [2, { "cnt": 1 }] ],
+//!     "action": [
+//!         [
+//!             [1, { "cnt": 0 }],
+//!             {
+//!                 "name": "inc",
+//!                 "location": {
+//!                     "module": "sw", "beginLine": 14, "beginColumn": 1,
+//!                     "endLine": 16, "endColumn": 10
+//!                 }
+//!             },
+//!             [2, { "cnt": 1 }]
+//!         ]
+//!     ]
+//! }
+//! ```
+//!
+//! Older versions of TLC reject `-dumpTrace json` and fail, so matla only [requests](request) the
+//! dump if the version of TLC [`supports`] it. Before the first run in a build directory, matla
+//! [probes](tlc::probe) TLC for its version. It then [records](record_version) it in the build
+//! directory, and [`TlcRun`](tlc::TlcRun) updates the record with the version TLC reports when it
+//! starts.
+//!
+//! States are 1-indexed. An action going back to an earlier state closes a lasso, and an action
+//! going back to its own source state means the trace ends by stuttering.
+//!
+//! JSON arrays become [`Tuple`](cex::value::Tuple)s, objects become [`SMap`](cex::value::SMap)s
+//! and strings become string constants. The dump is the main source of the cex: the
+//! [`TlcRun`](tlc::TlcRun) [merges](merge) it with the cex scraped from TLC's text output, which
+//! only fills in what the dump lacks. In particular, values matla fails to parse in the text
+//! output are resolved from the dump. When the dump is missing or cannot be parsed, the text cex is
+//! used as is.
+
+prelude!();
+
+use cex::value;
+use serde_json::Value as Json;
+
+/// Name of the trace dump file in the build directory.
+pub const FILE_NAME: &str = "trace.json";
+/// Name of the file recording the version of TLC, in the build directory.
+pub const VERSION_FILE_NAME: &str = "tlc_version";
+/// Oldest version of TLC supporting `-dumpTrace json`, as `(major, minor)`.
+pub const MIN_VERSION: (u32, u32) = (2, 16);
+
+/// True if a TLC version, as reported by TLC, is at least [`MIN_VERSION`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use project::tlc::dump::supports;
+/// assert!(supports("TLC2 Version 2.18 of Day Month 20??"));
+/// assert!(supports("TLC2 Version 2.16 of 31 December 2020 (rev: cdddf55)"));
+/// assert!(!supports("TLC2 Version 2.15 of Day Month 20??"));
+/// assert!(!supports("some unexpected version format"));
+/// ```
+pub fn supports(version: &str) -> bool {
+    let number = version
+        .split_whitespace()
+        .skip_while(|word| *word != "Version")
+        .nth(1)
+        .unwrap_or("");
+    let mut parts = number.split('.').map(str::parse::<u32>);
+    match (parts.next(), parts.next()) {
+        (Some(Ok(major)), Some(Ok(minor))) => (major, minor) >= MIN_VERSION,
+        _ => false,
+    }
+}
+
+/// Version of TLC recorded in `build_path`, if any.
+pub fn recorded_version(build_path: impl AsRef<io::Path>) -> Res<Option<String>> {
+    let path = build_path.as_ref().join(VERSION_FILE_NAME);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let version = io::load_file(&path)?;
+    Ok(Some(version.trim().to_string()))
+}
+
+/// Records the version of TLC running in `build_path`.
+pub fn record_version(build_path: impl AsRef<io::Path>, version: &str) -> Res<()> {
+    use io::Write;
+    let path = build_path.as_ref().join(VERSION_FILE_NAME);
+    let mut file = io::write_file(&path, true, false)?;
+    writeln!(file, "{}", version.trim())
+        .with_context(|| anyhow!("failed to write to `{}`", path.display()))
+}
+
+/// Adds the arguments requesting a trace dump to a TLC command running in `build_path`.
+///
+/// Only requests the dump if the version of TLC [`supports`] it, since older versions reject
+/// `-dumpTrace json`. The version is the [recorded](recorded_version) one if any, otherwise matla
+/// [probes](tlc::probe) TLC and records its version. Removes the dump of a previous run, if any,
+/// and returns the path to the dump if requested.
+pub fn request(
+    cmd: &mut io::Command,
+    build_path: impl AsRef<io::Path>,
+) -> Res<Option<io::PathBuf>> {
+    let build_path = build_path.as_ref();
+    let path = build_path.join(FILE_NAME);
+    if path.exists() {
+        io::remove_file(&path)
+            .with_context(|| anyhow!("failed to remove old trace dump `{}`", path.display()))?;
+    }
+    let version = match recorded_version(build_path)? {
+        Some(version) => Some(version),
+        None => probe_version(build_path),
+    };
+    match version {
+        Some(version) if supports(&version) => (),
+        version => {
+            log::debug!(
+                "not requesting a trace dump, TLC version is {}",
+                version.as_deref().unwrap_or("unknown"),
+            );
+            return Ok(None);
+        }
+    }
+    // TLC runs in the build directory.
+    cmd.args(["-dumpTrace", "json", FILE_NAME]);
+    Ok(Some(path))
+}
+
+/// Probes TLC for its version and records it in `build_path`, `None` if the probe fails.
+fn probe_version(build_path: &io::Path) -> Option<String> {
+    let version = match tlc::probe::run_current().and_then(tlc::probe::Probe::into_version) {
+        Ok(version) => version,
+        Err(e) => {
+            log::warn!("{:?}\nfailed to retrieve TLC's version", e);
+            return None;
+        }
+    };
+    if let Err(e) = record_version(build_path, &version) {
+        log::warn!("{:?}\nfailed to record TLC's version", e)
+    }
+    Some(version)
+}
+
+/// Loads a trace dump, `None` if the file does not exist.
+pub fn load(path: impl AsRef<io::Path>) -> Res<Option<cex::Cex>> {
+    let path = path.as_ref();
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = io::load_file(path)?;
+    parse(&content)
+        .with_context(|| anyhow!("failed to parse trace dump `{}`", path.display()))
+        .map(Some)
+}
+
+/// Parses a trace dump.
+///
+/// # Examples
+///
+/// ```rust
+/// # use project::tlc::dump::parse;
+/// let json = r#"{
+///     "state": [ [1, { "cnt": 0 }], [2, { "cnt": 1 }], [3, { "cnt": 2 }] ],
+///     "action": [
+///         [ [1, { "cnt": 0 }], { "name": "inc", "location": {
+///             "module": "sw", "beginLine": 14, "beginColumn": 1, "endLine": 16, "endColumn": 10
+///         } }, [2, { "cnt": 1 }] ],
+///         [ [2, { "cnt": 1 }], { "name": "inc", "location": {
+///             "module": "sw", "beginLine": 14, "beginColumn": 1, "endLine": 16, "endColumn": 10
+///         } }, [3, { "cnt": 2 }] ],
+///         [ [3, { "cnt": 2 }], { "name": "reset", "location": {
+///             "module": "sw", "beginLine": 18, "beginColumn": 1, "endLine": 20, "endColumn": 14
+///         } }, [2, { "cnt": 1 }] ]
+///     ]
+/// }"#;
+/// let cex = parse(json).unwrap();
+/// assert_eq!(cex.states.len(), 3);
+/// assert!(cex.states[cex.idx_of(0).unwrap()].info.is_none());
+/// let info = cex.states[cex.idx_of(2).unwrap()].info.as_ref().unwrap();
+/// assert_eq!(info.action, "inc");
+/// assert_eq!(info.module, "sw");
+/// assert!(info.span.0.is(14, 1));
+/// assert!(info.span.1.is(16, 10));
+/// assert_eq!(cex.shape, cex::Shape::Loop(cex.idx_of(1).unwrap()));
+/// ```
+pub fn parse(json: &str) -> Res<cex::Cex> {
+    let json: Json = serde_json::from_str(json).context("illegal JSON")?;
+
+    let mut states = json
+        .get("state")
+        .and_then(Json::as_array)
+        .ok_or_else(|| anyhow!("expected a `state` array"))?
+        .iter()
+        .map(indexed_state)
+        .collect::<Res<Vec<_>>>()?;
+    states.sort_by_key(|(index, _)| *index);
+
+    let mut infos: Map<usize, cex::StateInfo> = Map::new();
+    let mut back_to = None;
+    let actions = json.get("action").and_then(Json::as_array);
+    for action in actions.into_iter().flatten() {
+        let (src, action, tgt) = match action.as_array().map(Vec::as_slice) {
+            Some([src, act, tgt]) => (indexed_state(src)?.0, act, indexed_state(tgt)?.0),
+            _ => bail!("expected actions to be `[source, action, target]` triples"),
+        };
+        if tgt == src + 1 {
+            if let Some(info) = state_info(action)? {
+                let _prev = infos.insert(tgt, info);
+            }
+        } else if tgt <= src {
+            back_to = Some((src, tgt));
+        }
+    }
+
+    let mut cex = cex::Cex::new();
+    for (cnt, (index, values)) in states.into_iter().enumerate() {
+        if index != cnt + 1 {
+            bail!("expected state #{}, got #{}", cnt + 1, index)
+        }
+        let mut state = cex::State::new(infos.remove(&index));
+        for (var, val) in values {
+            let val =
+                value(val).with_context(|| anyhow!("in state #{}, variable `{}`", index, var))?;
+            let _prev = state.insert(var.clone(), val);
+        }
+        let _idx = cex.push(state);
+    }
+
+    match back_to {
+        None => Ok(cex),
+        Some((src, tgt)) if src == tgt => Ok(cex.shape(cex::Shape::Stuttering)),
+        Some((_, tgt)) => {
+            let idx = cex.idx_of(tgt - 1)?;
+            Ok(cex.shape(cex::Shape::Loop(idx)))
+        }
+    }
+}
+
+/// Merges the cex of a trace dump with the one scraped from TLC's text output.
+///
+/// The dump is the main source: it provides the states, their [`cex::StateInfo`], their values
+/// and the shape of the trace. The text cex only provides what the dump lacks: the property
+/// falsified, and the values of variables the dump does not mention or leaves `null`. If the two
+/// cexs do not have the same number of states, the dump is used as is.
+///
+/// # Examples
+///
+/// ```rust
+/// # use project::tlc::{dump, parse};
+/// let json = r#"{
+///     "state": [ [1, { "cnt": 0, "f": [1, 2] }], [2, { "cnt": 1, "f": [2, 1] }] ],
+///     "action": [
+///         [ [1, { "cnt": 0, "f": [1, 2] }], { "name": "swap", "location": {
+///             "module": "sw", "beginLine": 9, "beginColumn": 1, "endLine": 10, "endColumn": 20
+///         } }, [2, { "cnt": 1, "f": [2, 1] }] ],
+///         [ [2, { "cnt": 1, "f": [2, 1] }], { "name": "swap", "location": {
+///             "module": "sw", "beginLine": 9, "beginColumn": 1, "endLine": 10, "endColumn": 20
+///         } }, [2, { "cnt": 1, "f": [2, 1] }] ]
+///     ]
+/// }"#;
+/// let dumped = dump::parse(json).unwrap();
+///
+/// // matla failed to parse the values of `f` in TLC's text output, they are `null`
+/// let mut text = cex::Cex::new();
+/// for (cnt, pending) in [("0", "{}"), ("1", "{p1}")] {
+///     let mut state = cex::State::new(None);
+///     let _prev = state.insert("cnt".into(), parse::cex_value(cnt).unwrap());
+///     let _prev = state.insert("f".into(), cex::Value::Null);
+///     let _prev = state.insert("pending".into(), parse::cex_value(pending).unwrap());
+///     let _idx = text.push(state);
+/// }
+/// let text = text.set_falsified("Termination").unwrap();
+///
+/// let cex = dump::merge(dumped, text);
+/// assert_eq!(cex.falsified.as_deref(), Some("Termination"));
+/// assert_eq!(cex.shape, cex::Shape::Stuttering);
+/// let (first, last) = (cex.idx_of(0).unwrap(), cex.idx_of(1).unwrap());
+/// assert_eq!(cex.states[first]["f"].tla().to_string(), "<<1, 2>>");
+/// assert_eq!(cex.states[last]["f"].tla().to_string(), "<<2, 1>>");
+/// assert_eq!(cex.states[last]["cnt"].tla().to_string(), "1");
+/// // not in the dump
+/// assert_eq!(cex.states[last]["pending"].tla().to_string(), "{p1}");
+/// assert_eq!(cex.states[last].info.as_ref().unwrap().action, "swap");
+/// ```
+pub fn merge(mut dumped: cex::Cex, text: cex::Cex) -> cex::Cex {
+    if dumped.falsified.is_none() {
+        dumped.falsified = text.falsified;
+    }
+    if dumped.states.len() != text.states.len() {
+        log::warn!(
+            "trace dump has {} state(s) but TLC's text output has {}, ignoring the latter",
+            dumped.states.len(),
+            text.states.len(),
+        );
+        return dumped;
+    }
+    for (cnt, (_, text_state)) in text.states.index_iter().enumerate() {
+        let idx = dumped.idx_of(cnt).expect("same number of states");
+        let state = &mut dumped.states[idx];
+        if state.info.is_none() {
+            state.info = text_state.info.clone();
+        }
+        for (var, val) in text_state.values.iter() {
+            match state.values.get(var) {
+                Some(cex::Value::Plain(_)) => (),
+                None | Some(cex::Value::Null) => {
+                    let _prev = state.values.insert(var.clone(), val.clone());
+                }
+            }
+        }
+    }
+    dumped
+}
+
+/// Parses a `[index, { <var>: <value>, ... }]` state.
+fn indexed_state(json: &Json) -> Res<(usize, &serde_json::Map<String, Json>)> {
+    match json.as_array().map(Vec::as_slice) {
+        Some([index, values]) => {
+            let index = index
+                .as_u64()
+                .filter(|index| *index > 0)
+                .ok_or_else(|| anyhow!("expected positive state index, got `{}`", index))?;
+            let values = values
+                .as_object()
+                .ok_or_else(|| anyhow!("expected state #{} to be an object", index))?;
+            Ok((index as usize, values))
+        }
+        _ => bail!("expected states to be `[index, values]` pairs"),
+    }
+}
+
+/// Parses the state info of an action, `None` if it has no name or location.
+fn state_info(json: &Json) -> Res<Option<cex::StateInfo>> {
+    let name = json.get("name").and_then(Json::as_str);
+    let location = json.get("location");
+    let (name, location) = match (name, location) {
+        (Some(name), Some(location)) => (name, location),
+        _ => return Ok(None),
+    };
+    let module = location
+        .get("module")
+        .and_then(Json::as_str)
+        .ok_or_else(|| anyhow!("expected a module in the location of action `{}`", name))?;
+    let get = |key: &str| {
+        location
+            .get(key)
+            .and_then(Json::as_u64)
+            .map(|n| n as usize)
+            .ok_or_else(|| anyhow!("expected a `{}` in the location of action `{}`", key, name))
+    };
+    let span = (
+        source::Pos::new(get("beginLine")?, get("beginColumn")?),
+        source::Pos::new(get("endLine")?, get("endColumn")?),
+    );
+    Ok(Some(cex::StateInfo::new(name, span, module)))
+}
+
+/// Converts a JSON value to a cex value.
+fn value(json: &Json) -> Res<cex::Value> {
+    if json.is_null() {
+        Ok(cex::Value::Null)
+    } else {
+        plain_value(json).map(Into::into)
+    }
+}
+
+/// Converts a JSON value to a plain cex value.
+fn plain_value(json: &Json) -> Res<value::Plain> {
+    let res: value::Plain = match json {
+        Json::Bool(b) => (*b).into(),
+        Json::Number(n) => {
+            let i = if let Some(i) = n.as_i64() {
+                Int::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Int::from(u)
+            } else {
+                bail!("unexpected non-integer number `{}`", n)
+            };
+            i.into()
+        }
+        Json::String(s) => s.clone().into(),
+        Json::Array(elms) => {
+            value::Tuple::new(elms.iter().map(plain_value).collect::<Res<Vec<_>>>()?).into()
+        }
+        Json::Object(fields) => {
+            let mut smap = value::SMap::new_empty();
+            for (key, val) in fields {
+                let _prev = smap.insert(key.clone(), plain_value(val)?);
+            }
+            smap.into()
+        }
+        Json::Null => bail!("unexpected `null` value in a collection"),
+    };
+    Ok(res)
+}
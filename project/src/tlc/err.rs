@@ -2,7 +2,8 @@
 
 prelude!();
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
 pub enum TlcError {
     NoJavaRuntime,
     Parse(ParseError),
@@ -248,7 +249,8 @@ impl Exception {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Exc {
     Abort,
     NullPointer,
@@ -276,7 +278,7 @@ implem! {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct TlcErr {
     pub module: Option<String>,
     pub pos: Option<source::Pos>,
@@ -352,7 +354,7 @@ implem! {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct SemanticError {
     pub module: ModuleOrTop,
     pub err: Option<tlc::code::Err>,
@@ -517,7 +519,7 @@ implem! {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct LexicalError {
     pub module: ModuleOrTop,
     pub encountered: (String, source::Pos),
@@ -647,7 +649,7 @@ impl LexicalError {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct ParseError {
     pub module: ModuleOrTop,
     pub expected: String,
@@ -752,7 +754,8 @@ implem! {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
 pub enum RunErrorKind {
     AssertFailed {
         /// Failure message.
@@ -796,7 +799,7 @@ implem! {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct RunError {
     pub error: RunErrorKind,
     pub behavior: Option<cex::Cex>,
@@ -403,9 +403,28 @@ implem! {
     }
 }
 
+/// Where a [`TlcHandler`] reads TLC's output lines from.
+enum Source {
+    /// An actual TLC process.
+    Process {
+        /// Child channel.
+        com: ChildCmdCom,
+        /// Join handle for the child process.
+        handle: thread::JoinHandle<Res<io::ExitStatus>>,
+    },
+    /// A recording, see [`tlc::record`].
+    Replay {
+        /// Lines left to replay.
+        lines: std::vec::IntoIter<tlc::record::Line>,
+        /// Exit code of the recorded run, if any.
+        exit: Option<i32>,
+    },
+}
+
 /// A TLC communication channel.
 ///
-/// Stores a two-way communication channel [`ChildCmdCom`] with the actual TLC process.
+/// Stores a two-way communication channel [`ChildCmdCom`] with the actual TLC process, or the
+/// [`tlc::record::Recording`] of a previous run.
 ///
 /// Distinguishes between `stdout` and `stderr` messages by storing a separate message stack for
 /// each. Message stacks are needed because TLC messages can be nested.
@@ -413,16 +432,16 @@ implem! {
 /// Also stores a `handle` on the child process so that it can kill it, and a list of all the errors
 /// that happened during the run.
 pub struct TlcHandler {
-    /// Child channel.
-    com: ChildCmdCom,
+    /// Source of the lines.
+    source: Source,
     /// Message from `stdout` under construction.
     stdout_msg: Vec<(Code, tlc::msg::Elms)>,
     /// Message from `stderr` under construction.
     stderr_msg: Vec<(Code, tlc::msg::Elms)>,
-    /// Join handle for the child process.
-    handle: thread::JoinHandle<Res<io::ExitStatus>>,
     /// Errors that happened during the run.
     errors: Vec<tlc::code::Err>,
+    /// Records the lines read, if any.
+    recorder: Option<tlc::record::Recorder>,
 }
 impl TlcHandler {
     /// Constructor.
@@ -431,22 +450,65 @@ impl TlcHandler {
     pub fn new(cmd: io::Command) -> Self {
         let (child, com) = ChildCmd::new(cmd);
         let handle = child.spawn();
+        Self::of_source(Source::Process { com, handle })
+    }
+
+    /// Constructor replaying a recording, does not run anything.
+    pub fn replay(recording: tlc::record::Recording) -> Self {
+        Self::of_source(Source::Replay {
+            lines: recording.lines.into_iter(),
+            exit: recording.exit,
+        })
+    }
+
+    /// Constructor from a source.
+    fn of_source(source: Source) -> Self {
         Self {
-            com,
+            source,
             stdout_msg: vec![],
             stderr_msg: vec![],
-            handle,
             errors: Vec::new(),
+            recorder: None,
+        }
+    }
+
+    /// Records the lines read from now on, and the exit code.
+    pub fn record(&mut self, recorder: tlc::record::Recorder) {
+        self.recorder = Some(recorder)
+    }
+
+    /// Retrieves the next line, `bool` flag is true for lines from `stderr`.
+    fn next_line(&mut self) -> Option<Res<(String, bool)>> {
+        let next = match &mut self.source {
+            Source::Process { com, .. } => com.next(),
+            Source::Replay { lines, .. } => {
+                lines.next().map(|line| Ok((line.line, line.from_stderr)))
+            }
+        };
+        if let (Some(recorder), Some(Ok((line, from_stderr)))) = (self.recorder.as_mut(), &next) {
+            if let Err(e) = recorder.line(line, *from_stderr) {
+                return Some(Err(e));
+            }
         }
+        next
     }
 
     /// Destroys the process regarless of its state.
-    pub fn destroy(self) -> Res<tlc::ProcessOutcome> {
-        self.com.destroy();
-        self.handle
-            .join()
-            .map_err(|err| anyhow!("TLC-process panic: {:?}", err))?
-            .and_then(|out| tlc::ProcessOutcome::new(out.code().unwrap_or(-1)))
+    pub fn destroy(mut self) -> Res<tlc::ProcessOutcome> {
+        let code = match self.source {
+            Source::Process { com, handle } => {
+                com.destroy();
+                handle
+                    .join()
+                    .map_err(|err| anyhow!("TLC-process panic: {:?}", err))?
+                    .map(|out| out.code().unwrap_or(-1))?
+            }
+            Source::Replay { exit, .. } => exit.unwrap_or(-1),
+        };
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.exit(code)?;
+        }
+        tlc::ProcessOutcome::new(code)
     }
 
     /// Joins with the underlying child process.
@@ -464,25 +526,30 @@ impl TlcHandler {
                 }
             );
         }
-        self.handle
-            .join()
-            .map_err(|_| anyhow!("child process panicked"))
-            .and_then(|res| match res {
-                Ok(exit_status) => {
-                    let code = exit_status
-                        .code()
-                        .ok_or_else(|| anyhow!("exit code not available from child exit status"))?;
-                    Ok(tlc::ProcessOutcome::new(code)?)
-                }
-                Err(e) => Err(e),
-            })
+        let code = match self.source {
+            Source::Process { handle, .. } => {
+                let exit_status = handle
+                    .join()
+                    .map_err(|_| anyhow!("child process panicked"))??;
+                exit_status
+                    .code()
+                    .ok_or_else(|| anyhow!("exit code not available from child exit status"))?
+            }
+            Source::Replay { exit, .. } => exit.ok_or_else(|| {
+                anyhow!("exit code not available, TLC did not exit during the recorded run")
+            })?,
+        };
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.exit(code)?;
+        }
+        tlc::ProcessOutcome::new(code)
     }
 
     /// Returns the next message, if any.
     pub fn next(&mut self) -> Res<Option<Msg>> {
         macro_rules! next {
             { ($line:pat, $from_stderr:pat) => $($action:tt)* } => {
-                match self.com.next() {
+                match self.next_line() {
                     None => return Ok(None),
                     Some(Err(e)) => return Err(e),
                     Some(Ok(($line, $from_stderr))) => {
@@ -29,7 +29,8 @@ prelude!();
 /// A failed outcome of a TLC run.
 ///
 /// Used by [`ModeOutcomeKind`] and [`RunOutcome`].
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FailedOutcome {
     /// A parse error with a description.
     ParseError,
@@ -178,7 +179,8 @@ implem! {
 }
 
 /// Outcome of a TLC run.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum RunOutcome {
     /// Success.
     Success,
@@ -46,15 +46,22 @@ peg::parser! {
         } / expected!("`usize` value")
 
 
-        /// Parses an integer.
+        /// Parses an integer, possibly negative.
         ///
-        /// TODO: test and fix.
+        /// # Examples
+        ///
+        /// ```rust
+        /// # use project::tlc::parse::int;
+        /// assert_eq!(int("42").unwrap(), 42.into());
+        /// assert_eq!(int("-7").unwrap(), (-7).into());
+        /// assert!(int("- 7").is_err());
+        /// ```
         pub rule int() -> Int
         = quiet! {
-            num:$(num_char()+) {?
+            num:$("-"? num_char()+) {?
                 Int::parse_bytes(num.as_bytes(), 10).ok_or_else(|| "illegal integer")
             }
-        } / expected!("`usize` value")
+        } / expected!("integer")
 
         /// Parses an integer with `,` delimiters (`10³` separators).
         ///
@@ -112,6 +119,33 @@ peg::parser! {
             "\"" content:$(("\\\"" / "\\\\" / [^'"'])*) "\"" { content }
         } / expected!("double-quoted string")
 
+        /// Parses a double-quoted string and unescapes it.
+        ///
+        /// Handles the escape sequences TLC produces: `\"`, `\\`, `\n`, `\t`, `\r` and `\f`. Other
+        /// backslashes are kept as is.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// # use project::tlc::parse::dq_string_unescaped;
+        /// let input = r#""say \"hi\"\n\\in""#;
+        /// assert_eq!(dq_string_unescaped(input).unwrap(), "say \"hi\"\n\\in");
+        /// ```
+        pub rule dq_string_unescaped() -> String
+        = quiet! {
+            "\"" chars:(
+                "\\\"" { '"' }
+                / "\\\\" { '\\' }
+                / "\\n" { '\n' }
+                / "\\t" { '\t' }
+                / "\\r" { '\r' }
+                / "\\f" { '\x0c' }
+                / c:[^'"'] { c }
+            )* "\"" {
+                chars.into_iter().collect()
+            }
+        } / expected!("double-quoted string")
+
         /// A legal unix file/dir name.
         ///
         /// Spaces are expected to be escaped.
@@ -621,7 +655,7 @@ peg::parser! {
 
         /// Progress statistics.
         pub rule progress_stats() -> tlc::code::Tlc
-        = "Progress" _ "(" _ what_is_this:pretty_usize() _ ")" _ "at" _ date:date() _ ":"
+        = "Progress" _ "(" _ depth:pretty_usize() _ ")" _ "at" _ date:date() _ ":"
         _ generated:pretty_int_string() _ "states" _ "generated"
         _ gen_spm:(
             "(" _ gen_spm:pretty_int_string() _ "s" _ "/" _ "min" _ ")" _ { gen_spm }
@@ -632,6 +666,7 @@ peg::parser! {
         )? ","
         _ left:pretty_int_string() _ "states" _ "left" _ "on" _ "queue" _ "." {
             tlc::code::Tlc::TlcProgressStats {
+                depth,
                 generated,
                 gen_spm,
                 distinct,
@@ -640,15 +675,165 @@ peg::parser! {
             }
         }
 
-        /// A int / bool / string constant in a counterexample.
+        /// Final DFID statistics.
+        pub rule stats_dfid() -> tlc::code::Tlc
+        = generated:pretty_int_string() _ "state" ("s")? _ "generated" _ ","
+        _ distinct:pretty_int_string() _ "distinct" _ "state" ("s")? _ "found" _ "."? {
+            tlc::code::Tlc::TlcStatsDfid { generated, distinct }
+        }
+
+        /// DFID statistics when starting a new level.
+        pub rule progress_start_stats_dfid() -> tlc::code::Tlc
+        = "Starting" _ "level" _ level:pretty_usize() _ ":"
+        _ generated:pretty_int_string() _ "state" ("s")? _ "generated" _ ","
+        _ distinct:pretty_int_string() _ "distinct" _ "state" ("s")? _ "found" _ "."? {
+            tlc::code::Tlc::TlcProgressStartStatsDfid { level, generated, distinct }
+        }
+
+        /// DFID progress statistics.
+        pub rule progress_stats_dfid() -> tlc::code::Tlc
+        = "Progress" _ ("(" _ pretty_usize() _ ")" _)? ("at" _ date() _)? ":"
+        _ generated:pretty_int_string() _ "states" _ "generated"
+        _ gen_spm:(
+            "(" _ gen_spm:pretty_int_string() _ "s" _ "/" _ "min" _ ")" _ { gen_spm }
+        )? ","
+        _ distinct:pretty_int_string() _ "distinct" _ "states" _ "found"
+        _ dist_spm:(
+            "(" _ dist_spm:pretty_int_string() _ "ds" _ "/" _ "min" _ ")" _ { dist_spm }
+        )? "."? {
+            tlc::code::Tlc::TlcProgressStatsDfid {
+                generated,
+                gen_spm,
+                distinct,
+                dist_spm,
+            }
+        }
+
+        /// Simulation progress statistics.
+        ///
+        /// Older TLC versions only report the number of states checked.
+        pub rule progress_simu() -> tlc::code::Tlc
+        = "Progress" _ ("(" _ pretty_usize() _ ")" _)? ("at" _ date() _)? ":"
+        _ checked:pretty_int_string() _ "state" ("s")? _ "checked"
+        _ traces:(
+            "," _ traces:pretty_int_string() _ "trace" ("s")? _ "generated" _ { traces }
+        )?
+        mean_length:(
+            "(" _ "trace" _ "length" _ ":" _ "mean" _ "=" _ mean:$([^',' | ')']+) [_]* {
+                mean.trim().to_string()
+            }
+        )? "."? {
+            tlc::code::Tlc::TlcProgressSimu {
+                checked,
+                traces,
+                mean_length,
+            }
+        }
+
+        /// Final simulation statistics, ignores the seed information that follows.
+        pub rule stats_simu() -> tlc::code::Tlc
+        = "The" _ "number" _ "of" _ "states" _ "generated" _ ":"
+        _ generated:pretty_int_string() [_]* {
+            tlc::code::Tlc::TlcStatsSimu { generated }
+        }
+
+        /// A span in a file for some module, as written in coverage statistics.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// # use project::tlc::parse::module_span;
+        /// let span = module_span("line 3, col 1 to line 4, col 12 of module sw").unwrap();
+        /// assert_eq!(span.file, "sw");
+        /// assert!(span.pos.is(3, 1));
+        /// assert!(span.end.is(4, 12));
+        /// ```
+        pub rule module_span() -> source::FileSpan
+        = start:file_pos() _ "to" _ end:file_pos() _ "of" _ "module" _ module:ident() {
+            source::FilePos::new(module, start)
+                .into_span(end)
+        }
+
+        /// A coverage item: `<name span>`.
+        rule coverage_item() -> (String, source::FileSpan)
+        = "<" _ name:$([^ ' ' | '>']+) _ span:module_span() _ ">" {
+            (name.into(), span)
+        }
+
+        /// Distinct and generated state counts of a coverage action: `: distinct:generated`.
+        rule coverage_counts() -> ((Int, String), (Int, String))
+        = ":" _ distinct:pretty_int_string() _ ":" _ generated:pretty_int_string() {
+            (distinct, generated)
+        }
+
+        /// Coverage of an init action.
+        pub rule coverage_init() -> tlc::code::Tlc
+        = item:coverage_item() _ counts:coverage_counts() {
+            let ((name, span), (distinct, generated)) = (item, counts);
+            tlc::code::Tlc::TlcCoverageInit { name, span, distinct, generated }
+        }
+
+        /// Coverage of a next action.
+        pub rule coverage_next() -> tlc::code::Tlc
+        = item:coverage_item() _ counts:coverage_counts() {
+            let ((name, span), (distinct, generated)) = (item, counts);
+            tlc::code::Tlc::TlcCoverageNext { name, span, distinct, generated }
+        }
+
+        /// Coverage of a property.
+        pub rule coverage_property() -> tlc::code::Tlc
+        = item:coverage_item() [_]* {
+            let (name, span) = item;
+            tlc::code::Tlc::TlcCoverageProperty { name, span }
+        }
+
+        /// Coverage of a constraint.
+        pub rule coverage_constraint() -> tlc::code::Tlc
+        = item:coverage_item() [_]* {
+            let (name, span) = item;
+            tlc::code::Tlc::TlcCoverageConstraint { name, span }
+        }
+
+        /// Nesting level of a coverage value, the number of `|` before its span.
+        rule coverage_level() -> usize
+        = _ bars:$("|"*) { bars.len() }
+
+        /// Evaluation count of a sub-expression.
+        pub rule coverage_value() -> tlc::code::Tlc
+        = level:coverage_level() span:module_span() _ ":" _ count:pretty_int_string() _ {
+            tlc::code::Tlc::TlcCoverageValue { level, span, count }
+        }
+
+        /// Evaluation count and cost of a sub-expression.
+        pub rule coverage_value_cost() -> tlc::code::Tlc
+        = level:coverage_level() span:module_span() _ ":" _ count:pretty_int_string()
+        _ ":" _ cost:pretty_int_string() _ {
+            tlc::code::Tlc::TlcCoverageValueCost { level, span, count, cost }
+        }
+
+        /// A character that can appear in an identifier after the first one.
+        rule ident_char() = ['_' | '-' | 'a'..='z' | 'A'..='Z' | '0'..='9']
+
+        /// A int / bool / string / model value constant in a counterexample.
+        ///
+        /// Identifiers other than `TRUE` and `FALSE` are model values.
         pub rule cex_value_cst() -> cex::value::Cst = quiet! {
-            i:pretty_int() { i.into() }
+            neg:"-"? i:pretty_int() {
+                if neg.is_some() { (-i).into() } else { i.into() }
+            }
             / b:(
-                "TRUE" { true }
-                / "FALSE" { false }
+                "TRUE" !ident_char() { true }
+                / "FALSE" !ident_char() { false }
             ) { b.into() }
-            / s:dq_string() { s.into() }
-        } / expected!("boolean, integer, double-quoted string")
+            / s:dq_string_unescaped() { s.into() }
+            / m:ident() { cex::value::Cst::model(m) }
+        } / expected!("boolean, integer, double-quoted string, model value")
+
+        /// An integer interval in a counterexample: `<int>..<int>`.
+        pub rule cex_value_interval() -> cex::value::Interval
+        = lo:int() _ ".." _ hi:int() {
+            cex::value::Interval::new(lo, hi)
+        }
 
         /// A tuple value in a counterexample.
         pub rule cex_value_tuple() -> cex::value::Tuple
@@ -701,12 +886,25 @@ peg::parser! {
             content.unwrap_or_else(cex::value::SMap::new_empty)
         }
 
+        /// The count of an element of a bag, a positive integer.
+        rule cex_bag_count() -> Int
+        = count:int() {?
+            if count > Int::zero() {
+                Ok(count)
+            } else {
+                Err("positive integer")
+            }
+        }
+
         /// A bag value in a counterexample.
+        ///
+        /// TLC prints bags as functions to positive integers, [`cex_plain_value`] only parses
+        /// empty bags as bags and other ones as [functions](cex_value_fun).
         pub rule cex_value_bag() -> cex::value::Bag
         = "(" _  content:(
-            head_value:cex_plain_value() _ ":>" _ head_count:int()
+            head_value:cex_plain_value() _ ":>" _ head_count:cex_bag_count()
             tail:(
-                _ "@@" _ value:cex_plain_value() _ ":>" _ count:int() {
+                _ "@@" _ value:cex_plain_value() _ ":>" _ count:cex_bag_count() {
                     (value, count)
                 }
             )* {
@@ -720,18 +918,49 @@ peg::parser! {
             content.unwrap_or_else(cex::value::Bag::new_empty)
         }
 
+        /// A function value in a counterexample: `(<value> :> <value> @@ ...)`.
+        ///
+        /// TLC prints bags, *i.e.* functions to positive integers, the same way. Nothing tells them
+        /// apart outside of the specification, so [`cex_plain_value`] parses them as functions,
+        /// which also print back as TLC printed them.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// # use project::tlc::parse::cex_value_fun;
+        /// # use cex::value::Plain;
+        /// let fun = cex_value_fun(r#"(1 :> "a" @@ 2 :> "b")"#).unwrap();
+        /// assert_eq!(fun.len(), 2);
+        /// assert_eq!(fun.values().last(), Some(&Plain::from("b")));
+        /// ```
+        pub rule cex_value_fun() -> cex::value::Fun
+        = "(" _ head_key:cex_plain_value() _ ":>" _ head_value:cex_plain_value()
+        tail:(
+            _ "@@" _ key:cex_plain_value() _ ":>" _ value:cex_plain_value() {
+                (key, value)
+            }
+        )* _ ")" {
+            let mut content = cex::value::Fun::new_empty();
+            let _prev = content.insert(head_key, head_value);
+            debug_assert_eq!(_prev, None);
+            content.extend(tail);
+            content
+        }
+
         /// A plain value, collection or constant.
         pub rule cex_plain_value() -> cex::value::Plain
-        = cst:cex_value_cst() { cst.into() }
+        = interval:cex_value_interval() { interval.into() }
+        / cst:cex_value_cst() { cst.into() }
         / tuple:cex_value_tuple() { tuple.into() }
         / smap:cex_value_smap() { smap.into() }
+        / fun:cex_value_fun() { fun.into() }
         / bag:cex_value_bag() { bag.into() }
         / set:cex_value_set() { set.into() }
 
         /// A value or `null` (undefined value).
         pub rule cex_value() -> cex::Value
-        = val:cex_plain_value() { val.into() }
-        / "null" { cex::Value::Null }
+        = "null" !ident_char() { cex::Value::Null }
+        / val:cex_plain_value() { val.into() }
 
         /// Returns the state index, and the state info if not initial.
         pub rule state_info() -> (usize, Option<cex::StateInfo>)
@@ -763,6 +992,23 @@ peg::parser! {
             (id, val)
         }
 
+        /// Variable and raw value (`<ident> = <value>`) in a cex, for values [`cex_ident_value`]
+        /// fails to parse.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// # use project::tlc::parse::cex_ident_raw;
+        /// let (id, raw) = cex_ident_raw("/\\ x = <<1, 2>> :> [a |-> 0]").unwrap();
+        /// assert_eq!(id, "x");
+        /// assert_eq!(raw, "<<1, 2>> :> [a |-> 0]");
+        /// assert!(cex_ident_raw("     b |-> 2]").is_err());
+        /// ```
+        pub rule cex_ident_raw() -> (&'input str, &'input str)
+        = ("/\\" _)? id:ident() _ "=" _ raw:$([_]*) {
+            (id, raw.trim_end())
+        }
+
         /// A state in a trace of states and its index.
         pub rule trace_state() -> Res<(usize, cex::State)>
         = index_and_info:state_info() id_val:(
@@ -850,4 +1096,199 @@ mod test {
         let res = super::dq_string(input).unwrap();
         assert_eq!(res, "\\\\");
     }
+
+    #[test]
+    fn cex_values() {
+        use cex::value::{Cst, Plain};
+        let parse = |input: &str| super::cex_plain_value(input).unwrap();
+
+        assert_eq!(parse("-3"), Plain::Cst(Cst::I((-3).into())));
+        assert_eq!(parse("TRUE"), Plain::from(true));
+        assert_eq!(parse("TRUEx"), Plain::Cst(Cst::model("TRUEx")));
+        assert_eq!(parse(r#""a\"b\\c\n""#), Plain::from("a\"b\\c\n"));
+        match parse("-1..3") {
+            Plain::Interval(interval) => {
+                assert_eq!(interval.lo, (-1).into());
+                assert_eq!(interval.hi, 3.into());
+            }
+            value => panic!("unexpected value {:?}", value),
+        }
+        match parse("{p1, p2}") {
+            Plain::Set(set) => assert_eq!(
+                set.elms,
+                vec![Plain::Cst(Cst::model("p1")), Plain::Cst(Cst::model("p2"))],
+            ),
+            value => panic!("unexpected value {:?}", value),
+        }
+        match parse(r#"(1 :> "a" @@ 2 :> "b")"#) {
+            Plain::Fun(fun) => {
+                assert_eq!(fun.len(), 2);
+                assert_eq!(fun.values().next(), Some(&Plain::from("a")));
+            }
+            value => panic!("unexpected value {:?}", value),
+        }
+        match parse("(a :> 1 @@ b :> -2)") {
+            Plain::Fun(fun) => assert_eq!(fun.len(), 2),
+            value => panic!("unexpected value {:?}", value),
+        }
+        match parse("(a :> 1 @@ b :> 2)") {
+            Plain::Fun(fun) => assert_eq!(fun.len(), 2),
+            value => panic!("unexpected value {:?}", value),
+        }
+        assert_eq!(
+            cex::Value::from(parse("(a :> 1 @@ b :> 2)"))
+                .tla()
+                .to_string(),
+            "(a :> 1 @@ b :> 2)",
+        );
+        match parse("()") {
+            Plain::Bag(bag) => assert_eq!(bag.len(), 0),
+            value => panic!("unexpected value {:?}", value),
+        }
+        match parse("[pc |-> <<>>, id |-> p1, seq |-> <<1, -2>>]") {
+            Plain::SMap(smap) => {
+                assert_eq!(smap.len(), 3);
+                assert_eq!(smap.get("id"), Some(&Plain::Cst(Cst::model("p1"))));
+                assert!(matches!(smap.get("seq"), Some(Plain::Tuple(seq)) if seq.len() == 2));
+            }
+            value => panic!("unexpected value {:?}", value),
+        }
+        assert_eq!(super::cex_value("null").unwrap(), cex::Value::Null);
+    }
+
+    #[test]
+    fn progress_stats() {
+        use crate::tlc::code::Tlc;
+        let input = "Progress(12) at 2022-03-14 10:20:30: 1,234 states generated \
+            (5,678 s/min), 456 distinct states found (789 ds/min), 12 states left on queue.";
+        match super::progress_stats(input).unwrap() {
+            Tlc::TlcProgressStats {
+                depth,
+                generated,
+                gen_spm,
+                distinct,
+                dist_spm,
+                left,
+            } => {
+                assert_eq!(depth, 12);
+                assert_eq!(generated.1, "1,234");
+                assert_eq!(gen_spm.map(|(_, s)| s), Some("5,678".into()));
+                assert_eq!(distinct.1, "456");
+                assert_eq!(dist_spm.map(|(_, s)| s), Some("789".into()));
+                assert_eq!(left.1, "12");
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+    }
+
+    #[test]
+    fn coverage() {
+        use crate::tlc::code::Tlc;
+        let input = "<Next line 14, col 1 to line 16, col 10 of module sw>: 12:1,034";
+        match super::coverage_next(input).unwrap() {
+            Tlc::TlcCoverageNext {
+                name,
+                span,
+                distinct,
+                generated,
+            } => {
+                assert_eq!(name, "Next");
+                assert_eq!(span.file, "sw");
+                assert!(span.pos.is(14, 1));
+                assert!(span.end.is(16, 10));
+                assert_eq!(distinct.1, "12");
+                assert_eq!(generated.1, "1,034");
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+        let input = "<inv_cnt_pos line 29, col 1 to line 29, col 11 of module sw>";
+        match super::coverage_property(input).unwrap() {
+            Tlc::TlcCoverageProperty { name, span } => {
+                assert_eq!(name, "inv_cnt_pos");
+                assert!(span.pos.is(29, 1));
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+        let input = "  ||line 15, col 5 to line 15, col 20 of module sw: 34";
+        match super::coverage_value(input).unwrap() {
+            Tlc::TlcCoverageValue { level, span, count } => {
+                assert_eq!(level, 2);
+                assert!(span.pos.is(15, 5));
+                assert_eq!(count.1, "34");
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+        let input = "  |line 15, col 5 to line 15, col 20 of module sw: 34:1,000";
+        match super::coverage_value_cost(input).unwrap() {
+            Tlc::TlcCoverageValueCost {
+                level, count, cost, ..
+            } => {
+                assert_eq!(level, 1);
+                assert_eq!(count.1, "34");
+                assert_eq!(cost.1, "1,000");
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+    }
+
+    #[test]
+    fn progress_simu() {
+        use crate::tlc::code::Tlc;
+        let input = "Progress: 1,234 states checked, 56 traces generated \
+            (trace length: mean=22, var(x)=12, sd=3)";
+        match super::progress_simu(input).unwrap() {
+            Tlc::TlcProgressSimu {
+                checked,
+                traces,
+                mean_length,
+            } => {
+                assert_eq!(checked.1, "1,234");
+                assert_eq!(traces.map(|(_, s)| s), Some("56".into()));
+                assert_eq!(mean_length, Some("22".into()));
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+        let input = "Progress: 7 states checked.";
+        match super::progress_simu(input).unwrap() {
+            Tlc::TlcProgressSimu {
+                checked, traces, ..
+            } => {
+                assert_eq!(checked.1, "7");
+                assert!(traces.is_none());
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+    }
+
+    #[test]
+    fn progress_dfid() {
+        use crate::tlc::code::Tlc;
+        let input = "Starting level 3: 1,024 states generated, 12 distinct states found.";
+        match super::progress_start_stats_dfid(input).unwrap() {
+            Tlc::TlcProgressStartStatsDfid {
+                level,
+                generated,
+                distinct,
+            } => {
+                assert_eq!(level, 3);
+                assert_eq!(generated.1, "1,024");
+                assert_eq!(distinct.1, "12");
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+        let input = "Progress(4): 2,048 states generated, 17 distinct states found.";
+        match super::progress_stats_dfid(input).unwrap() {
+            Tlc::TlcProgressStatsDfid {
+                generated,
+                gen_spm,
+                distinct,
+                ..
+            } => {
+                assert_eq!(generated.1, "2,048");
+                assert!(gen_spm.is_none());
+                assert_eq!(distinct.1, "17");
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+    }
 }
@@ -0,0 +1,123 @@
+//! Probes TLC by running it on a trivial module.
+//!
+//! Used by `matla doctor` to check that TLC works, by `matla toolchain add` to record the version
+//! of a toolchain, and to know whether TLC supports the [trace dump](tlc::dump) before running it
+//! on an actual project.
+
+prelude!();
+
+use tlc::code;
+
+/// Name of the module TLC runs on.
+pub const MODULE_NAME: &str = "matla_probe";
+/// Module TLC runs on.
+pub const MODULE: &str = "\
+---- MODULE matla_probe ----
+VARIABLE x
+Init == x = 0
+Next == x' = x
+====
+";
+/// Configuration of the module TLC runs on.
+pub const CFG: &str = "\
+INIT Init
+NEXT Next
+";
+
+/// Result of a probe.
+#[derive(Debug, Clone)]
+pub struct Probe {
+    /// Output of TLC.
+    pub output: io::Output,
+    /// Version TLC reported, if any.
+    pub version: Option<String>,
+    /// True if TLC reported that no java runtime was found.
+    pub no_java: bool,
+}
+impl Probe {
+    /// Constructor from the output of TLC.
+    pub fn new(output: io::Output) -> Self {
+        let (version, no_java) = scan(&String::from_utf8_lossy(&output.stdout));
+        Self {
+            output,
+            version,
+            no_java,
+        }
+    }
+
+    /// Version TLC reported, fails if TLC did not run properly.
+    pub fn into_version(self) -> Res<String> {
+        if self.no_java {
+            bail!("no java runtime found")
+        } else if !self.output.status.success() {
+            bail!("TLC failed on a trivial module ({})", self.output.status)
+        }
+        self.version
+            .ok_or_else(|| anyhow!("TLC ran but did not report its version"))
+    }
+}
+
+/// Runs a TLC command on the probe module, in a temporary directory.
+///
+/// Fails if the directory cannot be created or if TLC cannot be launched.
+pub fn run(mut cmd: io::Command) -> Res<Probe> {
+    let dir = std::env::temp_dir().join(format!("{}_{}", MODULE_NAME, std::process::id()));
+    io::create_dir_all(&dir)
+        .with_context(|| anyhow!("failed to create directory `{}`", dir.display()))?;
+    let tla_path = dir.join(format!("{}.tla", MODULE_NAME));
+    let cfg_path = dir.join(format!("{}.cfg", MODULE_NAME));
+    {
+        use io::Write;
+        io::write_file(&tla_path, true, false)?.write_all(MODULE.as_bytes())?;
+        io::write_file(&cfg_path, true, false)?.write_all(CFG.as_bytes())?;
+    }
+
+    cmd.current_dir(&dir)
+        .args(["-tool", "-metadir", "tlc_meta"])
+        .arg(tla_path.file_name().expect("file path has a file name"));
+    let output = cmd.output();
+    if let Err(e) = io::remove_dir_all(&dir) {
+        log::warn!("failed to delete directory `{}`: {}", dir.display(), e)
+    }
+    let output = output.context("failed to launch TLC")?;
+    Ok(Probe::new(output))
+}
+
+/// Runs the TLC command of the current configuration on the probe module, see [`run`].
+pub fn run_current() -> Res<Probe> {
+    run(conf::toolchain::tlc_cmd()?)
+}
+
+/// Scans TLC's output for its version and for a missing java runtime.
+///
+/// # Examples
+///
+/// ```rust
+/// # use project::tlc::probe::scan;
+/// let stdout = "\
+/// @!@!@STARTMSG 2262:0 @!@!@
+/// TLC2 Version 2.18 of Day Month 20??
+/// @!@!@ENDMSG 2262 @!@!@
+/// ";
+/// let (version, no_java) = scan(stdout);
+/// assert_eq!(version.as_deref(), Some("TLC2 Version 2.18 of Day Month 20??"));
+/// assert!(!no_java);
+/// assert_eq!(scan("Error: unable to access jarfile"), (None, false));
+/// ```
+pub fn scan(stdout: &str) -> (Option<String>, bool) {
+    let mut version = None;
+    let mut no_java = false;
+    let mut lines = stdout.lines();
+    while let Some(line) = lines.next() {
+        match code::TopMsg::parse_start(line) {
+            Ok(Some((msg_code, _))) if msg_code == code::Tlc::TlcVersion.code() => {
+                version = lines.next().map(|line| line.trim().to_string());
+            }
+            Ok(Some((msg_code, _))) if msg_code == code::Err::NoJavaRuntime.code() => {
+                no_java = true
+            }
+            Ok(_) | Err(_) => (),
+        }
+    }
+    (version, no_java)
+}
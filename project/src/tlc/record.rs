@@ -0,0 +1,172 @@
+//! Recordings of TLC's raw output, see `matla run --record` and `matla run --replay`.
+//!
+//! A recording stores the lines [`TlcHandler`] reads from TLC's `stdout` and `stderr`, in the order
+//! it reads them, along with TLC's exit code. Replaying a recording feeds these lines to a
+//! [`TlcRun`] without running TLC at all, which makes it possible to reproduce (and test) how matla
+//! handles the output of a run without the JVM and toolchain that produced it.
+//!
+//! A recording is a text file starting with [`HEADER`], followed by comments starting with `#` and
+//! one entry per line: the number of milliseconds since the beginning of the run, a stream tag, and
+//! the line itself. The stream tag is `out` for `stdout`, `err` for `stderr`, and `exit` for the
+//! exit code of TLC, which is the last entry of complete recordings.
+//!
+//! ```text
+//! # matla TLC recording
+//! 0 out @!@!@STARTMSG 2262:0 @!@!@
+//! 0 out TLC2 Version 2.16 of 31 December 2020 (rev: cdddf55)
+//! 1 out @!@!@ENDMSG 2262 @!@!@
+//! 1532 exit 0
+//! ```
+//!
+//! Recordings are written line by line, so that they are usable even if matla crashes during the
+//! run.
+//!
+//! [`TlcHandler`]: tlc::msg::TlcHandler (TLC message handler)
+//! [`TlcRun`]: tlc::TlcRun (TLC run handler)
+
+prelude!();
+
+/// First line of recording files.
+pub const HEADER: &str = "# matla TLC recording";
+
+/// Tag of `stdout` lines.
+const STDOUT_TAG: &str = "out";
+/// Tag of `stderr` lines.
+const STDERR_TAG: &str = "err";
+/// Tag of the exit code.
+const EXIT_TAG: &str = "exit";
+
+/// A line of TLC's output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// Milliseconds since the beginning of the run.
+    pub time: u64,
+    /// True if the line comes from `stderr`.
+    pub from_stderr: bool,
+    /// Actual line.
+    pub line: String,
+}
+
+/// Writes a recording as TLC runs.
+#[derive(Debug)]
+pub struct Recorder {
+    /// Path to the recording file.
+    path: io::PathBuf,
+    /// Recording file.
+    file: io::File,
+    /// Beginning of the run.
+    start: time::Instant,
+}
+impl Recorder {
+    /// Constructor, overwrites the recording file if it exists.
+    pub fn new(path: impl Into<io::PathBuf>) -> Res<Self> {
+        use io::Write;
+        let path = path.into();
+        let mut file = io::write_file(&path, true, false)?;
+        writeln!(file, "{}", HEADER)
+            .with_context(|| anyhow!("failed to write recording `{}`", path.display()))?;
+        Ok(Self {
+            path,
+            file,
+            start: time::Instant::now(),
+        })
+    }
+
+    /// Writes an entry.
+    fn entry(&mut self, tag: &str, content: impl fmt::Display) -> Res<()> {
+        use io::Write;
+        let time = self.start.elapsed().as_millis();
+        writeln!(self.file, "{} {} {}", time, tag, content)
+            .with_context(|| anyhow!("failed to write recording `{}`", self.path.display()))
+    }
+
+    /// Records a line of TLC's output.
+    pub fn line(&mut self, line: &str, from_stderr: bool) -> Res<()> {
+        let tag = if from_stderr { STDERR_TAG } else { STDOUT_TAG };
+        self.entry(tag, line)
+    }
+
+    /// Records TLC's exit code.
+    pub fn exit(&mut self, code: i32) -> Res<()> {
+        self.entry(EXIT_TAG, code)
+    }
+}
+
+/// A recording of a TLC run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recording {
+    /// Lines of TLC's output.
+    pub lines: Vec<Line>,
+    /// TLC's exit code, `None` if the recording stopped before TLC exited.
+    pub exit: Option<i32>,
+}
+impl Recording {
+    /// Loads a recording file.
+    pub fn load(path: impl AsRef<io::Path>) -> Res<Self> {
+        let path = path.as_ref();
+        let content = io::load_file(path)?;
+        Self::parse(&content).with_context(|| anyhow!("illegal recording `{}`", path.display()))
+    }
+
+    /// Parses the content of a recording file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use project::tlc::record::Recording;
+    /// let content = "\
+    /// ## matla TLC recording
+    /// 0 out @!@!@STARTMSG 2262:0 @!@!@
+    /// 3 err some error
+    /// 3 out
+    /// 12 exit 0
+    /// ";
+    /// let recording = Recording::parse(content).unwrap();
+    /// assert_eq!(recording.lines.len(), 3);
+    /// assert_eq!(recording.lines[0].line, "@!@!@STARTMSG 2262:0 @!@!@");
+    /// assert!(recording.lines[1].from_stderr);
+    /// assert_eq!(recording.lines[1].time, 3);
+    /// assert_eq!(recording.lines[2].line, "");
+    /// assert_eq!(recording.exit, Some(0));
+    /// ```
+    pub fn parse(content: &str) -> Res<Self> {
+        let mut lines = content.lines().enumerate();
+        match lines.next() {
+            Some((_, header)) if header.trim() == HEADER => (),
+            _ => bail!("expected recording to start with `{}`", HEADER),
+        }
+
+        let mut res = Self {
+            lines: Vec::with_capacity(113),
+            exit: None,
+        };
+        for (idx, line) in lines {
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+            let error = || anyhow!("illegal entry on line {}: `{}`", idx + 1, line);
+            if res.exit.is_some() {
+                bail!("unexpected entry after the exit code on line {}", idx + 1)
+            }
+            let (time, rest) = line.split_once(' ').ok_or_else(error)?;
+            let time: u64 = time.parse().map_err(|_| error())?;
+            let (tag, content) = rest.split_once(' ').unwrap_or((rest, ""));
+            let from_stderr = match tag {
+                STDOUT_TAG => false,
+                STDERR_TAG => true,
+                EXIT_TAG => {
+                    res.exit = Some(content.trim().parse().map_err(|_| error())?);
+                    continue;
+                }
+                _ => return Err(error()),
+            };
+            res.lines.push(Line {
+                time,
+                from_stderr,
+                line: content.into(),
+            });
+        }
+        res.lines.shrink_to_fit();
+        Ok(res)
+    }
+}
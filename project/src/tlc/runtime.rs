@@ -284,6 +284,11 @@ pub struct Runtime {
     pub stack_mem: SVec<[Frame; 8]>,
     /// Updated on errors / cex-s.
     pub outcome: RunOutcome,
+    /// If true, TLC keeps checking after the first violation, see [`conf::TlcCla::keep_going`].
+    pub keep_going: bool,
+    /// If true, TLC dumps the trace, see [`tlc::dump`]: cex values matla fails to parse are
+    /// resolved from the dump instead of failing the run.
+    pub trace_dump: bool,
 }
 impl Runtime {
     /// Constructor.
@@ -292,12 +297,20 @@ impl Runtime {
             stack: smallvec![WarmUp.into()],
             stack_mem: smallvec![],
             outcome: tlc::RunOutcome::Success,
+            keep_going: false,
+            trace_dump: false,
         }
     }
 
     /// Pushes a frame on the stack.
-    fn push(&mut self, frame: Frame) {
+    fn push(&mut self, mut frame: Frame) {
         // println!("+ `{}`", frame.mode.desc());
+        // Modes creating the analysis mode know nothing about the runtime's options.
+        match &mut frame.mode {
+            TlcMode::Analysis(analysis) => analysis.keep_going = self.keep_going,
+            TlcMode::Trace(trace) => trace.trace_dump = self.trace_dump,
+            _ => (),
+        }
         self.stack.push(frame);
     }
     /// Pops a frame on the stack.
@@ -5,10 +5,15 @@ use super::*;
 #[derive(Debug, Clone)]
 pub struct Analysis {
     pub safe: bool,
+    /// If true, the analysis goes on after a cex, see [`Runtime::keep_going`].
+    pub keep_going: bool,
 }
 impl Analysis {
     pub fn new() -> Self {
-        Self { safe: true }
+        Self {
+            safe: true,
+            keep_going: false,
+        }
     }
 }
 
@@ -30,10 +35,35 @@ impl IsMode for Analysis {
                 out.handle_message(&msg, log::Level::Trace);
                 Control::replace(Success::new(self.safe)).ok_some()
             }
+            // Simulation ends.
+            Msg::Tlc(TlcMsg::Msg(Tlc::TlcStatsSimu { .. })) => {
+                out.handle_message(&msg, log::Level::Debug);
+                Control::replace(Success::new(self.safe)).ok_some()
+            }
 
             // Regular stats.
             Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressStats { .. }))
             | Msg::Tlc(TlcMsg::Msg(Tlc::TlcStats { .. }))
+            // Depth-first iterative deepening stats.
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressStartStatsDfid { .. }))
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressStatsDfid { .. }))
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcStatsDfid { .. }))
+            // Simulation stats.
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressSimu { .. }))
+            // Checkpoints.
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcCheckpointStart | Tlc::TlcCheckpointEnd))
+            // Coverage statistics.
+            | Msg::Tlc(TlcMsg::Msg(
+                Tlc::TlcCoverageStart
+                | Tlc::TlcCoverageEnd
+                | Tlc::TlcCoverageEndOverhead
+                | Tlc::TlcCoverageInit { .. }
+                | Tlc::TlcCoverageNext { .. }
+                | Tlc::TlcCoverageProperty { .. }
+                | Tlc::TlcCoverageConstraint { .. }
+                | Tlc::TlcCoverageValue { .. }
+                | Tlc::TlcCoverageValueCost { .. },
+            ))
             // Starting to check temporal properties.
             | Msg::Tlc(TlcMsg::Msg(Tlc::TlcCheckingTemporalProps { .. }))
             // Starting to check temporal properties.
@@ -42,19 +72,27 @@ impl IsMode for Analysis {
                 Control::keep(self).ok_some()
             }
 
-            // Cex on invariant.
-            Msg::TlcUnsafe(TlcUnsafe::TlcInvariantViolatedBehavior { invariant }) => {
+            // End of a run that kept going after some violation(s).
+            Msg::Tlc(TlcMsg::Msg(
+                Tlc::TlcSearchDepth { .. } | Tlc::TlcStateGraphOutdegree { .. },
+            )) if self.keep_going => {
                 out.handle_message(&msg, log::Level::Debug);
-                let cex = cex::Cex::new().set_falsified(invariant)?;
-                Control::keep_and(self, Trace::new(cex)).ok_some()
+                Control::replace(Success::new(self.safe)).ok_some()
             }
-            // Cex on temporal property.
-            Msg::TlcUnsafe(TlcUnsafe::TlcTemporalPropertyViolated) => {
+            Msg::Status(Status::TlcFinished { .. }) if self.keep_going => {
                 out.handle_message(&msg, log::Level::Debug);
-                let cex = cex::Cex::new();
-                Control::keep_and(self, Trace::new(cex)).ok_some()
+                Control::done(self.safe).ok_some()
             }
 
+            // Cex on invariant or temporal property.
+            Msg::TlcUnsafe(tlc_unsafe) => match Trace::of_violation(tlc_unsafe)? {
+                Some(trace) => {
+                    out.handle_message(&msg, log::Level::Debug);
+                    Control::keep_and(self, trace).ok_some()
+                }
+                None => Control::ignored(self).ok_some(),
+            },
+
             _ => Control::ignored(self).ok_some(),
         }
     }
@@ -69,7 +107,12 @@ impl IsMode for Analysis {
             }
             ModeOutcomeKind::Cex(cex) => {
                 out.handle_cex(cex);
-                Control::replace(Success::new(false)).ok()
+                if self.keep_going {
+                    self.safe = false;
+                    Control::keep(self).ok()
+                } else {
+                    Control::replace(Success::new(false)).ok()
+                }
             }
             ModeOutcomeKind::Problem { reported, .. } => {
                 if reported {
@@ -75,7 +75,9 @@ impl IsMode for Error {
         use code::*;
         match tlc_msg {
             Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressStats { .. }))
-            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcStats { .. })) => {
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcStats { .. }))
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressStatsDfid { .. }))
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcStatsDfid { .. })) => {
                 out.handle_message(&msg, log::Level::Debug);
                 Control::keep_and(self, Trace::new_empty()).ok_some()
             }
@@ -27,6 +27,11 @@ impl IsMode for InitialStates {
                 out.handle_message(&msg, log::Level::Trace);
                 Control::replace(Analysis::new()).ok_some()
             }
+            // Simulation progress, initial states are done.
+            Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressSimu { .. })) => {
+                out.handle_message(&msg, log::Level::Debug);
+                Control::replace(Analysis::new()).ok_some()
+            }
             Msg::Tlc(TlcMsg::Msg(Tlc::TlcComputingInitProgress))
             | Msg::Tlc(TlcMsg::Live(TlcLive::TlcLiveImplied)) => {
                 out.handle_message(&msg, log::Level::Trace);
@@ -22,10 +22,18 @@ impl IsMode for Starting {
                 out.handle_message(&msg, log::Level::Debug);
                 Control::replace(InitialStates).ok_some()
             }
-            Msg::Tlc(TlcMsg::Live(TlcLive::TlcLiveImplied)) => {
+            Msg::Tlc(TlcMsg::Live(TlcLive::TlcLiveImplied))
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcCheckpointRecoverStart)) => {
                 out.handle_message(&msg, log::Level::Debug);
                 Control::keep(self).ok_some()
             }
+            // Recovered from a checkpoint, no initial states to compute.
+            Msg::Tlc(TlcMsg::Msg(
+                Tlc::TlcCheckpointRecoverEnd | Tlc::TlcCheckpointRecoverEndDfid,
+            )) => {
+                out.handle_message(&msg, log::Level::Debug);
+                Control::replace(Analysis::new()).ok_some()
+            }
             _ => Control::ignored(self).ok_some(),
         }
     }
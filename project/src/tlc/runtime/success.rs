@@ -31,6 +31,22 @@ impl IsMode for Success {
             }
             Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressStats { .. }))
             | Msg::Tlc(TlcMsg::Msg(Tlc::TlcStats { .. }))
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressStatsDfid { .. }))
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcStatsDfid { .. }))
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressSimu { .. }))
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcStatsSimu { .. }))
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcCheckpointStart | Tlc::TlcCheckpointEnd))
+            | Msg::Tlc(TlcMsg::Msg(
+                Tlc::TlcCoverageStart
+                | Tlc::TlcCoverageEnd
+                | Tlc::TlcCoverageEndOverhead
+                | Tlc::TlcCoverageInit { .. }
+                | Tlc::TlcCoverageNext { .. }
+                | Tlc::TlcCoverageProperty { .. }
+                | Tlc::TlcCoverageConstraint { .. }
+                | Tlc::TlcCoverageValue { .. }
+                | Tlc::TlcCoverageValueCost { .. },
+            ))
             | Msg::Tlc(TlcMsg::Msg(
                 Tlc::TlcSearchDepth { .. } | Tlc::TlcStateGraphOutdegree { .. },
             )) => {
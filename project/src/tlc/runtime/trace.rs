@@ -6,6 +6,8 @@ use super::*;
 pub struct Trace {
     /// The trace of states.
     pub cex: cex::Cex,
+    /// If true, values matla fails to parse are left `null`, see [`Runtime::trace_dump`].
+    pub trace_dump: bool,
 }
 
 impl Trace {
@@ -15,7 +17,21 @@ impl Trace {
     }
     /// Constructor from a CEX.
     pub fn new(cex: cex::Cex) -> Self {
-        Self { cex }
+        Self {
+            cex,
+            trace_dump: false,
+        }
+    }
+    /// Constructor for the trace following a violation message, `None` if `msg` has no trace.
+    pub fn of_violation(msg: &code::TlcUnsafe) -> Res<Option<Self>> {
+        match msg {
+            code::TlcUnsafe::TlcInvariantViolatedBehavior { invariant } => {
+                let cex = cex::Cex::new().set_falsified(invariant)?;
+                Ok(Some(Self::new(cex)))
+            }
+            code::TlcUnsafe::TlcTemporalPropertyViolated => Ok(Some(Self::new_empty())),
+            _ => Ok(None),
+        }
     }
 }
 
@@ -50,7 +66,13 @@ impl IsMode for Trace {
             // End of temporal cex.
             Msg::Tlc(TlcMsg::Msg(Tlc::TlcCheckingTemporalPropsEnd))
             // End of normal cex. Note how inappropriate this message is.
-            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressStats { .. })) => {
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressStats { .. }))
+            // End of depth-first iterative deepening cex.
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressStatsDfid { .. }))
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcStatsDfid { .. }))
+            // End of simulation cex.
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcProgressSimu { .. }))
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcStatsSimu { .. })) => {
                 out.handle_message(&msg, log::Level::Trace);
                 let outcome = ModeOutcome::new_cex(self.cex);
                 Control::finalize(outcome).ok_some()
@@ -72,11 +94,35 @@ impl IsMode for Trace {
                 Control::keep(self).ok_some()
             }
 
+            // Next violation, only happens when TLC keeps going after a violation: the trace is
+            // complete and a new one starts.
+            Msg::TlcUnsafe(tlc_unsafe) => match Trace::of_violation(tlc_unsafe)? {
+                Some(next) => {
+                    out.handle_message(&msg, log::Level::Debug);
+                    out.handle_cex(self.cex);
+                    Control::replace(next).ok_some()
+                }
+                None => Control::ignored(self).ok_some(),
+            },
+
             // State of a trace.
-            Msg::Cex(TlcCex::TlcTraceState { index, state }) => {
+            Msg::Cex(TlcCex::TlcTraceState { index, state, raw }) => {
                 out.handle_message(&msg, log::Level::Trace);
                 // Cloning the state here, could be better with a swap or something.
-                let _idx = self.cex.push(state.clone());
+                let mut state = state.clone();
+                for (var, value) in raw {
+                    if !self.trace_dump {
+                        bail!("failed to parse value of `{}` in cex state: `{}`", var, value)
+                    }
+                    log::debug!(
+                        "failed to parse value of `{}` in cex state, resolving it from the trace \
+                        dump: `{}`",
+                        var,
+                        value,
+                    );
+                    let _prev = state.insert(var.clone(), cex::Value::Null);
+                }
+                let _idx = self.cex.push(state);
                 if *index == 0 {
                     bail!("unexpected state index `{}`", index);
                 }
@@ -22,8 +22,19 @@ impl IsMode for WarmUp {
                 out.handle_message(msg, log::Level::Debug);
                 Control::replace(Parsing::new()).ok_some()
             }
-            // Version info.
-            Msg::Tlc(TlcMsg::Msg(Tlc::TlcVersion)) | Msg::Tlc(TlcMsg::Msg(Tlc::TlcModeMc)) => {
+            // Version info, checked against the toolchain the project pins.
+            Msg::Tlc(TlcMsg::Msg(Tlc::TlcVersion)) => {
+                out.handle_message(msg, log::Level::Trace);
+                match tlc::warn::Version::check(msg) {
+                    Ok(Some(warning)) => out.handle_error(tlc::warn::TlcWarning::from(warning))?,
+                    Ok(None) => (),
+                    Err(e) => log::warn!("failed to check TLC's version: {:?}", e),
+                }
+                Control::keep(self).ok_some()
+            }
+            Msg::Tlc(TlcMsg::Msg(Tlc::TlcModeMc))
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcModeMcDfs))
+            | Msg::Tlc(TlcMsg::Msg(Tlc::TlcModeSimu)) => {
                 out.handle_message(msg, log::Level::Trace);
                 Control::keep(self).ok_some()
             }
@@ -3,16 +3,20 @@
 prelude!();
 
 /// Enumerates all the warnings handled by matla.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
 pub enum TlcWarning {
     /// A redefinition.
     Redef(Redef),
+    /// A TLC version mismatch with the toolchain the project pins.
+    Version(Version),
 }
 impl TlcWarning {
     /// Pretty, multi-line representation.
     pub fn pretty(&self, project: &crate::FullProject, styles: &conf::Styles) -> Res<Vec<String>> {
         match self {
             Self::Redef(w) => w.pretty(project, styles),
+            Self::Version(w) => w.pretty(project, styles),
         }
     }
 
@@ -20,6 +24,7 @@ impl TlcWarning {
     pub fn desc(&self) -> &'static str {
         match self {
             Self::Redef(w) => w.desc(),
+            Self::Version(w) => w.desc(),
         }
     }
 }
@@ -28,11 +33,14 @@ implem! {
         From<Redef> {
             |w| Self::Redef(w)
         }
+        From<Version> {
+            |w| Self::Version(w)
+        }
     }
 }
 
 /// A redefinition for some `sym`bol at some `pos`ition for a `prev`iously defined symbol.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct Redef {
     /// Position of the redefinition.
     pub pos: source::FileSpan,
@@ -93,3 +101,64 @@ impl Redef {
         Ok(res)
     }
 }
+
+/// TLC reports another version than the one recorded for the toolchain the project pins, see
+/// [`conf::toolchain_store`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Version {
+    /// Toolchain the project pins.
+    pub toolchain: String,
+    /// Version recorded for this toolchain, when it was added or the first time TLC ran with it.
+    pub expected: String,
+    /// Version TLC reports.
+    pub reported: String,
+}
+
+impl Version {
+    /// Checks the content of a TLC version message against the toolchain the project pins.
+    ///
+    /// Records the version for this toolchain if none was recorded, *i.e.* if TLC failed to run
+    /// when the toolchain was added.
+    pub fn check(msg: &tlc::msg::Msg) -> Res<Option<Self>> {
+        let toolchain = match conf::toolchain_store::pinned() {
+            Some(toolchain) => toolchain,
+            None => return Ok(None),
+        };
+        let lines = msg.lines();
+        let reported = match lines.iter().map(|line| line.trim()).find(|l| !l.is_empty()) {
+            Some(line) => line.to_string(),
+            None => return Ok(None),
+        };
+        match conf::toolchain_store::tlc_version(&toolchain)? {
+            Some(expected) if expected != reported => Ok(Some(Self {
+                toolchain,
+                expected,
+                reported,
+            })),
+            Some(_) => Ok(None),
+            None => {
+                conf::toolchain_store::record_tlc_version(&toolchain, &reported)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Static string description.
+    pub fn desc(&self) -> &'static str {
+        "warning"
+    }
+
+    /// Pretty, multi-line representation.
+    pub fn pretty(&self, _project: &crate::FullProject, styles: &conf::Styles) -> Res<Vec<String>> {
+        Ok(vec![
+            format!(
+                "project pins toolchain {}, for which TLC reported",
+                styles.bold.paint(&self.toolchain),
+            ),
+            format!("    {}", self.expected),
+            "but TLC now reports".into(),
+            format!("    {}", styles.bad.paint(&self.reported)),
+            "the toolchain's jar might have changed, or the launcher might not run it".into(),
+        ])
+    }
+}
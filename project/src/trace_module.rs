@@ -0,0 +1,508 @@
+//! Trace modules, TLA+ modules following the states of a cex.
+//!
+//! A trace module `EXTENDS` the module a cex comes from, along with [`EXTENDS_DEFAULT`] which
+//! define the values of traces, and declares a variable `matla_trace_idx`,
+//! the (1-indexed) position in the trace. Its init predicate and next-state relation bind the
+//! variables of the module to the values of the states of the trace, and can be restricted by the
+//! init predicate and next-state relation of the module, see [`TraceModule::behavior`]. For lassos,
+//! position `<state count> + 1` stands for the state the trace loops back to, once the loop is
+//! closed.
+//!
+//! The `cfg` file of a trace module keeps the `CONSTANTS` of the `cfg` file of the original module,
+//! see [`CfgSpec`], and declares the model values of the trace that are not constants of the
+//! original module.
+//!
+//! Cex regression tests (`testing::regression`) and `matla cex eval` build on trace modules.
+
+prelude!();
+
+/// Prefix of the identifiers of trace modules.
+pub const PREF: &str = "matla_trace";
+
+/// Modules trace modules extend by default: `Integers` for negative integers, `TLC` for functions
+/// written `(k1 :> v1 @@ k2 :> v2)`.
+pub const EXTENDS_DEFAULT: [&str; 2] = ["Integers", "TLC"];
+
+/// Symbolic constants which are not model values.
+const BUILTIN_CONSTANTS: [&str; 5] = ["BOOLEAN", "Int", "Nat", "Real", "STRING"];
+
+/// `INIT`/`NEXT` operators and `CONSTANTS` of a `cfg` file.
+#[derive(Debug, Clone, Default)]
+pub struct CfgSpec {
+    /// Init predicate.
+    pub init: Option<String>,
+    /// Next-state relation.
+    pub next: Option<String>,
+    /// True if the `cfg` file has a `SPECIFICATION`.
+    pub has_spec: bool,
+    /// Content of the `CONSTANTS` sections.
+    pub constants: Vec<String>,
+}
+impl CfgSpec {
+    /// Keywords starting `cfg` file sections.
+    const KEYWORDS: [&'static str; 20] = [
+        "CONSTANT",
+        "CONSTANTS",
+        "INIT",
+        "NEXT",
+        "SPECIFICATION",
+        "INVARIANT",
+        "INVARIANTS",
+        "PROPERTY",
+        "PROPERTIES",
+        "CONSTRAINT",
+        "CONSTRAINTS",
+        "ACTION_CONSTRAINT",
+        "ACTION_CONSTRAINTS",
+        "SYMMETRY",
+        "VIEW",
+        "ALIAS",
+        "POSTCONDITION",
+        "CHECK_DEADLOCK",
+        "TRACE_EXPLORE",
+        "TYPE",
+    ];
+
+    /// Extracts the `INIT`/`NEXT` operators and the constants of a `cfg` file.
+    ///
+    /// ```rust
+    /// # use project::trace_module::CfgSpec;
+    /// let cfg = r#"
+    /// \* Counter.
+    /// CONSTANTS
+    ///     N = 3
+    ///     Procs = {p1, p2}
+    /// INIT init NEXT next
+    /// INVARIANT inv
+    /// "#;
+    /// let spec = CfgSpec::parse(cfg);
+    /// assert_eq!(spec.init.as_deref(), Some("init"));
+    /// assert_eq!(spec.next.as_deref(), Some("next"));
+    /// assert!(!spec.has_spec);
+    /// assert_eq!(spec.constants, vec!["N = 3 Procs = {p1, p2}".to_string()]);
+    /// ```
+    pub fn parse(cfg: &str) -> Self {
+        let mut res = Self::default();
+        let mut section: Option<&str> = None;
+        for line in cfg.lines() {
+            let line = line.split("\\*").next().unwrap_or("");
+            for token in line.split_whitespace() {
+                if Self::KEYWORDS.contains(&token) {
+                    section = Some(token);
+                    match token {
+                        "CONSTANT" | "CONSTANTS" => res.constants.push(String::new()),
+                        "SPECIFICATION" => res.has_spec = true,
+                        _ => (),
+                    }
+                    continue;
+                }
+                match section {
+                    Some("INIT") if res.init.is_none() => res.init = Some(token.into()),
+                    Some("NEXT") if res.next.is_none() => res.next = Some(token.into()),
+                    Some("CONSTANT" | "CONSTANTS") => {
+                        if let Some(constants) = res.constants.last_mut() {
+                            if !constants.is_empty() {
+                                constants.push(' ');
+                            }
+                            constants.push_str(token);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+        res.constants.retain(|constants| !constants.is_empty());
+        res
+    }
+
+    /// Loads the `cfg` file of a top module of a project.
+    pub fn load(project: &SourceProject, module: &str) -> Res<Self> {
+        Self::try_load(project, module)?
+            .ok_or_else(|| anyhow!("module `{}` has no cfg file", module))
+    }
+
+    /// Loads the `cfg` file of a top module of a project, `None` if the module has no `cfg` file.
+    pub fn try_load(project: &SourceProject, module: &str) -> Res<Option<Self>> {
+        let tla_idx = project
+            .top_modules
+            .get(module)
+            .ok_or_else(|| anyhow!("unknown module `{}`", module))?;
+        match project.tla_to_cfg.get(tla_idx) {
+            Some(cfg_idx) => Ok(Some(Self::parse(&io::load_file(project[*cfg_idx].path())?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Constants assigned by the `CONSTANTS` sections.
+    fn assigned(&self) -> Set<&str> {
+        let mut res = Set::new();
+        for constants in self.constants.iter() {
+            let tokens: Vec<&str> = constants.split_whitespace().collect();
+            for pair in tokens.windows(2) {
+                if pair[1] == "=" || pair[1] == "<-" {
+                    let _ = res.insert(pair[0]);
+                }
+            }
+        }
+        res
+    }
+}
+
+/// Collects the model values of a value.
+fn model_values<'a>(value: &'a cex::value::Plain, acc: &mut Set<&'a str>) {
+    use cex::value::{Cst, Plain};
+    match value {
+        Plain::Cst(Cst::M(name)) => {
+            let _ = acc.insert(name);
+        }
+        Plain::Cst(_) | Plain::Interval(_) => (),
+        Plain::Tuple(tuple) => tuple.iter().for_each(|elm| model_values(elm, acc)),
+        Plain::Set(set) => set.iter().for_each(|elm| model_values(elm, acc)),
+        Plain::SMap(smap) => smap.values().for_each(|val| model_values(val, acc)),
+        Plain::Bag(bag) => bag.keys().for_each(|elm| model_values(elm, acc)),
+        Plain::Fun(fun) => fun.iter().for_each(|(key, val)| {
+            model_values(key, acc);
+            model_values(val, acc)
+        }),
+    }
+}
+
+/// A trace module and its `cfg` file.
+#[readonly]
+#[derive(Debug, Clone)]
+pub struct TraceModule<'a> {
+    /// Name of the trace module.
+    pub name: String,
+    /// Comment written right after the module header and at the top of the `cfg` file.
+    pub comment: Option<String>,
+    /// Module the cex comes from.
+    pub module: String,
+    /// Cex to follow.
+    pub cex: &'a cex::Cex,
+    /// Spec of the `cfg` file of the original module.
+    pub cfg: &'a CfgSpec,
+    /// Init predicate and next-state relation of the original module to follow, if any.
+    pub behavior: Option<(String, String)>,
+    /// Modules extended, on top of the original module, [`EXTENDS_DEFAULT`] by default.
+    pub extends: Vec<String>,
+    /// Additional definitions.
+    pub defs: Vec<String>,
+    /// Additional `cfg` file lines, *e.g.* `INVARIANT`s.
+    pub checks: Vec<String>,
+}
+impl<'a> TraceModule<'a> {
+    /// Constructor, `module` is the module the cex comes from.
+    pub fn new(
+        name: impl Into<String>,
+        module: impl Into<String>,
+        cex: &'a cex::Cex,
+        cfg: &'a CfgSpec,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            comment: None,
+            module: module.into(),
+            cex,
+            cfg,
+            behavior: None,
+            extends: EXTENDS_DEFAULT
+                .iter()
+                .map(|module| module.to_string())
+                .collect(),
+            defs: vec![],
+            checks: vec![],
+        }
+    }
+
+    /// Sets the comment of the trace module.
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+    /// Restricts the trace to the behaviors of the original module.
+    pub fn behavior(mut self, init: impl Into<String>, next: impl Into<String>) -> Self {
+        self.behavior = Some((init.into(), next.into()));
+        self
+    }
+    /// Extends some module, does nothing if the module is already extended.
+    pub fn extends(mut self, module: impl Into<String>) -> Self {
+        let module = module.into();
+        if !self.extends.contains(&module) {
+            self.extends.push(module);
+        }
+        self
+    }
+    /// Adds a definition.
+    pub fn def(mut self, def: impl Into<String>) -> Self {
+        self.defs.push(def.into());
+        self
+    }
+    /// Adds a line to the `cfg` file.
+    pub fn check(mut self, line: impl Into<String>) -> Self {
+        self.checks.push(line.into());
+        self
+    }
+
+    /// Position (1-indexed) of the state the trace goes back to, if any.
+    fn loops_to(&self) -> Option<usize> {
+        match self.cex.shape {
+            cex::Shape::Loop(idx) => Some(*idx + 1),
+            cex::Shape::Finite | cex::Shape::Stuttering => None,
+        }
+    }
+
+    /// Value of `matla_trace_idx` once the whole trace has been followed.
+    pub fn complete_idx(&self) -> usize {
+        let len = self.cex.states.len();
+        if self.loops_to().is_some() {
+            len + 1
+        } else {
+            len
+        }
+    }
+
+    /// Generates the trace module and its `cfg` file.
+    ///
+    /// Fails on empty traces and traces with undefined values.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use project::trace_module::{CfgSpec, TraceModule};
+    /// # use cex::{Cex, State, Value};
+    /// # use base::Int;
+    /// let mut cex = Cex::new();
+    /// for cnt in 0..2 {
+    ///     let mut state = State::new(None);
+    ///     let _ = state.insert("cnt".into(), Value::from(Int::from(cnt)));
+    ///     let _ = cex.push(state);
+    /// }
+    /// let spec = CfgSpec::parse("CONSTANT N = 3 INIT init NEXT next");
+    /// let module = TraceModule::new("cex_test__sw", "sw", &cex, &spec)
+    ///     .comment("comment")
+    ///     .behavior("init", "next");
+    /// let complete = module.complete_idx();
+    /// let (tla, cfg) = module
+    ///     .def(format!("matla_trace_incomplete == matla_trace_idx # {}", complete))
+    ///     .check("INVARIANT matla_trace_incomplete")
+    ///     .render()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     tla,
+    ///     r#"---- MODULE cex_test__sw ----
+    /// \* comment
+    ///
+    /// EXTENDS sw, Integers, TLC
+    ///
+    /// VARIABLE matla_trace_idx
+    ///
+    /// matla_trace_len == 2
+    ///
+    /// matla_trace == <<
+    ///     [cnt |-> 0],
+    ///     [cnt |-> 1]
+    /// >>
+    ///
+    /// matla_trace_succ(i) == i + 1
+    ///
+    /// matla_trace_pos(i) == i
+    ///
+    /// matla_trace_init ==
+    ///     /\ init
+    ///     /\ matla_trace_idx = 1
+    ///     /\ cnt = matla_trace[1].cnt
+    ///
+    /// matla_trace_next ==
+    ///     /\ next
+    ///     /\ matla_trace_idx < matla_trace_len
+    ///     /\ matla_trace_idx' = matla_trace_succ(matla_trace_idx)
+    ///     /\ cnt' = matla_trace[matla_trace_pos(matla_trace_idx')].cnt
+    ///
+    /// matla_trace_incomplete == matla_trace_idx # 2
+    ///
+    /// ====
+    /// "#
+    /// );
+    /// assert_eq!(
+    ///     cfg,
+    ///     r#"\* comment
+    /// CONSTANTS N = 3
+    /// INIT matla_trace_init
+    /// NEXT matla_trace_next
+    /// INVARIANT matla_trace_incomplete
+    /// CHECK_DEADLOCK FALSE
+    /// "#
+    /// );
+    /// ```
+    ///
+    /// Negative integers and functions are legal thanks to [`EXTENDS_DEFAULT`]:
+    ///
+    /// ```rust
+    /// # use project::trace_module::{CfgSpec, TraceModule};
+    /// # use cex::{value::Fun, Cex, State, Value};
+    /// # use base::Int;
+    /// let mut cex = Cex::new();
+    /// let mut state = State::new(None);
+    /// let _ = state.insert("x".into(), Value::from(Int::from(-3)));
+    /// let fun = Fun::new(
+    ///     vec![(Int::from(1).into(), "a".into()), (Int::from(2).into(), "b".into())]
+    ///         .into_iter()
+    ///         .collect(),
+    /// );
+    /// let _ = state.insert("f".into(), Value::from(fun));
+    /// let _ = cex.push(state);
+    /// let spec = CfgSpec::parse("INIT init NEXT next");
+    /// let (tla, _) = TraceModule::new("cex_test__sw", "sw", &cex, &spec)
+    ///     .behavior("init", "next")
+    ///     .render()
+    ///     .unwrap();
+    /// assert!(tla.contains("\nEXTENDS sw, Integers, TLC\n"));
+    /// assert!(tla.contains(r#"    [f |-> (1 :> "a" @@ 2 :> "b"), x |-> -3]"#));
+    /// ```
+    pub fn render(&self) -> Res<(String, String)> {
+        use fmt::Write;
+
+        let states: Vec<&cex::State> = self
+            .cex
+            .states
+            .index_iter()
+            .map(|(_, state)| state)
+            .collect();
+        let len = states.len();
+        if len == 0 {
+            bail!("cannot follow an empty trace");
+        }
+        self.cex.check_not_alias("trace modules")?;
+        let vars: Vec<&String> = states[0].values.keys().collect();
+
+        let mut constants: Set<&str> = Set::new();
+        for state in states.iter() {
+            for (var, value) in state.values.iter() {
+                match value {
+                    cex::Value::Plain(plain) => model_values(plain, &mut constants),
+                    cex::Value::Null => {
+                        bail!("cannot follow trace with undefined variable `{}`", var)
+                    }
+                }
+            }
+        }
+        let assigned = self.cfg.assigned();
+        constants.retain(|cst| !BUILTIN_CONSTANTS.contains(cst) && !assigned.contains(cst));
+
+        let loops_to = self.loops_to();
+
+        let mut tla = String::with_capacity(666);
+        writeln!(tla, "---- MODULE {} ----", self.name)?;
+        if let Some(comment) = self.comment.as_ref() {
+            writeln!(tla, "\\* {}", comment)?;
+        }
+        writeln!(tla)?;
+        writeln!(tla, "EXTENDS {}, {}", self.module, self.extends.join(", "))?;
+        writeln!(tla)?;
+        if !constants.is_empty() {
+            let constants: Vec<&str> = constants.iter().cloned().collect();
+            writeln!(tla, "CONSTANTS {}", constants.join(", "))?;
+            writeln!(tla)?;
+        }
+        writeln!(tla, "VARIABLE {}_idx", PREF)?;
+        writeln!(tla)?;
+        writeln!(tla, "{}_len == {}", PREF, len)?;
+        writeln!(tla)?;
+        writeln!(tla, "{} == <<", PREF)?;
+        for (idx, state) in states.iter().enumerate() {
+            let fields: Vec<String> = state
+                .values
+                .iter()
+                .map(|(var, val)| format!("{} |-> {}", var, val.tla()))
+                .collect();
+            let sep = if idx + 1 < len { "," } else { "" };
+            writeln!(tla, "    [{}]{}", fields.join(", "), sep)?;
+        }
+        writeln!(tla, ">>")?;
+        writeln!(tla)?;
+        match loops_to {
+            None => {
+                writeln!(tla, "{}_succ(i) == i + 1", PREF)?;
+                writeln!(tla)?;
+                writeln!(tla, "{}_pos(i) == i", PREF)?;
+            }
+            Some(target) => {
+                writeln!(
+                    tla,
+                    "{0}_succ(i) == IF i > {0}_len THEN {1} + 1 ELSE i + 1",
+                    PREF, target,
+                )?;
+                writeln!(tla)?;
+                writeln!(
+                    tla,
+                    "{0}_pos(i) == IF i > {0}_len THEN {1} ELSE i",
+                    PREF, target
+                )?;
+            }
+        }
+        writeln!(tla)?;
+        writeln!(tla, "{}_init ==", PREF)?;
+        if let Some((init, _)) = self.behavior.as_ref() {
+            writeln!(tla, "    /\\ {}", init)?;
+        }
+        writeln!(tla, "    /\\ {}_idx = 1", PREF)?;
+        for var in vars.iter() {
+            writeln!(tla, "    /\\ {1} = {0}[1].{1}", PREF, var)?;
+        }
+        writeln!(tla)?;
+        writeln!(tla, "{}_next ==", PREF)?;
+        if let Some((_, next)) = self.behavior.as_ref() {
+            writeln!(tla, "    /\\ {}", next)?;
+        }
+        if loops_to.is_none() {
+            writeln!(tla, "    /\\ {0}_idx < {0}_len", PREF)?;
+        }
+        writeln!(tla, "    /\\ {0}_idx' = {0}_succ({0}_idx)", PREF)?;
+        for var in vars.iter() {
+            writeln!(tla, "    /\\ {1}' = {0}[{0}_pos({0}_idx')].{1}", PREF, var)?;
+        }
+        writeln!(tla)?;
+        for def in self.defs.iter() {
+            writeln!(tla, "{}", def)?;
+            writeln!(tla)?;
+        }
+        writeln!(tla, "====")?;
+
+        let mut cfg = String::with_capacity(113);
+        if let Some(comment) = self.comment.as_ref() {
+            writeln!(cfg, "\\* {}", comment)?;
+        }
+        for constants in self.cfg.constants.iter() {
+            writeln!(cfg, "CONSTANTS {}", constants)?;
+        }
+        for cst in constants.iter() {
+            writeln!(cfg, "CONSTANT {0} = {0}", cst)?;
+        }
+        writeln!(cfg, "INIT {}_init", PREF)?;
+        writeln!(cfg, "NEXT {}_next", PREF)?;
+        for line in self.checks.iter() {
+            writeln!(cfg, "{}", line)?;
+        }
+        writeln!(cfg, "CHECK_DEADLOCK FALSE")?;
+
+        Ok((tla, cfg))
+    }
+
+    /// Generates the trace module and its `cfg` file in a directory.
+    ///
+    /// Returns the path to the TLA file and the path to the `cfg` file.
+    pub fn write(&self, dir: &io::Path) -> Res<(io::PathBuf, io::PathBuf)> {
+        use io::Write;
+        let (tla, cfg) = self.render()?;
+        io::create_dir_all(dir)
+            .with_context(|| anyhow!("failed to create directory `{}`", dir.display()))?;
+        let tla_path = dir.join(format!("{}.tla", self.name));
+        let cfg_path = tla_path.with_extension("cfg");
+        for (path, content) in [(&tla_path, tla), (&cfg_path, cfg)] {
+            let mut file = io::write_file(path, true, false)?;
+            file.write_all(content.as_bytes())
+                .with_context(|| anyhow!("failed to write `{}`", path.display()))?;
+        }
+        Ok((tla_path, cfg_path))
+    }
+}
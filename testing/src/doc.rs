@@ -1,23 +1,241 @@
 //! Defines and handles doc-tests.
+//!
+//! Doc tests are example blocks in the documentation comments of the operators of the project's
+//! modules, see [`project::doc`]. An example block is a fenced block, *i.e.* delimited by ` ``` `
+//! or ` ```tla ` and ` ``` `. Each non-indented line of the block starts an assertion, indented
+//! lines continue the current assertion.
+//!
+//! ```text
+//! \* Adds one to its input.
+//! \*
+//! \* ```
+//! \* inc(1) = 2
+//! \* inc(-1)
+//! \*     = 0
+//! \* ```
+//! inc(n) == n + 1
+//! ```
+//!
+//! Each example block yields a module `doc_test__<module>__<operator>__<n>` which `EXTENDS` the
+//! documented module and `ASSUME`s all the assertions of the block, along with a `cfg` file with no
+//! behavior spec. These files are generated in `target/<debug|release>/tests/doc_sources`, and each
+//! doc test is built in its own directory next to it.
 
 prelude!();
 
+/// Name of the directory in the test target where doc test modules are generated.
+pub const SOURCES_DIR: &str = "doc_sources";
+
 /// Test kind for doc tests.
 #[readonly]
 pub struct Kind {
-    /// Test source.
+    /// Test source, the content of the example block (0-indexed lines).
     pub source: source::LineSpan,
     /// Name of the entity the test is attached to.
     pub entity: String,
+    /// Name of the generated test module.
+    pub name: String,
 }
 impl Kind {
     /// Constructor.
-    pub fn new(entity: impl Into<String>, source: source::LineSpan) -> Self {
+    pub fn new(
+        name: impl Into<String>,
+        entity: impl Into<String>,
+        source: source::LineSpan,
+    ) -> Self {
         Self {
             source,
             entity: entity.into(),
+            name: name.into(),
+        }
+    }
+}
+
+/// An example block extracted from some documentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    /// Index of the first line of the block's content in the documentation.
+    pub start: usize,
+    /// Index of the last line of the block's content in the documentation.
+    pub end: usize,
+    /// Assertions of the example.
+    pub assertions: Vec<String>,
+}
+impl Example {
+    /// Extracts the example blocks of some documentation lines.
+    ///
+    /// Fails on unclosed blocks. Fenced blocks with an info string other than `tla` are ignored, as
+    /// are blocks with no assertions.
+    ///
+    /// ```rust
+    /// # use testing::doc::Example;
+    /// let doc: Vec<String> = vec![
+    ///     "Adds one to its input.",
+    ///     "",
+    ///     "```",
+    ///     "  inc(1) = 2",
+    ///     "",
+    ///     "  inc(-1)",
+    ///     "      = 0",
+    ///     "```",
+    ///     "```text",
+    ///     "not an example",
+    ///     "```",
+    /// ]
+    /// .into_iter()
+    /// .map(String::from)
+    /// .collect();
+    /// let examples = Example::extract(&doc).unwrap();
+    /// assert_eq!(examples.len(), 1);
+    /// assert_eq!(examples[0].start, 3);
+    /// assert_eq!(examples[0].end, 6);
+    /// assert_eq!(examples[0].assertions, vec!["inc(1) = 2", "inc(-1)\n    = 0"]);
+    ///
+    /// let doc = vec!["```tla".to_string(), "TRUE".to_string()];
+    /// assert!(Example::extract(&doc).is_err());
+    /// ```
+    pub fn extract(doc: &[String]) -> Res<Vec<Self>> {
+        let mut res = vec![];
+        let mut lines = doc.iter().enumerate();
+
+        while let Some((open, line)) = lines.next() {
+            let info = match line.trim().strip_prefix("```") {
+                Some(info) => info.trim(),
+                None => continue,
+            };
+            let mut content = vec![];
+            let close = loop {
+                match lines.next() {
+                    Some((close, line)) if line.trim() == "```" => break close,
+                    Some((_, line)) => content.push(line.as_str()),
+                    None => bail!("unclosed block opened at documentation line {}", open + 1),
+                }
+            };
+            if !info.is_empty() && info != "tla" {
+                continue;
+            }
+            let assertions = Self::assertions(&content);
+            if !assertions.is_empty() {
+                res.push(Self {
+                    start: open + 1,
+                    end: close - 1,
+                    assertions,
+                })
+            }
+        }
+
+        Ok(res)
+    }
+
+    /// Splits the content of a block into assertions.
+    fn assertions(content: &[&str]) -> Vec<String> {
+        let indent = content
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+
+        let mut res: Vec<String> = vec![];
+        for line in content {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let line = line.get(indent..).unwrap_or_else(|| line.trim_start());
+            if line.starts_with(char::is_whitespace) {
+                if let Some(last) = res.last_mut() {
+                    last.push('\n');
+                    last.push_str(line.trim_end());
+                    continue;
+                }
+            }
+            res.push(line.trim().to_string())
+        }
+        res
+    }
+}
+
+/// Loads the doc tests of a project.
+///
+/// Generates the test modules and builds the test projects. Tests which name is not matched by
+/// `filter`, if any, are ignored.
+pub fn load(
+    src_project: &project::SourceProject,
+    release: bool,
+    filter: Option<&Filter>,
+) -> Res<Vec<DocTest>> {
+    let sources_dir = conf::Target::new_test(src_project.path()?, release, SOURCES_DIR).build_path;
+    let mut tests = vec![];
+
+    for file in src_project.files.iter() {
+        if !file.is_tla() || file.is_matla_module() {
+            continue;
+        }
+        let doc = project::doc::ModuleDoc::load(file.path())?;
+        let constants = wrapper::constants(src_project, &doc.module)?;
+
+        for op in doc.operators.iter() {
+            let examples = Example::extract(&op.doc).with_context(|| {
+                anyhow!(
+                    "failed to extract examples for `{}` in `{}`",
+                    op.name,
+                    file.path().display(),
+                )
+            })?;
+
+            for (cnt, example) in examples.into_iter().enumerate() {
+                let name = format!("doc_test__{}__{}__{}", doc.module, op.name, cnt + 1);
+                if let Some(filter) = filter {
+                    if !filter.contains(&name) {
+                        continue;
+                    }
+                }
+                let entity = format!("{}::{}", doc.module, op.name);
+                // `op.doc_row` is 1-indexed, line spans are 0-indexed.
+                let source = source::LineSpan::new(
+                    file.path().clone(),
+                    (op.doc_row - 1 + example.start, op.doc_row - 1 + example.end),
+                )?;
+                let kind = Kind::new(name, entity, source);
+
+                let comment = format!(
+                    "Doc test for `{}` generated by matla from `{}`, lines {} to {}.",
+                    kind.entity,
+                    kind.source.path.display(),
+                    kind.source.start + 1,
+                    kind.source.end + 1,
+                );
+                let files = wrapper::generate(
+                    &sources_dir,
+                    &kind.name,
+                    &comment,
+                    &doc.module,
+                    &constants,
+                    &example.assertions,
+                )
+                .with_context(|| anyhow!("failed to generate doc test `{}`", kind.name))?;
+                let project = wrapper::build(src_project, release, &kind.name, files)?;
+
+                tests.push(DocTest::new(kind, project, RunOutcome::Success))
+            }
         }
     }
+
+    Ok(tests)
 }
 
-// impl DocTest {}
+impl wrapper::Kind for Kind {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn failure_header(&self) -> Res<Vec<String>> {
+        Ok(vec![format!(
+            "doc test `{}` for `{}` ({}, lines {} to {})",
+            self.name,
+            self.entity,
+            self.source.path.display(),
+            self.source.start + 1,
+            self.source.end + 1,
+        )])
+    }
+}
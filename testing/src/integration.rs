@@ -123,6 +123,29 @@ impl TestConf {
 #[derive(Debug, Clone)]
 pub struct LibConf;
 
+/// Result of running an integration test, along with the information needed for reports.
+#[derive(Debug, Clone)]
+pub struct TestRun {
+    /// Test result, error lines on failure.
+    pub res: TestRes,
+    /// Expected TLC exit status.
+    pub expected: code::Exit,
+    /// Actual TLC exit status, `None` if TLC's exit code is unknown.
+    pub status: Option<code::Exit>,
+    /// Actual TLC exit code.
+    pub code: i32,
+    /// Duration of the TLC run.
+    pub duration: time::Duration,
+    /// TLC output, only collected on failure.
+    pub tlc_lines: Vec<String>,
+}
+impl TestRun {
+    /// True if the test passed.
+    pub fn is_ok(&self) -> bool {
+        self.res.is_ok()
+    }
+}
+
 /// An integration test.
 #[derive(Debug, Clone)]
 pub struct Test {
@@ -228,7 +251,7 @@ impl Test {
         mut proj: project::SourceProject,
         release: bool,
         libs: &[TestLib],
-    ) -> Res<Result<(), Vec<String>>> {
+    ) -> Res<TestRun> {
         // Add tla and cfg files for this tests.
         let tla_idx = proj.add_file(&self.tla_path)?;
         let _cfg_idx = proj.add_file(&self.cfg_path)?;
@@ -242,23 +265,30 @@ impl Test {
         let test_dir = format!("integration_test_{}", self.module_path_as_ident());
         let target = conf::Target::new_test(proj.path()?, release, &test_dir);
         let entry = proj[tla_idx].module().to_string();
-        let tlc_cla = conf::TlcCla::default()
-            .seed(0)
-            .workers(Some(1))
-            .terse(true)
-            .diff_cexs(true)
-            .into_customl(conf::customl::Source::Custom("internal test configuration"));
+        let tlc_cla = crate::tlc_cla();
         let (project, _) = proj.into_full(Some(entry), target, Some(&tlc_cla))?;
 
+        let start = time::Instant::now();
         let mut tlc_out = TlcOutputHandler::new();
         let tlc = project.run_tlc_async(&mut tlc_out)?;
 
         let outcome = tlc.run()?;
+        let duration = start.elapsed();
         let ProcessOutcome { code, status } = outcome.process;
-        let expected = self.conf.expected().to_exit_code().code();
+        let expected_exit = self.conf.expected().to_exit_code();
+        let expected = expected_exit.code();
+
+        let mut run = TestRun {
+            res: Ok(()),
+            expected: expected_exit,
+            status: status.clone(),
+            code,
+            duration,
+            tlc_lines: vec![],
+        };
 
         if status.as_ref().map(|c| c.code()) == Some(expected) {
-            return Ok(Ok(()));
+            return Ok(run);
         }
 
         let mut error = vec![format!(
@@ -280,10 +310,13 @@ impl Test {
         });
         error.push("".into());
         error.push("|===| TLC output:".into());
-        error.extend(tlc_out.lines().into_iter().map(|s| format!("| {}", s)));
+        let tlc_lines = tlc_out.lines();
+        error.extend(tlc_lines.iter().map(|s| format!("| {}", s)));
         error.push("|===|".into());
 
-        Ok(Err(error))
+        run.res = Err(error);
+        run.tlc_lines = tlc_lines;
+        Ok(run)
     }
 }
 
@@ -476,6 +509,10 @@ impl<'a> Cxt<'a> {
                     }
                     return Ok(());
                 }
+                Some(ext) if ext == project::cex_store::FILE_EXT => {
+                    // Cex regression test, see `crate::regression`.
+                    return Ok(());
+                }
                 Some(_) | None => {
                     // What is this doing here?
                     log::warn!(
@@ -637,7 +674,7 @@ impl<'a> Cxt<'a> {
     pub fn run<'me, T, Action>(&'me self, parallel: bool, action: Action) -> Res<Vec<T>>
     where
         T: Send + 'me,
-        Action: Fn(Res<TestRes>, &'me Test) -> T + Sync,
+        Action: Fn(Res<TestRun>, &'me Test) -> T + Sync,
     {
         if !self.pending_cfg.is_empty() {
             bail!("trying to run integration tests before context finalization");
@@ -13,7 +13,10 @@ pub mod prelude {
         tlc::{self, outcome::*},
     };
 
-    pub use crate::{doc, err::*, integration, Filter, TestRes};
+    pub use crate::{
+        doc, err::*, integration, regression, report, unit, wrapper, DocTest, Filter,
+        RegressionTest, TestRes, UnitTest,
+    };
 }
 /// Imports this crate's prelude.
 #[macro_export]
@@ -28,6 +31,10 @@ prelude!();
 pub mod doc;
 pub mod err;
 pub mod integration;
+pub mod regression;
+pub mod report;
+pub mod unit;
+pub mod wrapper;
 
 /// A list of regex-es.
 #[derive(Debug, Clone)]
@@ -67,9 +74,25 @@ impl Filter {
 //     }
 // }
 
+/// TLC configuration used to run tests.
+pub fn tlc_cla() -> conf::customl::TlcCla {
+    conf::TlcCla::default()
+        .seed(0)
+        .workers(Some(1))
+        .terse(true)
+        .diff_cexs(true)
+        .into_customl(conf::customl::Source::Custom("internal test configuration"))
+}
+
 /// A documentation test.
 pub type DocTest = Test<doc::Kind>;
 
+/// A unit test.
+pub type UnitTest = Test<unit::Kind>;
+
+/// A cex regression test.
+pub type RegressionTest = Test<regression::Kind>;
+
 /// Test result.
 pub type TestRes = Result<(), Vec<String>>;
 
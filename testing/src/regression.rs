@@ -0,0 +1,253 @@
+//! Defines and handles cex regression tests.
+//!
+//! A regression test is a cex file, as saved by `matla run` (see [`project::cex_store`]), anywhere
+//! in the project's `tests` directory. `matla cex regress` puts them there. The `expect` entry of
+//! the header of the file specifies what the test checks:
+//!
+//! - `fixed` (default): the trace is **not** a behavior of the spec anymore;
+//! - `violated`: the trace is still a behavior of the spec, and it still falsifies the property it
+//!   falsified originally.
+//!
+//! ```text
+//! \* matla counterexample
+//! \* module: sw
+//! \* falsified: cnt_leq_10
+//! \* date: 2022-05-04T14:02:31+00:00
+//! \* shape: finite
+//! \* expect: fixed
+//! ...
+//! ```
+//!
+//! Each regression test yields a trace module `cex_test__<name>`, with `<name>` the stem of the
+//! cex file, which `EXTENDS` the module the cex comes from. It restricts the module's `INIT` and
+//! `NEXT` to follow the states of the trace, see [`project::trace_module`]. The `INIT` and `NEXT`
+//! operators come from the module's `cfg` file, unless the header specifies them with `init` and
+//! `next` entries, which is mandatory for modules using `SPECIFICATION`. Fairness constraints are
+//! ignored. The `CONSTANTS` of the module's `cfg` file are copied to the `cfg` file of the trace
+//! module.
+//!
+//! Trace modules are generated in `target/<debug|release>/tests/cex_sources`, and each test is
+//! built in its own directory next to it.
+
+prelude!();
+
+use project::{
+    cex_store::SavedCex,
+    tlc::code,
+    trace_module::{self, CfgSpec, TraceModule},
+};
+
+/// Name of the directory in the test target where trace modules are generated.
+pub const SOURCES_DIR: &str = "cex_sources";
+
+/// Header key of the expectation of a regression test.
+pub const EXPECT_KEY: &str = "expect";
+/// Header key of the init predicate of a regression test.
+pub const INIT_KEY: &str = "init";
+/// Header key of the next-state relation of a regression test.
+pub const NEXT_KEY: &str = "next";
+
+/// What a regression test expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expect {
+    /// The trace is not a behavior of the spec anymore.
+    Fixed,
+    /// The trace still falsifies the property it falsified originally.
+    Violated,
+}
+impl Expect {
+    /// Value description for expectations.
+    pub const VALUES: &'static str = "fixed|violated";
+
+    /// Parses an expectation.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim() {
+            "fixed" => Ok(Self::Fixed),
+            "violated" => Ok(Self::Violated),
+            s => Err(format!("expected {}, got `{}`", Self::VALUES, s)),
+        }
+    }
+}
+implem! {
+    for Expect {
+        Display {
+            |&self, fmt| match self {
+                Self::Fixed => write!(fmt, "fixed"),
+                Self::Violated => write!(fmt, "violated"),
+            }
+        }
+    }
+}
+
+/// Test kind for cex regression tests.
+#[readonly]
+pub struct Kind {
+    /// Path to the cex file.
+    pub path: io::PathBuf,
+    /// Module the cex comes from.
+    pub module: String,
+    /// What the test expects.
+    pub expect: Expect,
+    /// True if the cex falsifies a temporal property.
+    pub is_temporal: bool,
+    /// Name of the generated trace module.
+    pub name: String,
+}
+
+/// Loads the cex regression tests of a project.
+///
+/// Generates the trace modules and builds the test projects. Tests which trace module name is not
+/// matched by `filter`, if any, are ignored.
+pub fn load(
+    src_project: &project::SourceProject,
+    release: bool,
+    filter: Option<&Filter>,
+) -> Res<Vec<RegressionTest>> {
+    let project_path = src_project.path()?;
+    let tests_dir = project_path.join("tests");
+    let mut tests = vec![];
+    if !tests_dir.is_dir() {
+        return Ok(tests);
+    }
+    let sources_dir = conf::Target::new_test(&project_path, release, SOURCES_DIR).build_path;
+
+    let mut paths = vec![];
+    integration::Cxt::dir_files_do(&tests_dir, true, |path| {
+        match io::file_ext(path) {
+            Some(ext) if ext == project::cex_store::FILE_EXT => paths.push(path.to_path_buf()),
+            Some(_) | None => (),
+        }
+        Ok(())
+    })?;
+    paths.sort();
+
+    for path in paths {
+        let test = build_test(src_project, release, filter, &sources_dir, &path)
+            .with_context(|| anyhow!("failed to load cex regression test `{}`", path.display()))?;
+        tests.extend(test);
+    }
+
+    Ok(tests)
+}
+
+/// Loads a regression test, `None` if `filter` ignores it.
+fn build_test(
+    src_project: &project::SourceProject,
+    release: bool,
+    filter: Option<&Filter>,
+    sources_dir: &io::Path,
+    path: &io::Path,
+) -> Res<Option<RegressionTest>> {
+    let saved = SavedCex::load(path)?;
+    let name = format!("cex_test__{}", saved.id.replace('-', "_"));
+    if let Some(filter) = filter {
+        if !filter.contains(&name) {
+            return Ok(None);
+        }
+    }
+    let expect = match saved.extra.get(EXPECT_KEY) {
+        Some(expect) => Expect::parse(expect).map_err(Error::msg)?,
+        None => Expect::Fixed,
+    };
+
+    let spec = CfgSpec::load(src_project, &saved.module)?;
+    let operator = |key: &str, from_cfg: Option<&String>| {
+        saved.extra.get(key).or(from_cfg).cloned().ok_or_else(|| {
+            let why = if spec.has_spec {
+                "its `cfg` file uses `SPECIFICATION`"
+            } else {
+                "its `cfg` file has none"
+            };
+            anyhow!(
+                "cannot retrieve the {} operator of module `{}`, {}: please add \
+                    `\\* {}: <operator>` to the header of the cex file",
+                key.to_uppercase(),
+                saved.module,
+                why,
+                key,
+            )
+        })
+    };
+    let init = operator(INIT_KEY, spec.init.as_ref())?;
+    let next = operator(NEXT_KEY, spec.next.as_ref())?;
+
+    let comment = format!(
+        "Cex regression test generated by matla from `{}`, expecting `{}`.",
+        path.display(),
+        expect,
+    );
+    let module = TraceModule::new(&name, &saved.module, &saved.cex, &spec)
+        .comment(comment)
+        .behavior(init, next);
+    // With `Expect::Fixed`, check the invariant that the trace is never completed; with
+    // `Expect::Violated`, check the property falsified by the cex.
+    let module = match expect {
+        Expect::Fixed => {
+            let incomplete = format!("{}_incomplete", trace_module::PREF);
+            let complete = module.complete_idx();
+            module
+                .def(format!(
+                    "{} == {}_idx # {}",
+                    incomplete,
+                    trace_module::PREF,
+                    complete
+                ))
+                .check(format!("INVARIANT {}", incomplete))
+        }
+        Expect::Violated => {
+            let (falsified, is_temporal) = saved.cex.falsified();
+            let falsified = falsified
+                .ok_or_else(|| anyhow!("cannot check violation, cex has no falsified property"))?;
+            if is_temporal {
+                module.check(format!("PROPERTY {}", falsified))
+            } else {
+                module.check(format!("INVARIANT {}", falsified))
+            }
+        }
+    };
+    let files = module.write(sources_dir)?;
+    let project = wrapper::build(src_project, release, &name, files)?;
+
+    let expected = match expect {
+        Expect::Fixed => RunOutcome::Success,
+        Expect::Violated => RunOutcome::Failure(FailedOutcome::Unsafe),
+    };
+    let kind = Kind {
+        path: path.to_path_buf(),
+        module: saved.module,
+        expect,
+        is_temporal: saved.cex.falsified().1,
+        name,
+    };
+    Ok(Some(RegressionTest::new(kind, project, expected)))
+}
+
+impl wrapper::Kind for Kind {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn failure_header(&self) -> Res<Vec<String>> {
+        let what = match self.expect {
+            Expect::Fixed => "the trace is still a behavior of the spec",
+            Expect::Violated => "the trace does not falsify its property anymore",
+        };
+        Ok(vec![
+            format!(
+                "cex regression test `{}` on module `{}` ({})",
+                self.name,
+                self.module,
+                self.path.display(),
+            ),
+            what.into(),
+        ])
+    }
+    fn expected_exit(&self) -> code::Exit {
+        match self.expect {
+            Expect::Fixed => code::Exit::Success,
+            Expect::Violated if self.is_temporal => {
+                code::Exit::Violation(code::ExitViolation::ViolationLiveness)
+            }
+            Expect::Violated => code::Exit::Violation(code::ExitViolation::ViolationSafety),
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! Test reports for external tools.
+//!
+//! Currently only supports [JUnit XML][junit] reports, which most CI dashboards can read.
+//!
+//! [junit]: https://github.com/testmoapp/junitxml (JUnit XML format)
+
+prelude!();
+
+use integration::TestRun;
+
+/// Report format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// JUnit XML.
+    Junit,
+}
+implem! {
+    for Format {
+        Display {
+            |&self, fmt| match self {
+                Self::Junit => "junit".fmt(fmt),
+            }
+        }
+    }
+}
+
+/// A report request: a format and the file to write the report to.
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// Report format.
+    pub format: Format,
+    /// Report file.
+    pub path: io::PathBuf,
+}
+impl Report {
+    /// Value description for report arguments.
+    pub const VALUES: &'static str = "junit=FILE";
+
+    /// Parses a report request of the form `<format>=<file>`.
+    ///
+    /// ```rust
+    /// # use testing::report::*;
+    /// let report = Report::parse("junit=target/report.xml").unwrap();
+    /// assert_eq!(report.format, Format::Junit);
+    /// assert_eq!(report.path.display().to_string(), "target/report.xml");
+    ///
+    /// assert!(Report::parse("junit").is_err());
+    /// assert!(Report::parse("junit=").is_err());
+    /// assert!(Report::parse("html=report.html").is_err());
+    /// ```
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (format, path) = match s.split_once('=') {
+            Some((format, path)) if !path.is_empty() => (format, path),
+            _ => return Err(format!("expected {}", Self::VALUES)),
+        };
+        let format = match format {
+            "junit" => Format::Junit,
+            _ => {
+                return Err(format!(
+                    "unknown report format `{}`, expected `junit`",
+                    format
+                ))
+            }
+        };
+        Ok(Self {
+            format,
+            path: path.into(),
+        })
+    }
+
+    /// Writes a report for some test suites, *e.g.* `("integration", cases)`.
+    pub fn write(&self, suites: &[(&str, Vec<Case>)]) -> Res<()> {
+        let mut file = io::write_file(&self.path, true, false)?;
+        let res = match self.format {
+            Format::Junit => junit(&mut file, suites),
+        };
+        res.with_context(|| anyhow!("failed to write report file `{}`", self.path.display()))
+    }
+}
+
+/// Outcome of a test case.
+#[derive(Debug, Clone)]
+pub enum CaseOutcome<'a> {
+    /// Test passed.
+    Success,
+    /// Test ran but did not produce the expected outcome.
+    Failure {
+        /// Expected and actual TLC exit status.
+        message: String,
+        /// TLC's output.
+        output: &'a [String],
+    },
+    /// Test could not run.
+    Error {
+        /// Error, with its context.
+        message: String,
+    },
+}
+
+/// A test case in a report.
+#[derive(Debug, Clone)]
+pub struct Case<'a> {
+    /// Test name.
+    pub name: &'a str,
+    /// Test duration.
+    pub duration: time::Duration,
+    /// Test outcome.
+    pub outcome: CaseOutcome<'a>,
+}
+impl<'a> Case<'a> {
+    /// Constructor from a test run.
+    pub fn new(name: &'a str, run: &'a Res<TestRun>) -> Self {
+        let (duration, outcome) = match run {
+            Ok(run) => {
+                let outcome = match &run.res {
+                    Ok(()) => CaseOutcome::Success,
+                    Err(_) => CaseOutcome::Failure {
+                        message: format!(
+                            "expected `{}` {}, got {}",
+                            run.expected,
+                            run.expected.code(),
+                            match run.status.as_ref() {
+                                Some(status) => format!("`{}` {}", status, status.code()),
+                                None => format!("unknown exit code {}", run.code),
+                            },
+                        ),
+                        output: &run.tlc_lines,
+                    },
+                };
+                (run.duration, outcome)
+            }
+            Err(e) => (
+                time::Duration::ZERO,
+                CaseOutcome::Error {
+                    message: format!("{:?}", e),
+                },
+            ),
+        };
+        Self {
+            name,
+            duration,
+            outcome,
+        }
+    }
+}
+
+/// Escapes a string for XML attributes and text, see [`html_escape`].
+///
+/// Also drops the control characters other than whitespace, which are illegal in XML 1.0.
+///
+/// ```rust
+/// # use testing::report::xml_escape;
+/// assert_eq!(
+///     xml_escape("a < b && \"c\" > 'd'"),
+///     "a &lt; b &amp;&amp; &quot;c&quot; &gt; &#39;d&#39;",
+/// );
+/// assert_eq!(xml_escape("bell\u{7}"), "bell");
+/// ```
+pub fn xml_escape(s: &str) -> String {
+    let legal = |c: &char| !c.is_control() || matches!(c, '\n' | '\t' | '\r');
+    html_escape(s.chars().filter(legal).collect::<String>())
+}
+
+/// Counts the cases, failures and errors of some test cases, and sums their duration.
+fn stats(cases: &[Case]) -> (usize, usize, usize, f64) {
+    let (mut failures, mut errors) = (0, 0);
+    for case in cases {
+        match case.outcome {
+            CaseOutcome::Success => (),
+            CaseOutcome::Failure { .. } => failures += 1,
+            CaseOutcome::Error { .. } => errors += 1,
+        }
+    }
+    let time: f64 = cases.iter().map(|case| case.duration.as_secs_f64()).sum();
+    (cases.len(), failures, errors, time)
+}
+
+/// Writes a JUnit XML report for some test suites.
+///
+/// Empty test suites are omitted.
+pub fn junit<S: AsRef<str>>(mut w: impl io::Write, suites: &[(S, Vec<Case>)]) -> Res<()> {
+    let suites: Vec<_> = suites
+        .iter()
+        .filter(|(_, cases)| !cases.is_empty())
+        .map(|(suite, cases)| (xml_escape(suite.as_ref()), cases, stats(cases)))
+        .collect();
+    let (mut tests, mut failures, mut errors, mut time) = (0, 0, 0, 0.0);
+    for (_, _, (t, f, e, d)) in suites.iter() {
+        tests += t;
+        failures += f;
+        errors += e;
+        time += d;
+    }
+
+    writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        w,
+        r#"<testsuites name="matla" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+        tests, failures, errors, time,
+    )?;
+    for (suite, cases, (tests, failures, errors, time)) in suites {
+        junit_suite(&mut w, &suite, cases, tests, failures, errors, time)?;
+    }
+    writeln!(w, "</testsuites>")?;
+    Ok(())
+}
+
+/// Writes a single JUnit XML test suite, `suite` must be escaped already.
+fn junit_suite(
+    mut w: impl io::Write,
+    suite: &str,
+    cases: &[Case],
+    tests: usize,
+    failures: usize,
+    errors: usize,
+    time: f64,
+) -> Res<()> {
+    writeln!(
+        w,
+        r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+        suite, tests, failures, errors, time,
+    )?;
+    for case in cases {
+        write!(
+            w,
+            r#"    <testcase name="{}" classname="{}" time="{:.3}""#,
+            xml_escape(case.name),
+            suite,
+            case.duration.as_secs_f64(),
+        )?;
+        match &case.outcome {
+            CaseOutcome::Success => writeln!(w, "/>")?,
+            CaseOutcome::Failure { message, output } => {
+                writeln!(w, ">")?;
+                writeln!(
+                    w,
+                    r#"      <failure message="{0}" type="unexpected outcome">{0}</failure>"#,
+                    xml_escape(message),
+                )?;
+                writeln!(w, "      <system-out>")?;
+                for line in output.iter() {
+                    writeln!(w, "{}", xml_escape(line))?;
+                }
+                writeln!(w, "      </system-out>")?;
+                writeln!(w, "    </testcase>")?;
+            }
+            CaseOutcome::Error { message } => {
+                writeln!(w, ">")?;
+                writeln!(
+                    w,
+                    r#"      <error message="unexpected error" type="error">"#
+                )?;
+                writeln!(w, "{}", xml_escape(message))?;
+                writeln!(w, "      </error>")?;
+                writeln!(w, "    </testcase>")?;
+            }
+        }
+    }
+    writeln!(w, "  </testsuite>")?;
+    Ok(())
+}
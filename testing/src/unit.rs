@@ -0,0 +1,152 @@
+//! Defines and handles unit tests.
+//!
+//! A unit test is a nullary operator of a source module with a [`MARKER`] line in its header
+//! comment. It is a predicate expected to hold.
+//!
+//! ```text
+//! \* [unit_test]
+//! inc_test ==
+//!     /\ inc(1) = 2
+//!     /\ inc(-1) = 0
+//! ```
+//!
+//! Each unit test yields a module `unit_test__<module>__<operator>` which `EXTENDS` the module
+//! and `ASSUME`s the operator, with a `cfg` file with no behavior spec. These files are generated in
+//! `target/<debug|release>/tests/unit_sources`, and each unit test is built in its own directory
+//! next to it.
+
+prelude!();
+
+/// Marker of unit tests in the header comment of an operator.
+pub const MARKER: &str = "[unit_test]";
+
+/// Name of the directory in the test target where unit test wrapper modules are generated.
+pub const SOURCES_DIR: &str = "unit_sources";
+
+/// Test kind for unit tests.
+#[readonly]
+pub struct Kind {
+    /// Position of the operator's name in its definition.
+    pub pos: source::FilePos,
+    /// Module the operator is defined in.
+    pub module: String,
+    /// Operator name.
+    pub operator: String,
+    /// Name of the generated wrapper module.
+    pub name: String,
+}
+impl Kind {
+    /// Constructor.
+    pub fn new(
+        pos: source::FilePos,
+        module: impl Into<String>,
+        operator: impl Into<String>,
+    ) -> Self {
+        let (module, operator) = (module.into(), operator.into());
+        Self {
+            pos,
+            name: format!("unit_test__{}__{}", module, operator),
+            module,
+            operator,
+        }
+    }
+}
+
+/// True if some documentation marks a unit test.
+///
+/// ```rust
+/// # use testing::unit::is_marked;
+/// let doc: Vec<String> = vec!["Checks `inc`.".into(), " [unit_test]".into()];
+/// assert!(is_marked(&doc));
+/// let doc: Vec<String> = vec!["Not a [unit_test].".into()];
+/// assert!(!is_marked(&doc));
+/// ```
+pub fn is_marked(doc: &[String]) -> bool {
+    doc.iter().any(|line| line.trim() == MARKER)
+}
+
+/// Loads the unit tests of a project.
+///
+/// Generates the wrapper modules and builds the test projects. Tests which wrapper module name is
+/// not matched by `filter`, if any, are ignored.
+///
+/// Fails if a unit test operator takes parameters.
+pub fn load(
+    src_project: &project::SourceProject,
+    release: bool,
+    filter: Option<&Filter>,
+) -> Res<Vec<UnitTest>> {
+    let sources_dir = conf::Target::new_test(src_project.path()?, release, SOURCES_DIR).build_path;
+    let mut tests = vec![];
+
+    for file in src_project.files.iter() {
+        if !file.is_tla() || file.is_matla_module() {
+            continue;
+        }
+        let doc = project::doc::ModuleDoc::load(file.path())?;
+
+        for op in doc.operators.iter() {
+            if !is_marked(&op.doc) {
+                continue;
+            }
+            let pos = source::FilePos::new(file.path().display().to_string(), (op.row, op.col));
+            if op.signature != op.name {
+                let pretty = pos
+                    .pretty(
+                        |file, buf| io::load_file_to(file, buf),
+                        Some("unit tests cannot take parameters"),
+                    )?
+                    .join("\n");
+                bail!(anyhow!("{}", pretty).context(anyhow!(
+                    "illegal unit test `{}` in `{}`",
+                    op.signature,
+                    file.path().display(),
+                )))
+            }
+
+            let kind = Kind::new(pos, &doc.module, &op.name);
+            if let Some(filter) = filter {
+                if !filter.contains(&kind.name) {
+                    continue;
+                }
+            }
+
+            let comment = format!(
+                "Unit test for `{}::{}` generated by matla from {}.",
+                kind.module, kind.operator, kind.pos,
+            );
+            let constants = wrapper::constants(src_project, &kind.module)?;
+            let files = wrapper::generate(
+                &sources_dir,
+                &kind.name,
+                &comment,
+                &kind.module,
+                &constants,
+                &[kind.operator.clone()],
+            )
+            .with_context(|| anyhow!("failed to generate unit test `{}`", kind.name))?;
+            let project = wrapper::build(src_project, release, &kind.name, files)?;
+
+            tests.push(UnitTest::new(kind, project, RunOutcome::Success))
+        }
+    }
+
+    Ok(tests)
+}
+
+impl wrapper::Kind for Kind {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn failure_header(&self) -> Res<Vec<String>> {
+        let mut res = vec![format!(
+            "unit test `{}::{}` ({})",
+            self.module, self.operator, self.pos,
+        )];
+        res.extend(self.pos.pretty(
+            |file, buf| io::load_file_to(file, buf),
+            Some("this unit test does not hold"),
+        )?);
+        Ok(res)
+    }
+}
@@ -0,0 +1,219 @@
+//! Tests running a generated wrapper module, *i.e.* doc tests, unit tests and cex regression tests.
+//!
+//! A wrapper module `EXTENDS` a module from the project's sources and `ASSUME`s some assertions. It
+//! comes with a `cfg` file with no behavior spec, so that TLC only checks the assumptions. This
+//! `cfg` file keeps the `CONSTANTS` of the `cfg` file of the extended module, if any, see
+//! [`constants`]. The test passes if TLC succeeds.
+//!
+//! Cex [`regression`](crate::regression) tests generate a trace module instead, and may expect TLC
+//! to find a violation, see [`Kind::expected_exit`].
+
+prelude!();
+
+use integration::{TestRun, TlcOutputHandler};
+use project::{tlc::code, trace_module::CfgSpec};
+
+use crate::Test;
+
+/// Test kinds running a wrapper module.
+pub trait Kind {
+    /// Name of the wrapper module.
+    fn name(&self) -> &str;
+    /// First lines of the error report of a failed test, describing the test.
+    fn failure_header(&self) -> Res<Vec<String>>;
+    /// TLC exit status expected, success by default.
+    fn expected_exit(&self) -> code::Exit {
+        code::Exit::Success
+    }
+}
+
+/// Constant assignments of the `cfg` file of a module, none if the module has no `cfg` file.
+///
+/// Yields the content of the `CONSTANTS` sections, see [`CfgSpec::constants`].
+pub fn constants(src_project: &project::SourceProject, module: &str) -> Res<Vec<String>> {
+    let spec = CfgSpec::try_load(src_project, module)
+        .with_context(|| anyhow!("failed to load the cfg file of module `{}`", module))?;
+    Ok(spec.map(|spec| spec.constants).unwrap_or_default())
+}
+
+/// Generates a wrapper module and its `cfg` file in a directory.
+///
+/// - `name` is the name of the wrapper module;
+/// - `comment` is written as a comment right after the module header;
+/// - `module` is the module to extend;
+/// - `constants` are the constant assignments of the `cfg` file, see [`constants`];
+/// - each assertion yields an `ASSUME`, multi-line assertions are indented.
+///
+/// Returns the path to the TLA file and the path to the `cfg` file.
+///
+/// # Examples
+///
+/// ```rust
+/// # use testing::wrapper::generate;
+/// let dir = std::env::temp_dir().join("matla_wrapper_doctest");
+/// let constants = vec!["N = 3 Procs = {p1, p2}".to_string()];
+/// let assertions = vec!["Cardinality(Procs) < N".to_string()];
+/// let (tla, cfg) = generate(&dir, "unit_test__sw", "comment", "sw", &constants, &assertions)
+///     .unwrap();
+/// assert_eq!(
+///     std::fs::read_to_string(tla).unwrap(),
+///     "\
+/// ---- MODULE unit_test__sw ----
+/// \\* comment
+///
+/// EXTENDS sw
+///
+/// ASSUME
+///     Cardinality(Procs) < N
+///
+/// ====
+/// ",
+/// );
+/// assert_eq!(
+///     std::fs::read_to_string(cfg).unwrap(),
+///     "\
+/// \\* Test `unit_test__sw`: no behavior spec, only checks assumptions.
+/// CONSTANTS N = 3 Procs = {p1, p2}
+/// ",
+/// );
+/// ```
+pub fn generate(
+    dir: &io::Path,
+    name: &str,
+    comment: &str,
+    module: &str,
+    constants: &[String],
+    assertions: &[String],
+) -> Res<(io::PathBuf, io::PathBuf)> {
+    use io::Write;
+    io::create_dir_all(dir)
+        .with_context(|| anyhow!("failed to create directory `{}`", dir.display()))?;
+
+    let mut tla_path = dir.to_path_buf();
+    tla_path.push(format!("{}.tla", name));
+    let mut cfg_path = tla_path.clone();
+    cfg_path.set_extension("cfg");
+
+    {
+        let mut tla = io::write_file(&tla_path, true, false)?;
+        writeln!(tla, "---- MODULE {} ----", name)?;
+        writeln!(tla, "\\* {}", comment)?;
+        writeln!(tla)?;
+        writeln!(tla, "EXTENDS {}", module)?;
+        for assertion in assertions {
+            writeln!(tla)?;
+            writeln!(tla, "ASSUME")?;
+            for line in assertion.lines() {
+                writeln!(tla, "    {}", line)?;
+            }
+        }
+        writeln!(tla)?;
+        writeln!(tla, "====")?;
+    }
+    {
+        let mut cfg = io::write_file(&cfg_path, true, false)?;
+        writeln!(
+            cfg,
+            "\\* Test `{}`: no behavior spec, only checks assumptions.",
+            name
+        )?;
+        for constants in constants {
+            writeln!(cfg, "CONSTANTS {}", constants)?;
+        }
+    }
+
+    Ok((tla_path, cfg_path))
+}
+
+/// Builds the project of a wrapper module generated by [`generate`].
+///
+/// The project is built in `target/<debug|release>/tests/<name>`.
+pub fn build(
+    src_project: &project::SourceProject,
+    release: bool,
+    name: &str,
+    (tla_path, cfg_path): (io::PathBuf, io::PathBuf),
+) -> Res<project::FullProject> {
+    let mut proj = src_project.clone();
+    let _tla_idx = proj.add_file(tla_path)?;
+    let _cfg_idx = proj.add_file(cfg_path)?;
+    let target = conf::Target::new_test(proj.path()?, release, name);
+    let (project, _) = proj.into_full(Some(name.into()), target, Some(&crate::tlc_cla()))?;
+    Ok(project)
+}
+
+impl<K: Kind> Test<K> {
+    /// Name of the wrapper module.
+    pub fn name(&self) -> &str {
+        self.kind.name()
+    }
+
+    /// Runs the test.
+    pub fn run(&self) -> Res<TestRun> {
+        let expected = self.kind.expected_exit();
+        if self.expected.is_success() != (expected.code() == code::Exit::Success.code()) {
+            bail!(
+                "test `{}` expects outcome `{:?}` but exit code `{}`",
+                self.name(),
+                self.expected,
+                expected.code(),
+            );
+        }
+
+        let start = time::Instant::now();
+        let mut tlc_out = TlcOutputHandler::new();
+        let tlc = self.project.run_tlc_async(&mut tlc_out)?;
+
+        let outcome = tlc.run()?;
+        let duration = start.elapsed();
+        let ProcessOutcome { code, status } = outcome.process;
+
+        let mut run = TestRun {
+            res: Ok(()),
+            expected: expected.clone(),
+            status: status.clone(),
+            code,
+            duration,
+            tlc_lines: vec![],
+        };
+
+        if status.as_ref().map(|c| c.code()) == Some(expected.code()) {
+            return Ok(run);
+        }
+
+        let mut error = self.kind.failure_header()?;
+        error.push(format!("expected exit code to be `{}`", expected.code()));
+        error.push(if let Some(exit) = status {
+            format!("but got `{}`", exit)
+        } else {
+            format!("but got unknown exit code {}", code)
+        });
+        error.push("".into());
+        error.push("|===| TLC output:".into());
+        let tlc_lines = tlc_out.lines();
+        error.extend(tlc_lines.iter().map(|s| format!("| {}", s)));
+        error.push("|===|".into());
+
+        run.res = Err(error);
+        run.tlc_lines = tlc_lines;
+        Ok(run)
+    }
+}
+
+/// Runs some tests over wrapper modules.
+pub fn run<'me, K, T, Action>(tests: &'me [Test<K>], parallel: bool, action: Action) -> Vec<T>
+where
+    K: Kind + Sync,
+    T: Send + 'me,
+    Action: Fn(Res<TestRun>, &'me Test<K>) -> T + Sync,
+{
+    if parallel {
+        use rayon::prelude::*;
+        tests
+            .par_iter()
+            .map(|test| action(test.run(), test))
+            .collect()
+    } else {
+        tests.iter().map(|test| action(test.run(), test)).collect()
+    }
+}